@@ -0,0 +1,20 @@
+//! Embeds the git commit as `GIT_COMMIT` for `GET /version`
+
+use std::process::Command;
+
+fn main() {
+	println!("cargo:rerun-if-env-changed=GIT_COMMIT");
+	println!("cargo:rerun-if-changed=../.git/HEAD");
+	println!("cargo:rerun-if-changed=../.git/refs");
+	// builds without a checkout, e.g. in a container, pass the commit in `GIT_COMMIT`
+	let commit = std::env::var("GIT_COMMIT").ok().or_else(|| {
+		Command::new("git")
+			.args(["rev-parse", "--short", "HEAD"])
+			.output()
+			.ok()
+			.filter(|output| output.status.success())
+			.and_then(|output| String::from_utf8(output.stdout).ok())
+	});
+	let commit = commit.as_deref().map_or("unknown", str::trim);
+	println!("cargo:rustc-env=GIT_COMMIT={commit}");
+}
@@ -1,3 +1,5 @@
+mod throttle;
 mod tokenizer;
 
+pub use throttle::LoginThrottle;
 pub use tokenizer::Tokenizer;
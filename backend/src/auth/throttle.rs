@@ -0,0 +1,246 @@
+use std::{
+	collections::HashMap,
+	hash::Hash,
+	net::IpAddr,
+	sync::Mutex,
+	time::{
+		Duration,
+		Instant,
+	},
+};
+
+use tracing::{
+	info,
+	warn,
+};
+
+use crate::{
+	config::LoginConfig,
+	Error,
+	Result,
+};
+
+/// Counts failed logins per account and login attempts per client IP
+///
+/// The counts are kept in memory, like the keys of the [`super::Tokenizer`] they start over with
+/// every restart and are not shared between instances. Accounts are identified by the email of
+/// the login, unknown emails are counted and locked like existing ones so the responses don't
+/// tell which emails exist.
+pub struct LoginThrottle {
+	max_failures: u32,
+	lockout: Duration,
+	max_attempts_per_ip: u32,
+	window: Duration,
+	accounts: Mutex<HashMap<String, Attempts>>,
+	ips: Mutex<HashMap<IpAddr, Attempts>>,
+}
+
+/// Attempts counted since `since`
+struct Attempts {
+	count: u32,
+	since: Instant,
+	locked_until: Option<Instant>,
+}
+
+impl Attempts {
+	fn new(now: Instant) -> Self {
+		Self {
+			count: 0,
+			since: now,
+			locked_until: None,
+		}
+	}
+
+	/// Counts another attempt, the count starts over once `window` has passed
+	fn count(&mut self, now: Instant, window: Duration) -> u32 {
+		if now.duration_since(self.since) >= window {
+			self.count = 0;
+			self.since = now;
+		}
+		self.count += 1;
+		self.count
+	}
+
+	fn locked(&self, now: Instant) -> Option<Duration> {
+		self
+			.locked_until
+			.filter(|until| *until > now)
+			.map(|until| until - now)
+	}
+
+	/// Neither counting nor locking anything anymore
+	fn is_stale(&self, now: Instant, window: Duration) -> bool {
+		now.duration_since(self.since) >= window && self.locked(now).is_none()
+	}
+}
+
+impl LoginThrottle {
+	pub fn new(config: &LoginConfig) -> Self {
+		Self {
+			max_failures: config.max_failures,
+			lockout: Duration::from_secs(config.lockout),
+			max_attempts_per_ip: config.max_attempts_per_ip,
+			window: Duration::from_secs(config.window),
+			accounts: Mutex::new(HashMap::new()),
+			ips: Mutex::new(HashMap::new()),
+		}
+	}
+
+	/// Counts a login attempt of `ip`
+	///
+	/// Returns `429 Too Many Requests` while `ip` is over its limit or `email` is locked, the
+	/// password must not be checked then.
+	pub fn attempt(&self, email: &str, ip: Option<IpAddr>) -> Result<()> {
+		let now = Instant::now();
+		if let Some(ip) = ip {
+			let mut ips = lock(&self.ips, now, self.window);
+			let attempts = ips.entry(ip).or_insert_with(|| Attempts::new(now));
+			if attempts.count(now, self.window) > self.max_attempts_per_ip {
+				warn!("Rejecting login from {}, too many attempts", ip);
+				let retry = self.window - now.duration_since(attempts.since);
+				return Err(Error::too_many_requests("login.rate_limited").with_arg(seconds(retry)));
+			}
+		}
+		let accounts = lock(&self.accounts, now, self.window);
+		if let Some(retry) = accounts
+			.get(&account(email))
+			.and_then(|attempts| attempts.locked(now))
+		{
+			info!("Rejecting login of locked account {}", email);
+			return Err(Error::too_many_requests("login.locked").with_arg(seconds(retry)));
+		}
+		Ok(())
+	}
+
+	/// Counts a wrong password for `email`, the account is locked once `max_failures` is reached
+	pub fn failure(&self, email: &str) {
+		let now = Instant::now();
+		let mut accounts = lock(&self.accounts, now, self.window);
+		let attempts = accounts
+			.entry(account(email))
+			.or_insert_with(|| Attempts::new(now));
+		if attempts.count(now, self.window) >= self.max_failures {
+			warn!(
+				"Locking login of {} for {}s after {} failed attempts",
+				email,
+				self.lockout.as_secs(),
+				attempts.count
+			);
+			*attempts = Attempts {
+				locked_until: Some(now + self.lockout),
+				..Attempts::new(now)
+			};
+		}
+	}
+
+	/// Forgets the failed logins of `email`
+	pub fn success(&self, email: &str) {
+		self
+			.accounts
+			.lock()
+			.expect("Login throttle poisoned")
+			.remove(&account(email));
+	}
+
+	/// Lifts the lock of `email` and forgets its failed logins, returns whether it was locked
+	pub fn unlock(&self, email: &str) -> bool {
+		let now = Instant::now();
+		let unlocked = self
+			.accounts
+			.lock()
+			.expect("Login throttle poisoned")
+			.remove(&account(email))
+			.is_some_and(|attempts| attempts.locked(now).is_some());
+		if unlocked {
+			info!("Unlocked login of {}", email);
+		}
+		unlocked
+	}
+}
+
+/// Locks `map` and drops the entries that don't count anymore, so it can't grow without bound
+fn lock<K: Eq + Hash>(
+	map: &Mutex<HashMap<K, Attempts>>,
+	now: Instant,
+	window: Duration,
+) -> std::sync::MutexGuard<'_, HashMap<K, Attempts>> {
+	let mut map = map.lock().expect("Login throttle poisoned");
+	map.retain(|_, attempts| !attempts.is_stale(now, window));
+	map
+}
+
+/// Emails differing in case or surrounding whitespace share their count
+fn account(email: &str) -> String {
+	email.trim().to_lowercase()
+}
+
+/// Whole seconds, rounded up so a client waiting that long isn't rejected again
+fn seconds(duration: Duration) -> u64 {
+	duration.as_secs() + u64::from(duration.subsec_nanos() > 0)
+}
+
+#[cfg(test)]
+mod test {
+	use std::net::{
+		IpAddr,
+		Ipv4Addr,
+	};
+
+	use super::LoginThrottle;
+	use crate::{
+		config::LoginConfig,
+		error::Error,
+	};
+
+	fn code(result: crate::Result<()>) -> &'static str {
+		match result {
+			Ok(()) => "ok",
+			Err(Error::Coded { code, .. }) => code,
+			Err(e) => panic!("Unexpected error {e}"),
+		}
+	}
+
+	#[test]
+	fn lockout() {
+		let throttle = LoginThrottle::new(&LoginConfig {
+			max_failures: 3,
+			..LoginConfig::default()
+		});
+		throttle.failure("admin@example.com");
+		throttle.failure("admin@example.com");
+		assert_eq!(code(throttle.attempt("admin@example.com", None)), "ok");
+		throttle.failure(" Admin@Example.com");
+		assert_eq!(
+			code(throttle.attempt("admin@example.com", None)),
+			"login.locked"
+		);
+		assert_eq!(code(throttle.attempt("user@example.com", None)), "ok");
+
+		assert!(throttle.unlock("ADMIN@example.com"));
+		assert!(!throttle.unlock("admin@example.com"));
+		assert_eq!(code(throttle.attempt("admin@example.com", None)), "ok");
+
+		throttle.failure("user@example.com");
+		throttle.failure("user@example.com");
+		throttle.success("user@example.com");
+		throttle.failure("user@example.com");
+		assert_eq!(code(throttle.attempt("user@example.com", None)), "ok");
+	}
+
+	#[test]
+	fn rate_limit() {
+		let throttle = LoginThrottle::new(&LoginConfig {
+			max_attempts_per_ip: 2,
+			..LoginConfig::default()
+		});
+		let ip = Some(IpAddr::V4(Ipv4Addr::new(192, 0, 2, 1)));
+		let other = Some(IpAddr::V4(Ipv4Addr::new(192, 0, 2, 2)));
+		assert_eq!(code(throttle.attempt("a@example.com", ip)), "ok");
+		assert_eq!(code(throttle.attempt("b@example.com", ip)), "ok");
+		assert_eq!(
+			code(throttle.attempt("c@example.com", ip)),
+			"login.rate_limited"
+		);
+		assert_eq!(code(throttle.attempt("c@example.com", other)), "ok");
+	}
+}
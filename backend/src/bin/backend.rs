@@ -1,6 +1,12 @@
+use zeiterfassung_backend::{
+	config::Config,
+	Error,
+};
+
 #[rocket::main]
-async fn main() -> std::result::Result<(), rocket::Error> {
-	let _guard = zeiterfassung_backend::tracing::init();
+async fn main() -> std::result::Result<(), Box<rocket::Error>> {
+	let config = Config::load().unwrap_or_else(|e| exit(e));
+	let _guard = zeiterfassung_backend::tracing::init(&config.logging).unwrap_or_else(|e| exit(e));
 	let _rocket = zeiterfassung_backend::rocket()
 		.ignite()
 		.await?
@@ -8,3 +14,9 @@ async fn main() -> std::result::Result<(), rocket::Error> {
 		.await?;
 	Ok(())
 }
+
+/// Stops before launch, tracing is not set up yet so the reason goes to stderr
+fn exit(error: Error) -> ! {
+	eprintln!("{error}");
+	std::process::exit(1)
+}
@@ -15,16 +15,18 @@ use tracing::{
 use crate::error::ErrorJson;
 
 #[catch(default)]
-/// always return json
+/// always return json, in the language the client asked for and with the request id
 pub fn default_catcher(status: Status, request: &Request) -> String {
-	let error = ErrorJson::new(status.code, status.reason_lossy());
+	let error = ErrorJson::new(status.code, status.reason_lossy()).for_request(request);
 	let json = rocket::serde::json::to_string(&error).expect("Could not serialize json");
 	let debug_msg = format!(
-		"Default Catcher: status: {} with reason: {} for request_uri: {} and method: {}",
+		"Default Catcher: status: {} with reason: {} for request_uri: {} and method: {}, request id: \
+		 {}",
 		status.code,
 		status.reason_lossy(),
 		request.uri(),
-		request.method()
+		request.method(),
+		error.request_id.as_deref().unwrap_or("none")
 	);
 	match status.class() {
 		StatusClass::Success | StatusClass::Informational => {
@@ -0,0 +1,282 @@
+use std::{
+	path::PathBuf,
+	time::Duration,
+};
+
+use rocket::{
+	fairing::AdHoc,
+	figment::Figment,
+};
+use rocket_cors::{
+	AllowedOrigins,
+	Cors,
+	CorsOptions,
+};
+use serde::Deserialize;
+use tracing::error;
+
+use crate::{
+	auth::{
+		LoginThrottle,
+		Tokenizer,
+	},
+	Result,
+};
+
+/// Settings of the application, read from `Rocket.toml` and `ROCKET_*` variables like the
+/// ones of rocket itself, see `Rocket.dist.toml` for all keys
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+	pub cors: CorsConfig,
+	pub token: TokenConfig,
+	pub logging: LoggingConfig,
+	pub metrics: MetricsConfig,
+	pub login: LoginConfig,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CorsConfig {
+	/// Origins of the frontend, `*` allows every origin
+	pub allowed_origins: Vec<String>,
+}
+
+impl Default for CorsConfig {
+	fn default() -> Self {
+		Self {
+			allowed_origins: vec!["http://localhost:5173".to_string()],
+		}
+	}
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TokenConfig {
+	/// Seconds until a login token expires
+	pub lifetime: u64,
+}
+
+impl Default for TokenConfig {
+	fn default() -> Self {
+		Self {
+			lifetime: 5 * 24 * 60 * 60,
+		}
+	}
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LoggingConfig {
+	/// Directory of the JSON log files
+	pub dir: PathBuf,
+	/// Format of the output on stdout, the files are always JSON
+	pub format: LogFormat,
+	/// How often a new log file is started
+	pub rotation: LogRotation,
+	/// Only log to stdout, e.g. in a container, `dir` and `rotation` are ignored
+	pub stdout_only: bool,
+}
+
+impl Default for LoggingConfig {
+	fn default() -> Self {
+		Self {
+			dir: PathBuf::from("/var/log/zeiterfassung"),
+			format: LogFormat::default(),
+			rotation: LogRotation::default(),
+			stdout_only: false,
+		}
+	}
+}
+
+/// Basic auth credentials for `/metrics`, open to everyone without them
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MetricsConfig {
+	pub username: Option<String>,
+	pub password: Option<String>,
+}
+
+/// Limits of failed logins, see [`crate::auth::LoginThrottle`]
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LoginConfig {
+	/// Failed logins of an account within `window` until it is locked
+	pub max_failures: u32,
+	/// Seconds an account stays locked
+	pub lockout: u64,
+	/// Login attempts of a client IP within `window`, successful ones included
+	pub max_attempts_per_ip: u32,
+	/// Seconds attempts are counted for
+	pub window: u64,
+}
+
+impl Default for LoginConfig {
+	fn default() -> Self {
+		Self {
+			max_failures: 5,
+			lockout: 15 * 60,
+			max_attempts_per_ip: 50,
+			window: 15 * 60,
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+	#[default]
+	Pretty,
+	Compact,
+	Json,
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogRotation {
+	Minutely,
+	Hourly,
+	#[default]
+	Daily,
+	Never,
+}
+
+impl Config {
+	/// Reads and validates the settings, the errors name the offending key
+	pub fn from_figment(figment: &Figment) -> Result<Self> {
+		let config = figment.extract::<Self>()?;
+		config.validate()?;
+		Ok(config)
+	}
+
+	/// Settings of the default figment, for use before rocket is built
+	pub fn load() -> Result<Self> {
+		Self::from_figment(&rocket::Config::figment())
+	}
+
+	fn validate(&self) -> Result<()> {
+		if self.token.lifetime == 0 {
+			return Err(invalid("token.lifetime must be greater than 0 seconds"));
+		}
+		if !self.logging.stdout_only && self.logging.dir.as_os_str().is_empty() {
+			return Err(invalid(
+				"logging.dir must not be empty unless logging.stdout_only is set",
+			));
+		}
+		if self.metrics.username.is_some() != self.metrics.password.is_some() {
+			return Err(invalid(
+				"metrics.username and metrics.password must be set together",
+			));
+		}
+		if self.login.max_failures == 0 || self.login.max_attempts_per_ip == 0 {
+			return Err(invalid(
+				"login.max_failures and login.max_attempts_per_ip must be greater than 0",
+			));
+		}
+		if self.login.lockout == 0 || self.login.window == 0 {
+			return Err(invalid(
+				"login.lockout and login.window must be greater than 0 seconds",
+			));
+		}
+		self.cors()?;
+		Ok(())
+	}
+
+	/// CORS fairing for [`CorsConfig::allowed_origins`]
+	pub fn cors(&self) -> Result<Cors> {
+		let origins = &self.cors.allowed_origins;
+		let allowed_origins = if origins.iter().any(|origin| origin == "*") {
+			AllowedOrigins::all()
+		} else {
+			AllowedOrigins::some_exact(origins)
+		};
+		CorsOptions {
+			allowed_origins,
+			..Default::default()
+		}
+		.to_cors()
+		.map_err(|e| invalid(&format!("cors.allowed_origins: {e}")))
+	}
+
+	/// Signs login tokens valid for [`TokenConfig::lifetime`]
+	pub fn tokenizer(&self) -> Tokenizer {
+		Tokenizer::new(Duration::from_secs(self.token.lifetime))
+	}
+
+	/// Counts failed logins with the limits of [`LoginConfig`]
+	pub fn login_throttle(&self) -> LoginThrottle {
+		LoginThrottle::new(&self.login)
+	}
+}
+
+fn invalid(message: &str) -> crate::Error {
+	rocket::figment::Error::from(message.to_string()).into()
+}
+
+/// Reads the [`Config`] at ignition, attaches CORS and manages the [`Tokenizer`] and
+/// [`LoginThrottle`]
+///
+/// An invalid configuration aborts the launch with the reason
+pub fn fairing() -> AdHoc {
+	AdHoc::try_on_ignite("Config", |rocket| async move {
+		let config = match Config::from_figment(rocket.figment()) {
+			Ok(config) => config,
+			Err(e) => {
+				error!("Invalid configuration: {e}");
+				return Err(rocket);
+			}
+		};
+		// validated above
+		let cors = config.cors().expect("CORS options were validated");
+		Ok(
+			rocket
+				.attach(cors)
+				.manage(config.tokenizer())
+				.manage(config.login_throttle())
+				.manage(config),
+		)
+	})
+}
+
+#[cfg(test)]
+mod test {
+	use rocket::figment::{
+		providers::Serialized,
+		Figment,
+	};
+
+	use super::Config;
+
+	#[test]
+	fn defaults() {
+		let config = Config::from_figment(&Figment::new()).unwrap();
+		assert_eq!(config.cors.allowed_origins, ["http://localhost:5173"]);
+		assert_eq!(config.token.lifetime, 5 * 24 * 60 * 60);
+		assert!(!config.logging.stdout_only);
+		assert_eq!(config.login.max_failures, 5);
+	}
+
+	#[test]
+	fn invalid() {
+		let figment = |key: &str, value: &str| Figment::new().merge(Serialized::default(key, value));
+		let error = Config::from_figment(&figment("token.lifetime", "soon")).unwrap_err();
+		assert!(error.to_string().contains("token.lifetime"), "{error}");
+		let error = Config::from_figment(&figment("logging.rotation", "weekly")).unwrap_err();
+		assert!(error.to_string().contains("logging.rotation"), "{error}");
+		let error =
+			Config::from_figment(&Figment::new().merge(Serialized::default("token.lifetime", 0)))
+				.unwrap_err();
+		assert!(error.to_string().contains("token.lifetime"), "{error}");
+		let error = Config::from_figment(&Figment::new().merge(Serialized::default("login.window", 0)))
+			.unwrap_err();
+		assert!(error.to_string().contains("login.window"), "{error}");
+		let error = Config::from_figment(
+			&Figment::new().merge(Serialized::default("cors.allowed_origins", ["not a url"])),
+		)
+		.unwrap_err();
+		assert!(
+			error.to_string().contains("cors.allowed_origins"),
+			"{error}"
+		);
+	}
+}
@@ -1,34 +1,63 @@
+use diesel::dsl::now;
 #[cfg(test)]
 use fake::{
 	faker::company::en::*,
 	Dummy,
 	Fake,
 };
-use rocket_db_pools::{
-	diesel::{
-		insert_into,
-		prelude::*,
-		RunQueryDsl,
-	},
-	Connection,
-};
+use rocket_db_pools::Connection;
 use tracing::trace;
 
 use super::{
-	last_insert_id,
+	audit::{
+		Action,
+		Audit,
+	},
+	ListQuery,
 	PaginationResult,
 };
 use crate::{
+	db::prelude::*,
 	schema::*,
+	Result,
 	DB,
 };
 
+list_query!(
+	activity,
+	search: [name, category],
+	fields: {
+		id: i32,
+		name: String,
+		billable: bool,
+		category: String,
+		created_at: chrono::NaiveDateTime,
+		updated_at: chrono::NaiveDateTime,
+	}
+);
+
+crud!(
+	activity,
+	Activity,
+	CreateActivity,
+	UpdateActivity,
+	soft_delete
+);
+
 /// Struct representing a row in table `activity`
 #[derive(
-	Debug, Clone, serde::Serialize, serde::Deserialize, Queryable, Selectable, QueryableByName,
+	Debug,
+	Clone,
+	serde::Serialize,
+	serde::Deserialize,
+	schemars::JsonSchema,
+	Queryable,
+	Selectable,
+	QueryableByName,
 )]
 #[diesel(table_name = activity, primary_key(id))]
 #[cfg_attr(test, derive(PartialEq))]
+#[typeshare::typeshare]
 pub struct Activity {
 	/// Field representing column `id`
 	pub id: i32,
@@ -40,12 +69,19 @@ pub struct Activity {
 	pub created_at: chrono::NaiveDateTime,
 	/// Field representing column `updated_at`
 	pub updated_at: chrono::NaiveDateTime,
+	/// Field representing column `deleted_at`
+	pub deleted_at: Option<chrono::NaiveDateTime>,
+	/// Field representing column `billable`, entries with non-billable activities only are left out of billing
+	pub billable: bool,
+	/// Field representing column `category`
+	pub category: Option<String>,
 }
 
 /// Create Struct for a row in table `activity` for [`Activity`]
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, Insertable)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, schemars::JsonSchema, Insertable)]
 #[diesel(table_name = activity)]
 #[cfg_attr(test, derive(Dummy))]
+#[typeshare::typeshare]
 pub struct CreateActivity {
 	/// Field representing column `token`
 	pub token: Option<String>,
@@ -58,16 +94,52 @@ pub struct CreateActivity {
 		)
 	)]
 	pub name: String,
+	/// Field representing column `billable`
+	#[serde(default = "default_billable")]
+	#[cfg_attr(test, dummy(expr = "true"))]
+	pub billable: bool,
+	/// Field representing column `category`
+	#[serde(default)]
+	#[cfg_attr(test, dummy(default))]
+	pub category: Option<String>,
+}
+
+fn default_billable() -> bool {
+	true
 }
 
 /// Update Struct for a row in table `activity` for [`Activity`]
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, AsChangeset, PartialEq, Default)]
+#[derive(
+	Debug,
+	Clone,
+	serde::Serialize,
+	serde::Deserialize,
+	schemars::JsonSchema,
+	AsChangeset,
+	PartialEq,
+	Default,
+)]
 #[diesel(table_name = activity)]
+#[typeshare::typeshare]
 pub struct UpdateActivity {
 	/// Field representing column `token`
+	#[serde(
+		default,
+		skip_serializing_if = "Option::is_none",
+		deserialize_with = "serde_with::rust::double_option::deserialize"
+	)]
 	pub token: Option<Option<String>>,
 	/// Field representing column `name`
 	pub name: Option<String>,
+	/// Field representing column `billable`
+	pub billable: Option<bool>,
+	/// Field representing column `category`
+	#[serde(
+		default,
+		skip_serializing_if = "Option::is_none",
+		deserialize_with = "serde_with::rust::double_option::deserialize"
+	)]
+	pub category: Option<Option<String>>,
 	/// Field representing column `created_at`
 	pub created_at: Option<chrono::NaiveDateTime>,
 	/// Field representing column `updated_at`
@@ -75,114 +147,183 @@ pub struct UpdateActivity {
 }
 
 impl Activity {
-	/// Insert a new row into `activity` with a given [`CreateActivity`]
-	pub async fn create(db: &mut Connection<DB>, item: &CreateActivity) -> QueryResult<Self> {
-		use crate::schema::activity::dsl::*;
-
-		trace!("Inserting into activity table: {:?}", item);
-		db.transaction(|mut conn| {
-			Box::pin(async move {
-				insert_into(activity)
-					.values(item)
-					.execute(&mut conn)
-					.await?;
-				activity
-					.select(Activity::as_select())
-					.filter(id.eq(last_insert_id()))
-					.first::<Self>(&mut conn)
-					.await
-			})
-		})
-		.await
-	}
-
-	/// Get a row from `activity`, identified by the primary key
-	pub async fn read(db: &mut Connection<DB>, param_id: i32) -> QueryResult<Self> {
-		use crate::schema::activity::dsl::*;
-
-		trace!("Reading from activity table: {}", param_id);
-		activity.filter(id.eq(param_id)).first::<Self>(db).await
-	}
-
 	/// Paginates through the table where page is a 0-based index (i.e. page 0 is the first page)
+	///
+	/// Soft deleted rows are skipped
 	pub async fn paginate(
 		db: &mut Connection<DB>,
 		page: i64,
 		page_size: i64,
-	) -> QueryResult<PaginationResult<Self>> {
+		list: &ListQuery,
+	) -> Result<PaginationResult<Self>> {
 		use crate::schema::activity::dsl::*;
 
 		trace!(
-			"Paginating through activity table: page {}, page_size {}",
+			"Paginating through activity table: page {}, page_size {}, {:?}",
 			page,
-			page_size
+			page_size,
+			list
 		);
-		let page_size = if page_size < 1 { 1 } else { page_size };
-		let total_items = activity.count().get_result(db).await?;
-		let items = activity
-			.limit(page_size)
-			.offset(page * page_size)
-			.load::<Self>(db)
-			.await?;
-
-		Ok(PaginationResult {
-			items,
-			total_items,
+		Self::paginate_query(
+			db,
+			|| list_filter(activity.filter(deleted_at.is_null()).into_boxed(), list),
+			list,
 			page,
 			page_size,
-			/* ceiling division of integers */
-			num_pages: total_items / page_size + i64::from(total_items % page_size != 0),
-		})
+		)
+		.await
 	}
 
-	/// Update a row in `activity`, identified by the primary key with [`UpdateActivity`]
-	pub async fn update(
+	/// Move every reference of `param_id` over to `target` and soft delete `param_id` afterwards
+	///
+	/// Links that would exist twice afterwards are dropped, e.g. a tracking entry that was
+	/// booked with both activities keeps only `target`. Everything happens in one transaction.
+	pub async fn merge_into(
 		db: &mut Connection<DB>,
+		actor: i32,
 		param_id: i32,
-		item: &UpdateActivity,
+		target: i32,
 	) -> QueryResult<Self> {
 		use crate::schema::activity::dsl::*;
 
-		trace!("Updating activity table: {} with {:?}", param_id, item);
+		trace!("Merging activity {} into {}", param_id, target);
 		db.transaction(|mut conn| {
 			Box::pin(async move {
+				let old = activity
+					.filter(id.eq(param_id))
+					.filter(deleted_at.is_null())
+					.first::<Self>(&mut conn)
+					.await?;
+				let new_target = activity
+					.filter(id.eq(target))
+					.filter(deleted_at.is_null())
+					.first::<Self>(&mut conn)
+					.await?;
+
+				let target_tracking = tracking_to_activity::table
+					.filter(tracking_to_activity::activity_id.eq(target))
+					.select(tracking_to_activity::tracking_id)
+					.load::<i32>(&mut conn)
+					.await?;
+				diesel::delete(
+					tracking_to_activity::table
+						.filter(tracking_to_activity::activity_id.eq(param_id))
+						.filter(tracking_to_activity::tracking_id.eq_any(target_tracking)),
+				)
+				.execute(&mut conn)
+				.await?;
+				diesel::update(
+					tracking_to_activity::table.filter(tracking_to_activity::activity_id.eq(param_id)),
+				)
+				.set(tracking_to_activity::activity_id.eq(target))
+				.execute(&mut conn)
+				.await?;
+
+				let target_projects = project_activity::table
+					.filter(project_activity::activity_id.eq(target))
+					.select(project_activity::project_id)
+					.load::<i32>(&mut conn)
+					.await?;
+				diesel::delete(
+					project_activity::table
+						.filter(project_activity::activity_id.eq(param_id))
+						.filter(project_activity::project_id.eq_any(target_projects)),
+				)
+				.execute(&mut conn)
+				.await?;
+				diesel::update(project_activity::table.filter(project_activity::activity_id.eq(param_id)))
+					.set(project_activity::activity_id.eq(target))
+					.execute(&mut conn)
+					.await?;
+
+				diesel::update(project_budget::table.filter(project_budget::activity_id.eq(param_id)))
+					.set(project_budget::activity_id.eq(target))
+					.execute(&mut conn)
+					.await?;
+
 				diesel::update(activity.filter(id.eq(param_id)))
-					.set(item)
+					.set(deleted_at.eq(now.nullable()))
 					.execute(&mut conn)
 					.await?;
-				activity
+				let new = activity
 					.filter(id.eq(param_id))
 					.first::<Self>(&mut conn)
-					.await
+					.await?;
+				Audit::log(
+					conn,
+					actor,
+					"activity",
+					param_id,
+					Action::Merge,
+					Some(&old),
+					Some(&new_target),
+				)
+				.await?;
+				Audit::log(
+					conn,
+					actor,
+					"activity",
+					param_id,
+					Action::Delete,
+					Some(&old),
+					Some(&new),
+				)
+				.await?;
+				Ok(new_target)
 			})
 		})
 		.await
 	}
 
-	/// Delete a row in `activity`, identified by the primary key
-	pub async fn delete(db: &mut Connection<DB>, param_id: i32) -> QueryResult<usize> {
-		use crate::schema::activity::dsl::*;
-
-		trace!("Deleting from activity table: {}", param_id);
-		diesel::delete(activity.filter(id.eq(param_id)))
-			.execute(db)
-			.await
+	/// Count the rows of other tables that still reference a row in `activity`
+	///
+	/// Only tables with at least one reference are returned
+	pub async fn references(
+		db: &mut Connection<DB>,
+		param_id: i32,
+	) -> QueryResult<Vec<(&'static str, i64)>> {
+		trace!("Counting references to activity: {}", param_id);
+		let tracking_to_activity: i64 = tracking_to_activity::table
+			.filter(tracking_to_activity::activity_id.eq(param_id))
+			.count()
+			.get_result(db)
+			.await?;
+		let project_activity: i64 = project_activity::table
+			.filter(project_activity::activity_id.eq(param_id))
+			.count()
+			.get_result(db)
+			.await?;
+		let project_budget: i64 = project_budget::table
+			.filter(project_budget::activity_id.eq(param_id))
+			.count()
+			.get_result(db)
+			.await?;
+		Ok(
+			[
+				("tracking_to_activity", tracking_to_activity),
+				("project_budget", project_budget),
+				("project_activity", project_activity),
+			]
+			.into_iter()
+			.filter(|(_, count)| *count > 0)
+			.collect(),
+		)
 	}
 
-	pub async fn last_page(db: &mut Connection<DB>, page_size: i64) -> QueryResult<i64> {
+	pub async fn last_page(db: &mut Connection<DB>, page_size: i64, list: &ListQuery) -> Result<i64> {
 		use crate::schema::activity::dsl::*;
 
-		trace!("Getting last page of activity table for page_size {page_size}");
-
-		let total_items: i64 = activity.count().get_result(db).await?;
-		// index starts at 0
-		Ok((total_items / page_size + i64::from(total_items % page_size != 0)) - 1)
+		let query = list_filter(activity.filter(deleted_at.is_null()).into_boxed(), list)?;
+		Self::last_page_query(db, query, page_size).await
 	}
 }
 
 #[cfg(test)]
 impl PartialEq<CreateActivity> for Activity {
 	fn eq(&self, other: &CreateActivity) -> bool {
-		self.name == other.name && self.token == other.token
+		self.name == other.name
+			&& self.token == other.token
+			&& self.billable == other.billable
+			&& self.category == other.category
 	}
 }
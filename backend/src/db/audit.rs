@@ -0,0 +1,276 @@
+use diesel::insert_into;
+use rocket::serde::json::{
+	serde_json::{
+		self,
+		Map,
+	},
+	Value,
+};
+use rocket_db_pools::Connection;
+use schemars::JsonSchema;
+use serde::{
+	Deserialize,
+	Serialize,
+};
+use tracing::trace;
+
+use super::{
+	DbConnection,
+	PaginationResult,
+};
+use crate::{
+	db::prelude::*,
+	schema::*,
+	DB,
+};
+
+/// Fields that must never end up in the audit log in plain text
+const REDACTED_FIELDS: [&str; 1] = ["hash"];
+
+/// Kind of change that got recorded
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[serde(rename_all = "lowercase")]
+#[typeshare::typeshare]
+pub enum Action {
+	Create,
+	Update,
+	/// Soft delete
+	Delete,
+	Restore,
+	/// Hard delete
+	Purge,
+	/// All references were moved to another row, `new_value` holds the target
+	Merge,
+}
+
+impl Action {
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			Self::Create => "create",
+			Self::Update => "update",
+			Self::Delete => "delete",
+			Self::Restore => "restore",
+			Self::Purge => "purge",
+			Self::Merge => "merge",
+		}
+	}
+
+	/// Counterpart of [`Action::as_str`], `None` for anything that is no action
+	pub fn parse(action: &str) -> Option<Self> {
+		[
+			Self::Create,
+			Self::Update,
+			Self::Delete,
+			Self::Restore,
+			Self::Purge,
+			Self::Merge,
+		]
+		.into_iter()
+		.find(|known| known.as_str() == action)
+	}
+}
+
+/// Struct representing a row in table `audit_log`
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = audit_log, primary_key(id))]
+struct AuditRow {
+	id: i32,
+	user_id: i32,
+	entity: String,
+	entity_id: i32,
+	action: String,
+	old_value: Option<String>,
+	new_value: Option<String>,
+	created_at: chrono::NaiveDateTime,
+}
+
+/// Create Struct for a row in table `audit_log` for [`AuditRow`]
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = audit_log)]
+struct CreateAuditRow<'a> {
+	user_id: i32,
+	entity: &'a str,
+	entity_id: i32,
+	action: &'a str,
+	old_value: Option<String>,
+	new_value: Option<String>,
+}
+
+/// A single entry of the audit log as it is returned by the API
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[typeshare::typeshare]
+pub struct Audit {
+	pub id: i32,
+	/// The user that made the change
+	pub user_id: i32,
+	/// Name of the table that got changed
+	pub entity: String,
+	/// Primary key of the changed row
+	pub entity_id: i32,
+	pub action: Action,
+	/// Changed fields before the change, `null` on create
+	pub old_value: Option<Value>,
+	/// Changed fields after the change, `null` on purge
+	pub new_value: Option<Value>,
+	pub created_at: chrono::NaiveDateTime,
+}
+
+impl TryFrom<AuditRow> for Audit {
+	type Error = diesel::result::Error;
+
+	fn try_from(row: AuditRow) -> QueryResult<Self> {
+		let parse = |value: Option<String>| value.and_then(|value| serde_json::from_str(&value).ok());
+		let action = Action::parse(&row.action).ok_or_else(|| {
+			diesel::result::Error::DeserializationError(
+				format!("Unknown audit action: {}", row.action).into(),
+			)
+		})?;
+		Ok(Self {
+			id: row.id,
+			user_id: row.user_id,
+			entity: row.entity,
+			entity_id: row.entity_id,
+			action,
+			old_value: parse(row.old_value),
+			new_value: parse(row.new_value),
+			created_at: row.created_at,
+		})
+	}
+}
+
+/// Filter for [`Audit::filter`], every field that is `None` is ignored
+#[derive(Debug, Clone, Default)]
+pub struct AuditFilter {
+	pub entity: Option<String>,
+	pub entity_id: Option<i32>,
+	pub user_id: Option<i32>,
+}
+
+impl Audit {
+	/// Append an entry to the audit log.
+	///
+	/// Takes the bare connection so it can be called from within the transaction
+	/// of the change that is being recorded.
+	/// On update only the fields that actually changed are stored.
+	pub async fn log<T: Serialize>(
+		conn: &mut DbConnection,
+		actor: i32,
+		entity: &str,
+		entity_id: i32,
+		action: Action,
+		old: Option<&T>,
+		new: Option<&T>,
+	) -> QueryResult<()> {
+		trace!(
+			"Audit: user {} {} {} {}",
+			actor,
+			action.as_str(),
+			entity,
+			entity_id
+		);
+		let (old_value, new_value) = diff(
+			old.map(|old| serde_json::to_value(old).unwrap_or_default()),
+			new.map(|new| serde_json::to_value(new).unwrap_or_default()),
+		);
+		let item = CreateAuditRow {
+			user_id: actor,
+			entity,
+			entity_id,
+			action: action.as_str(),
+			old_value: old_value.map(|value| value.to_string()),
+			new_value: new_value.map(|value| value.to_string()),
+		};
+		insert_into(audit_log::table)
+			.values(&item)
+			.execute(conn)
+			.await?;
+		Ok(())
+	}
+
+	/// Paginates through the audit log, newest entries first
+	pub async fn filter(
+		db: &mut Connection<DB>,
+		filter: &AuditFilter,
+		page: i64,
+		page_size: i64,
+	) -> QueryResult<PaginationResult<Self>> {
+		trace!(
+			"Paginating through audit_log table: {:?}, page {}, page_size {}",
+			filter,
+			page,
+			page_size
+		);
+		let query = || {
+			let mut query = audit_log::table.into_boxed();
+			if let Some(entity) = &filter.entity {
+				query = query.filter(audit_log::entity.eq(entity.clone()));
+			}
+			if let Some(entity_id) = filter.entity_id {
+				query = query.filter(audit_log::entity_id.eq(entity_id));
+			}
+			if let Some(user_id) = filter.user_id {
+				query = query.filter(audit_log::user_id.eq(user_id));
+			}
+			query
+		};
+		let page_size = page_size.max(1);
+		let total_items = query().count().get_result(db).await?;
+		let items = query()
+			.select(AuditRow::as_select())
+			.order(audit_log::id.desc())
+			.limit(page_size)
+			.offset(page * page_size)
+			.load::<AuditRow>(db)
+			.await?
+			.into_iter()
+			.map(Audit::try_from)
+			.collect::<QueryResult<_>>()?;
+		Ok(PaginationResult::new(items, total_items, page, page_size))
+	}
+}
+
+/// Reduce two snapshots of a row to the fields that differ
+/// and strip everything listed in [`REDACTED_FIELDS`]
+fn diff(old: Option<Value>, new: Option<Value>) -> (Option<Value>, Option<Value>) {
+	match (old, new) {
+		(Some(Value::Object(old)), Some(Value::Object(new))) => {
+			let mut old_changed = Map::new();
+			let mut new_changed = Map::new();
+			for (key, new_value) in new {
+				let old_value = old.get(&key).cloned().unwrap_or(Value::Null);
+				if old_value != new_value {
+					old_changed.insert(key.clone(), redact(&key, old_value));
+					new_changed.insert(key.clone(), redact(&key, new_value));
+				}
+			}
+			(
+				Some(Value::Object(old_changed)),
+				Some(Value::Object(new_changed)),
+			)
+		}
+		(old, new) => (old.map(redact_all), new.map(redact_all)),
+	}
+}
+
+fn redact(key: &str, value: Value) -> Value {
+	if REDACTED_FIELDS.contains(&key) {
+		Value::String("<redacted>".to_string())
+	} else {
+		value
+	}
+}
+
+fn redact_all(value: Value) -> Value {
+	match value {
+		Value::Object(map) => Value::Object(
+			map
+				.into_iter()
+				.map(|(key, value)| {
+					let value = redact(&key, value);
+					(key, value)
+				})
+				.collect(),
+		),
+		value => value,
+	}
+}
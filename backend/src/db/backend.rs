@@ -0,0 +1,121 @@
+//! The database backend, chosen at compile time by the `mysql`, `postgres` or `sqlite` feature
+
+#[cfg(any(
+	all(feature = "mysql", feature = "postgres"),
+	all(feature = "mysql", feature = "sqlite"),
+	all(feature = "postgres", feature = "sqlite"),
+))]
+compile_error!("The features `mysql`, `postgres` and `sqlite` are mutually exclusive");
+#[cfg(not(any(feature = "mysql", feature = "postgres", feature = "sqlite")))]
+compile_error!("One of the features `mysql`, `postgres` or `sqlite` has to be enabled");
+
+/// diesel's prelude with the async query methods of diesel-async
+///
+/// Like `rocket_db_pools::diesel::prelude`, which is missing without its MySQL or PostgreSQL feature.
+pub mod prelude {
+	pub use diesel::prelude::*;
+	pub use diesel_async::{
+		AsyncConnection,
+		RunQueryDsl,
+	};
+}
+
+#[cfg(feature = "mysql")]
+mod selected {
+	pub use diesel::{
+		mysql::Mysql as Backend,
+		MysqlConnection as SyncConnection,
+	};
+	pub use rocket_db_pools::diesel::{
+		AsyncMysqlConnection as DbConnection,
+		MysqlPool as Pool,
+	};
+
+	/// Migrations of the backend, relative to the backend crate
+	pub const MIGRATIONS: diesel_migrations::EmbeddedMigrations =
+		diesel_migrations::embed_migrations!("../migrations/mysql");
+
+	diesel::sql_function!(fn last_insert_id() -> Integer);
+}
+
+#[cfg(feature = "postgres")]
+mod selected {
+	pub use diesel::{
+		pg::Pg as Backend,
+		PgConnection as SyncConnection,
+	};
+	pub use rocket_db_pools::diesel::{
+		AsyncPgConnection as DbConnection,
+		PgPool as Pool,
+	};
+
+	/// Migrations of the backend, relative to the backend crate
+	pub const MIGRATIONS: diesel_migrations::EmbeddedMigrations =
+		diesel_migrations::embed_migrations!("../migrations/postgres");
+}
+
+#[cfg(feature = "sqlite")]
+mod selected {
+	pub use diesel::{
+		sqlite::Sqlite as Backend,
+		SqliteConnection as SyncConnection,
+	};
+
+	pub use crate::db::sqlite::{
+		AsyncSqliteConnection as DbConnection,
+		SqlitePool as Pool,
+	};
+
+	/// Migrations of the backend, relative to the backend crate
+	pub const MIGRATIONS: diesel_migrations::EmbeddedMigrations =
+		diesel_migrations::embed_migrations!("../migrations/sqlite");
+}
+
+pub use selected::*;
+
+/// Inserts `values` into `table` and loads the inserted row as `struct`
+///
+/// PostgreSQL and SQLite return the row right away, MySQL has to look it up by `LAST_INSERT_ID()`,
+/// which is why a connection inside a transaction is expected.
+///
+/// ```ignore
+/// let new = insert_returning!(&mut conn, project_member, item, ProjectMember)?;
+/// ```
+#[cfg(feature = "mysql")]
+macro_rules! insert_returning {
+	($conn:expr, $table:ident, $values:expr, $struct:ty) => {
+		match diesel::insert_into($crate::schema::$table::table)
+			.values($values)
+			.execute($conn)
+			.await
+		{
+			Ok(_) => {
+				$crate::schema::$table::table
+					.select(<$struct>::as_select())
+					.filter($crate::schema::$table::id.eq($crate::db::last_insert_id()))
+					.first::<$struct>($conn)
+					.await
+			}
+			Err(e) => Err(e),
+		}
+	};
+}
+
+/// Inserts `values` into `table` and loads the inserted row as `struct`
+///
+/// PostgreSQL and SQLite return the row right away, MySQL has to look it up by `LAST_INSERT_ID()`,
+/// which is why a connection inside a transaction is expected.
+///
+/// ```ignore
+/// let new = insert_returning!(&mut conn, project_member, item, ProjectMember)?;
+/// ```
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+macro_rules! insert_returning {
+	($conn:expr, $table:ident, $values:expr, $struct:ty) => {
+		diesel::insert_into($crate::schema::$table::table)
+			.values($values)
+			.returning(<$struct>::as_returning())
+			.get_result::<$struct>($conn)
+			.await
+	};
+}
@@ -0,0 +1,116 @@
+use diesel::dsl::{
+	count_star,
+	not,
+	And,
+	Eq,
+	EqAny,
+	Filter,
+	InnerJoin,
+	NeAny,
+	Select,
+};
+use rocket_db_pools::Connection;
+use schemars::JsonSchema;
+use serde::{
+	Deserialize,
+	Serialize,
+};
+use tracing::trace;
+
+use crate::{
+	db::prelude::*,
+	schema::*,
+	DB,
+};
+
+/// Subquery selecting the ids of all tracking entries booked with a (non-)billable activity
+pub type TrackingWithActivity = Select<
+	Filter<InnerJoin<tracking_to_activity::table, activity::table>, Eq<activity::billable, bool>>,
+	tracking_to_activity::tracking_id,
+>;
+
+/// Condition on `tracking` for entries that are not billed
+pub type NotBilled =
+	And<EqAny<tracking::id, TrackingWithActivity>, NeAny<tracking::id, TrackingWithActivity>>;
+
+fn tracking_with_activity(billable: bool) -> TrackingWithActivity {
+	tracking_to_activity::table
+		.inner_join(activity::table)
+		.filter(activity::billable.eq(billable))
+		.select(tracking_to_activity::tracking_id)
+}
+
+/// Matches the tracking entries that are not billed, `not(not_billed())` the billed ones
+///
+/// The hours of an entry are not split between its activities. An entry with a billable and a
+/// non-billable activity is billed completely, only entries with non-billable activities only
+/// are left out. Entries without activities are billed.
+pub fn not_billed() -> NotBilled {
+	tracking::id
+		.eq_any(tracking_with_activity(false))
+		.and(tracking::id.ne_all(tracking_with_activity(true)))
+}
+
+/// Billable hours of a project in a period
+///
+/// Entries with non-billable activities only are never billed and only counted in `excluded_*`,
+/// see [`not_billed`]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[typeshare::typeshare]
+pub struct BillingReport {
+	pub project_id: i32,
+	pub from: chrono::NaiveDate,
+	pub to: chrono::NaiveDate,
+	#[typeshare(serialized_as = "I54")]
+	pub entries: i64,
+	pub performed: f64,
+	pub billed: f64,
+	#[typeshare(serialized_as = "I54")]
+	pub excluded_entries: i64,
+	pub excluded_billed: f64,
+}
+
+impl BillingReport {
+	/// Billing report of a project between `from` and `to`, both inclusive
+	pub async fn project(
+		db: &mut Connection<DB>,
+		param_project_id: i32,
+		from: chrono::NaiveDate,
+		to: chrono::NaiveDate,
+	) -> QueryResult<Self> {
+		trace!(
+			"Billing report for project {} from {} to {}",
+			param_project_id,
+			from,
+			to
+		);
+		let (entries, performed, billed): (i64, Option<f32>, Option<f32>) = tracking::table
+			.filter(tracking::project_id.eq(param_project_id))
+			.filter(tracking::date.between(from, to))
+			.filter(not(not_billed()))
+			.select((
+				count_star(),
+				diesel::dsl::sum(tracking::performed),
+				diesel::dsl::sum(tracking::billed),
+			))
+			.first(db)
+			.await?;
+		let (excluded_entries, excluded_billed): (i64, Option<f32>) = tracking::table
+			.filter(tracking::project_id.eq(param_project_id))
+			.filter(tracking::date.between(from, to))
+			.filter(not_billed())
+			.select((count_star(), diesel::dsl::sum(tracking::billed)))
+			.first(db)
+			.await?;
+		Ok(Self {
+			project_id: param_project_id,
+			from,
+			to,
+			entries,
+			performed: performed.unwrap_or_default().into(),
+			billed: billed.unwrap_or_default().into(),
+			excluded_entries,
+			excluded_billed: excluded_billed.unwrap_or_default().into(),
+		})
+	}
+}
@@ -1,33 +1,71 @@
+use diesel::dsl::now;
 #[cfg(test)]
 use fake::{
 	faker::company::en::*,
 	Dummy,
 	Fake,
 };
-use rocket_db_pools::{
-	diesel::{
-		insert_into,
-		prelude::*,
-		RunQueryDsl,
-	},
-	Connection,
-};
+use rocket_db_pools::Connection;
 use tracing::trace;
 
 use super::{
-	last_insert_id,
+	audit::{
+		Action,
+		Audit,
+	},
+	custom_field::{
+		CustomField,
+		ENTITY_CLIENT,
+	},
+	Backend,
+	DbConnection,
+	ListQuery,
 	PaginationResult,
 };
 use crate::{
+	db::prelude::*,
 	schema::*,
+	Error,
+	Result,
 	DB,
 };
+list_query!(
+	client,
+	search: [name, customer_number],
+	fields: {
+		id: i32,
+		name: String,
+		customer_number: String,
+		currency: String,
+		payment_terms: i32,
+		archived: bool,
+		created_at: chrono::NaiveDateTime,
+		updated_at: chrono::NaiveDateTime,
+	},
+	custom_fields: ENTITY_CLIENT,
+);
+
+crud!(client, Client, CreateClient, UpdateClient, soft_delete, on_purge: purge_custom_fields);
+
+/// The custom field values of a client go with it when it is purged
+async fn purge_custom_fields(conn: &mut DbConnection, param_id: i32) -> QueryResult<usize> {
+	CustomField::delete_values(conn, ENTITY_CLIENT, param_id).await
+}
+
 /// Struct representing a row in table `client`
 #[derive(
-	Debug, Clone, serde::Serialize, serde::Deserialize, Queryable, Selectable, QueryableByName,
+	Debug,
+	Clone,
+	serde::Serialize,
+	serde::Deserialize,
+	schemars::JsonSchema,
+	Queryable,
+	Selectable,
+	QueryableByName,
 )]
 #[diesel(table_name = client, primary_key(id))]
 #[cfg_attr(test, derive(PartialEq))]
+#[typeshare::typeshare]
 pub struct Client {
 	/// Field representing column `id`
 	pub id: i32,
@@ -37,12 +75,33 @@ pub struct Client {
 	pub created_at: chrono::NaiveDateTime,
 	/// Field representing column `updated_at`
 	pub updated_at: chrono::NaiveDateTime,
+	/// Field representing column `deleted_at`
+	pub deleted_at: Option<chrono::NaiveDateTime>,
+	/// Field representing column `archived`
+	pub archived: bool,
+	/// Field representing column `customer_number`
+	pub customer_number: Option<String>,
+	/// Field representing column `billing_street`
+	pub billing_street: Option<String>,
+	/// Field representing column `billing_zip`
+	pub billing_zip: Option<String>,
+	/// Field representing column `billing_city`
+	pub billing_city: Option<String>,
+	/// Field representing column `billing_country`
+	pub billing_country: Option<String>,
+	/// Field representing column `vat_id`
+	pub vat_id: Option<String>,
+	/// Field representing column `currency`, ISO 4217 code e.g. `EUR`
+	pub currency: String,
+	/// Field representing column `payment_terms`, days until an invoice is due
+	pub payment_terms: i32,
 }
 
 /// Create Struct for a row in table `client` for [`Client`]
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, Insertable)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, schemars::JsonSchema, Insertable)]
 #[diesel(table_name = client)]
 #[cfg_attr(test, derive(Dummy))]
+#[typeshare::typeshare]
 pub struct CreateClient {
 	/// Field representing column `name`
 	#[cfg_attr(
@@ -53,14 +112,123 @@ pub struct CreateClient {
 		)
 	)]
 	pub name: String,
+	/// Field representing column `customer_number`
+	#[serde(default)]
+	#[cfg_attr(test, dummy(default))]
+	pub customer_number: Option<String>,
+	/// Field representing column `billing_street`
+	#[serde(default)]
+	#[cfg_attr(test, dummy(default))]
+	pub billing_street: Option<String>,
+	/// Field representing column `billing_zip`
+	#[serde(default)]
+	#[cfg_attr(test, dummy(default))]
+	pub billing_zip: Option<String>,
+	/// Field representing column `billing_city`
+	#[serde(default)]
+	#[cfg_attr(test, dummy(default))]
+	pub billing_city: Option<String>,
+	/// Field representing column `billing_country`
+	#[serde(default)]
+	#[cfg_attr(test, dummy(default))]
+	pub billing_country: Option<String>,
+	/// Field representing column `vat_id`
+	#[serde(default)]
+	#[cfg_attr(test, dummy(default))]
+	pub vat_id: Option<String>,
+	/// Field representing column `currency`
+	#[serde(default = "default_currency")]
+	#[cfg_attr(test, dummy(expr = "default_currency()"))]
+	pub currency: String,
+	/// Field representing column `payment_terms`
+	#[serde(default = "default_payment_terms")]
+	#[cfg_attr(test, dummy(expr = "default_payment_terms()"))]
+	pub payment_terms: i32,
+}
+
+fn default_currency() -> String {
+	"EUR".to_string()
+}
+
+fn default_payment_terms() -> i32 {
+	30
+}
+
+/// Reject billing details that can't end up on an invoice
+pub fn check_billing(currency: &str, payment_terms: i32) -> Result<()> {
+	if currency.len() != 3 || !currency.chars().all(|c| c.is_ascii_uppercase()) {
+		return Err(Error::bad_request("client.invalid_currency"));
+	}
+	if payment_terms < 0 {
+		return Err(Error::bad_request("client.invalid_payment_terms"));
+	}
+	Ok(())
 }
 
 /// Update Struct for a row in table `client` for [`Client`]
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, AsChangeset, PartialEq, Default)]
+#[derive(
+	Debug,
+	Clone,
+	serde::Serialize,
+	serde::Deserialize,
+	schemars::JsonSchema,
+	AsChangeset,
+	PartialEq,
+	Default,
+)]
 #[diesel(table_name = client)]
+#[typeshare::typeshare]
 pub struct UpdateClient {
 	/// Field representing column `name`
 	pub name: Option<String>,
+	/// Field representing column `archived`
+	pub archived: Option<bool>,
+	/// Field representing column `customer_number`
+	#[serde(
+		default,
+		skip_serializing_if = "Option::is_none",
+		deserialize_with = "serde_with::rust::double_option::deserialize"
+	)]
+	pub customer_number: Option<Option<String>>,
+	/// Field representing column `billing_street`
+	#[serde(
+		default,
+		skip_serializing_if = "Option::is_none",
+		deserialize_with = "serde_with::rust::double_option::deserialize"
+	)]
+	pub billing_street: Option<Option<String>>,
+	/// Field representing column `billing_zip`
+	#[serde(
+		default,
+		skip_serializing_if = "Option::is_none",
+		deserialize_with = "serde_with::rust::double_option::deserialize"
+	)]
+	pub billing_zip: Option<Option<String>>,
+	/// Field representing column `billing_city`
+	#[serde(
+		default,
+		skip_serializing_if = "Option::is_none",
+		deserialize_with = "serde_with::rust::double_option::deserialize"
+	)]
+	pub billing_city: Option<Option<String>>,
+	/// Field representing column `billing_country`
+	#[serde(
+		default,
+		skip_serializing_if = "Option::is_none",
+		deserialize_with = "serde_with::rust::double_option::deserialize"
+	)]
+	pub billing_country: Option<Option<String>>,
+	/// Field representing column `vat_id`
+	#[serde(
+		default,
+		skip_serializing_if = "Option::is_none",
+		deserialize_with = "serde_with::rust::double_option::deserialize"
+	)]
+	pub vat_id: Option<Option<String>>,
+	/// Field representing column `currency`
+	pub currency: Option<String>,
+	/// Field representing column `payment_terms`
+	pub payment_terms: Option<i32>,
 	/// Field representing column `created_at`
 	pub created_at: Option<chrono::NaiveDateTime>,
 	/// Field representing column `updated_at`
@@ -68,107 +236,161 @@ pub struct UpdateClient {
 }
 
 impl Client {
-	/// Insert a new row into `client` with a given [`CreateClient`]
-	pub async fn create(db: &mut Connection<DB>, item: &CreateClient) -> QueryResult<Self> {
-		use crate::schema::client::dsl::*;
-
-		trace!("Inserting into client table: {:?}", item);
-		db.transaction(|mut conn| {
-			Box::pin(async move {
-				insert_into(client).values(item).execute(&mut conn).await?;
-				client
-					.select(Client::as_select())
-					.filter(id.eq(last_insert_id()))
-					.first::<Self>(&mut conn)
-					.await
-			})
-		})
-		.await
-	}
-
-	/// Get a row from `client`, identified by the primary key
-	pub async fn read(db: &mut Connection<DB>, param_id: i32) -> QueryResult<Self> {
-		use crate::schema::client::dsl::*;
-
-		trace!("Reading from client table: {:?}", param_id);
-		client.filter(id.eq(param_id)).first::<Self>(db).await
-	}
-
 	/// Paginates through the table where page is a 0-based index (i.e. page 0 is the first page)
+	///
+	/// Soft deleted rows are skipped, archived rows only show up with `include_archived`
 	pub async fn paginate(
 		db: &mut Connection<DB>,
 		page: i64,
 		page_size: i64,
-	) -> QueryResult<PaginationResult<Self>> {
-		use crate::schema::client::dsl::*;
-
+		include_archived: bool,
+		list: &ListQuery,
+	) -> Result<PaginationResult<Self>> {
 		trace!(
-			"Paginating through client table: page {}, page_size {}",
+			"Paginating through client table: page {}, page_size {}, include_archived {}, {:?}",
 			page,
-			page_size
+			page_size,
+			include_archived,
+			list
 		);
-		let page_size = if page_size < 1 { 1 } else { page_size };
-		let total_items = client.count().get_result(db).await?;
-		let items = client
-			.limit(page_size)
-			.offset(page * page_size)
-			.load::<Self>(db)
-			.await?;
-
-		Ok(PaginationResult {
-			items,
-			total_items,
+		Self::paginate_query(
+			db,
+			|| Self::listed(include_archived, list),
+			list,
 			page,
 			page_size,
-			/* ceiling division of integers */
-			num_pages: total_items / page_size + i64::from(total_items % page_size != 0),
-		})
+		)
+		.await
+	}
+
+	/// Rows that are listed, narrowed down by `list`
+	fn listed<'a>(
+		include_archived: bool,
+		list: &ListQuery,
+	) -> Result<client::BoxedQuery<'a, Backend>> {
+		use crate::schema::client::dsl::*;
+
+		let mut query = client.filter(deleted_at.is_null()).into_boxed();
+		if !include_archived {
+			query = query.filter(archived.eq(false));
+		}
+		list_filter(query, list)
 	}
 
-	/// Update a row in `client`, identified by the primary key with [`UpdateClient`]
-	pub async fn update(
+	/// Move every project, contact and tracking entry of `param_id` over to `target`
+	/// and soft delete `param_id` afterwards, all in one transaction
+	pub async fn merge_into(
 		db: &mut Connection<DB>,
+		actor: i32,
 		param_id: i32,
-		item: &UpdateClient,
+		target: i32,
 	) -> QueryResult<Self> {
 		use crate::schema::client::dsl::*;
 
-		trace!("Updating client table: {} with {:?}", param_id, item);
+		trace!("Merging client {} into {}", param_id, target);
 		db.transaction(|mut conn| {
 			Box::pin(async move {
-				diesel::update(client.filter(id.eq(param_id)))
-					.set(item)
+				let old = client
+					.filter(id.eq(param_id))
+					.filter(deleted_at.is_null())
+					.first::<Self>(&mut conn)
+					.await?;
+				let new_target = client
+					.filter(id.eq(target))
+					.filter(deleted_at.is_null())
+					.first::<Self>(&mut conn)
+					.await?;
+
+				diesel::update(project::table.filter(project::client_id.eq(param_id)))
+					.set(project::client_id.eq(target))
+					.execute(&mut conn)
+					.await?;
+				diesel::update(tracking::table.filter(tracking::client_id.eq(param_id)))
+					.set(tracking::client_id.eq(target))
+					.execute(&mut conn)
+					.await?;
+				diesel::update(client_contact::table.filter(client_contact::client_id.eq(param_id)))
+					.set(client_contact::client_id.eq(target))
 					.execute(&mut conn)
 					.await?;
 
-				client
-					.select(Client::as_select())
-					.filter(id.eq(last_insert_id()))
+				diesel::update(client.filter(id.eq(param_id)))
+					.set(deleted_at.eq(now.nullable()))
+					.execute(&mut conn)
+					.await?;
+				let new = client
+					.filter(id.eq(param_id))
 					.first::<Self>(&mut conn)
-					.await
+					.await?;
+				Audit::log(
+					conn,
+					actor,
+					"client",
+					param_id,
+					Action::Merge,
+					Some(&old),
+					Some(&new_target),
+				)
+				.await?;
+				Audit::log(
+					conn,
+					actor,
+					"client",
+					param_id,
+					Action::Delete,
+					Some(&old),
+					Some(&new),
+				)
+				.await?;
+				Ok(new_target)
 			})
 		})
 		.await
 	}
 
-	/// Delete a row in `client`, identified by the primary key
-	pub async fn delete(db: &mut Connection<DB>, param_id: i32) -> QueryResult<usize> {
-		use crate::schema::client::dsl::*;
-
-		trace!("Deleting from client table: {}", param_id);
-		diesel::delete(client.filter(id.eq(param_id)))
-			.execute(db)
-			.await
+	/// Count the rows of other tables that still reference a row in `client`
+	///
+	/// Only tables with at least one reference are returned
+	pub async fn references(
+		db: &mut Connection<DB>,
+		param_id: i32,
+	) -> QueryResult<Vec<(&'static str, i64)>> {
+		trace!("Counting references to client: {}", param_id);
+		let project: i64 = project::table
+			.filter(project::client_id.eq(param_id))
+			.count()
+			.get_result(db)
+			.await?;
+		let tracking: i64 = tracking::table
+			.filter(tracking::client_id.eq(param_id))
+			.count()
+			.get_result(db)
+			.await?;
+		let client_contact: i64 = client_contact::table
+			.filter(client_contact::client_id.eq(param_id))
+			.count()
+			.get_result(db)
+			.await?;
+		Ok(
+			[
+				("project", project),
+				("tracking", tracking),
+				("client_contact", client_contact),
+			]
+			.into_iter()
+			.filter(|(_, count)| *count > 0)
+			.collect(),
+		)
 	}
 
-	pub async fn last_page(db: &mut Connection<DB>, page_size: i64) -> QueryResult<i64> {
-		use crate::schema::client::dsl::*;
-
-		trace!("Getting last page of client table for page_size {page_size}");
-
-		let total_items: i64 = client.count().get_result(db).await?;
-		// index starts at 0
-		Ok((total_items / page_size + i64::from(total_items % page_size != 0)) - 1)
+	pub async fn last_page(
+		db: &mut Connection<DB>,
+		page_size: i64,
+		include_archived: bool,
+		list: &ListQuery,
+	) -> Result<i64> {
+		let query = Self::listed(include_archived, list)?;
+		Self::last_page_query(db, query, page_size).await
 	}
 }
 
@@ -176,5 +398,9 @@ impl Client {
 impl PartialEq<CreateClient> for Client {
 	fn eq(&self, other: &CreateClient) -> bool {
 		self.name == other.name
+			&& self.customer_number == other.customer_number
+			&& self.vat_id == other.vat_id
+			&& self.currency == other.currency
+			&& self.payment_terms == other.payment_terms
 	}
 }
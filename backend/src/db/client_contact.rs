@@ -0,0 +1,155 @@
+#[cfg(test)]
+use fake::{
+	faker::{
+		internet::en::SafeEmail,
+		name::en::Name,
+		phone_number::en::PhoneNumber,
+	},
+	Dummy,
+};
+use rocket_db_pools::Connection;
+use schemars::JsonSchema;
+use serde::{
+	Deserialize,
+	Serialize,
+};
+use tracing::trace;
+
+use super::client::Client;
+use crate::{
+	db::prelude::*,
+	schema::*,
+	DB,
+};
+
+crud!(
+	client_contact,
+	ClientContact,
+	CreateClientContact,
+	UpdateClientContact,
+	audited
+);
+
+/// Struct representing a row in table `client_contact`
+#[derive(
+	Debug,
+	Clone,
+	Serialize,
+	Deserialize,
+	JsonSchema,
+	Queryable,
+	Selectable,
+	QueryableByName,
+	Associations,
+	Identifiable,
+)]
+#[diesel(table_name=client_contact, primary_key(id), belongs_to(Client, foreign_key=client_id))]
+#[cfg_attr(test, derive(PartialEq))]
+#[typeshare::typeshare]
+pub struct ClientContact {
+	/// Field representing column `id`
+	pub id: i32,
+	/// Field representing column `client_id`
+	pub client_id: i32,
+	/// Field representing column `name`
+	pub name: String,
+	/// Field representing column `email`
+	pub email: Option<String>,
+	/// Field representing column `phone`
+	pub phone: Option<String>,
+	/// Field representing column `position`
+	pub position: Option<String>,
+	/// Field representing column `created_at`
+	pub created_at: chrono::NaiveDateTime,
+	/// Field representing column `updated_at`
+	pub updated_at: chrono::NaiveDateTime,
+}
+
+/// Create Struct for a row in table `client_contact` for [`ClientContact`]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Insertable)]
+#[diesel(table_name=client_contact)]
+#[cfg_attr(test, derive(Dummy))]
+#[typeshare::typeshare]
+pub struct CreateClientContact {
+	/// Field representing column `client_id`, taken from the url
+	#[serde(skip_deserializing)]
+	pub client_id: i32,
+	/// Field representing column `name`
+	#[cfg_attr(test, dummy(faker = "Name()"))]
+	pub name: String,
+	/// Field representing column `email`
+	#[cfg_attr(test, dummy(faker = "SafeEmail()"))]
+	pub email: Option<String>,
+	/// Field representing column `phone`
+	#[cfg_attr(test, dummy(faker = "PhoneNumber()"))]
+	pub phone: Option<String>,
+	/// Field representing column `position`
+	#[cfg_attr(test, dummy(default))]
+	pub position: Option<String>,
+}
+
+/// Update Struct for a row in table `client_contact` for [`ClientContact`]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, AsChangeset, PartialEq, Default)]
+#[diesel(table_name=client_contact)]
+#[typeshare::typeshare]
+pub struct UpdateClientContact {
+	/// Field representing column `name`
+	pub name: Option<String>,
+	/// Field representing column `email`
+	#[serde(
+		default,
+		skip_serializing_if = "Option::is_none",
+		deserialize_with = "serde_with::rust::double_option::deserialize"
+	)]
+	pub email: Option<Option<String>>,
+	/// Field representing column `phone`
+	#[serde(
+		default,
+		skip_serializing_if = "Option::is_none",
+		deserialize_with = "serde_with::rust::double_option::deserialize"
+	)]
+	pub phone: Option<Option<String>>,
+	/// Field representing column `position`
+	#[serde(
+		default,
+		skip_serializing_if = "Option::is_none",
+		deserialize_with = "serde_with::rust::double_option::deserialize"
+	)]
+	pub position: Option<Option<String>>,
+}
+
+impl ClientContact {
+	/// Get a contact, identified by the primary key, if it belongs to the client
+	pub async fn of_client(
+		db: &mut Connection<DB>,
+		param_client_id: i32,
+		param_id: i32,
+	) -> QueryResult<Self> {
+		use crate::schema::client_contact::dsl::*;
+
+		trace!("Reading from client_contact table: {}", param_id);
+		client_contact
+			.filter(id.eq(param_id))
+			.filter(client_id.eq(param_client_id))
+			.first::<Self>(db)
+			.await
+	}
+
+	/// Get all contacts of a client
+	pub async fn from_client(
+		db: &mut Connection<DB>,
+		param_client_id: i32,
+	) -> QueryResult<Vec<Self>> {
+		use crate::schema::client_contact::dsl::*;
+
+		trace!(
+			"Reading from client_contact table for client: {}",
+			param_client_id
+		);
+		client_contact
+			.filter(client_id.eq(param_client_id))
+			.order(name)
+			.load::<Self>(db)
+			.await
+	}
+}
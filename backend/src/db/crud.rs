@@ -0,0 +1,437 @@
+use super::PaginationResult;
+
+/// Number of pages needed for `total_items`, at least one item per page
+pub(crate) fn num_pages(total_items: i64, page_size: i64) -> i64 {
+	let page_size = page_size.max(1);
+	/* ceiling division of integers */
+	total_items / page_size + i64::from(total_items % page_size != 0)
+}
+
+impl<T> PaginationResult<T> {
+	pub(crate) fn new(items: Vec<T>, total_items: i64, page: i64, page_size: i64) -> Self {
+		Self {
+			items,
+			total_items,
+			page,
+			page_size,
+			num_pages: num_pages(total_items, page_size),
+		}
+	}
+}
+
+/// Generates the functions every entity table shares
+///
+/// Every variant generates `create`, `read`, `update` and `delete`.
+///
+/// `soft_delete` tables log every change to the [audit log](crate::db::audit), `delete` only
+/// sets `deleted_at` and `restore` and `purge` are generated as well. `read` and `update` skip
+/// soft deleted rows. Rows depending on a purged row can be removed by the `on_purge` hook, it
+/// runs in the transaction of the purge.
+///
+/// `audited` tables log every change as well, but `delete` removes the row right away. Rows
+/// depending on it can be removed by the `on_delete` hook.
+///
+/// Without either `delete` removes the row and nothing is audited, e.g. `tracking` is audited by
+/// its middle layer.
+///
+/// `soft_delete` and plain tables also get the private helpers `paginate_query` and
+/// `last_page_query`, which page through a boxed query built by the entity so it can add its own
+/// filters. [`list_query!`] has to be invoked for those tables beforehand.
+///
+/// ```ignore
+/// list_query!(client, search: [name], fields: { id: i32, name: String });
+/// crud!(client, Client, CreateClient, UpdateClient, soft_delete, on_purge: purge_custom_fields);
+/// crud!(team, Team, CreateTeam, UpdateTeam, audited, on_delete: delete_members);
+/// ```
+macro_rules! crud {
+	(
+		$table:ident,
+		$struct:ident,
+		$create:ident,
+		$update:ident,
+		soft_delete
+		$(, on_purge: $on_purge:path)? $(,)?
+	) => {
+		impl $struct {
+			/// Get a row, identified by the primary key, unless it is soft deleted
+			pub async fn read(
+				db: &mut $crate::db::DbConnection,
+				param_id: i32,
+			) -> diesel::QueryResult<Self> {
+				use $crate::db::prelude::*;
+
+				use $crate::schema::$table::dsl::*;
+
+				tracing::trace!("Reading from {} table: {}", stringify!($table), param_id);
+				$table
+					.filter(id.eq(param_id))
+					.filter(deleted_at.is_null())
+					.first::<Self>(db)
+					.await
+			}
+
+			/// Soft delete a row, identified by the primary key
+			///
+			/// The row is kept for historic entries, `purge` removes it for good
+			pub async fn delete(
+				db: &mut rocket_db_pools::Connection<$crate::DB>,
+				actor: i32,
+				param_id: i32,
+			) -> diesel::QueryResult<usize> {
+				use $crate::db::prelude::*;
+
+				use $crate::schema::$table::dsl::*;
+
+				tracing::trace!("Soft deleting from {} table: {}", stringify!($table), param_id);
+				db.transaction(|mut conn| {
+					Box::pin(async move {
+						let Some(old) = $table
+							.filter(id.eq(param_id))
+							.filter(deleted_at.is_null())
+							.first::<Self>(&mut conn)
+							.await
+							.optional()?
+						else {
+							return Ok(0);
+						};
+						let deleted = diesel::update($table.filter(id.eq(param_id)))
+							.set(deleted_at.eq(diesel::dsl::now.nullable()))
+							.execute(&mut conn)
+							.await?;
+						let new = $table
+							.filter(id.eq(param_id))
+							.first::<Self>(&mut conn)
+							.await?;
+						$crate::db::audit::Audit::log(
+							conn,
+							actor,
+							stringify!($table),
+							param_id,
+							$crate::db::audit::Action::Delete,
+							Some(&old),
+							Some(&new),
+						)
+						.await?;
+						Ok(deleted)
+					})
+				})
+				.await
+			}
+
+			/// Restore a soft deleted row, identified by the primary key
+			pub async fn restore(
+				db: &mut rocket_db_pools::Connection<$crate::DB>,
+				actor: i32,
+				param_id: i32,
+			) -> diesel::QueryResult<Self> {
+				use $crate::db::prelude::*;
+
+				use $crate::schema::$table::dsl::*;
+
+				tracing::trace!("Restoring {}: {}", stringify!($table), param_id);
+				db.transaction(|mut conn| {
+					Box::pin(async move {
+						let old = $table
+							.filter(id.eq(param_id))
+							.first::<Self>(&mut conn)
+							.await?;
+						diesel::update($table.filter(id.eq(param_id)))
+							.set(deleted_at.eq(None::<chrono::NaiveDateTime>))
+							.execute(&mut conn)
+							.await?;
+						let new = $table
+							.filter(id.eq(param_id))
+							.first::<Self>(&mut conn)
+							.await?;
+						$crate::db::audit::Audit::log(
+							conn,
+							actor,
+							stringify!($table),
+							param_id,
+							$crate::db::audit::Action::Restore,
+							Some(&old),
+							Some(&new),
+						)
+						.await?;
+						Ok(new)
+					})
+				})
+				.await
+			}
+
+			/// Permanently delete a row, identified by the primary key
+			///
+			/// Fails on foreign keys as long as the row is still referenced
+			pub async fn purge(
+				db: &mut rocket_db_pools::Connection<$crate::DB>,
+				actor: i32,
+				param_id: i32,
+			) -> diesel::QueryResult<usize> {
+				use $crate::db::prelude::*;
+
+				use $crate::schema::$table::dsl::*;
+
+				tracing::trace!("Purging from {} table: {}", stringify!($table), param_id);
+				db.transaction(|mut conn| {
+					Box::pin(async move {
+						let Some(old) = $table
+							.filter(id.eq(param_id))
+							.first::<Self>(&mut conn)
+							.await
+							.optional()?
+						else {
+							return Ok(0);
+						};
+						$($on_purge(conn, param_id).await?;)?
+						let deleted = diesel::delete($table.filter(id.eq(param_id)))
+							.execute(&mut conn)
+							.await?;
+						$crate::db::audit::Audit::log(
+							conn,
+							actor,
+							stringify!($table),
+							param_id,
+							$crate::db::audit::Action::Purge,
+							Some(&old),
+							None,
+						)
+						.await?;
+						Ok(deleted)
+					})
+				})
+				.await
+			}
+		}
+
+		crud!(@common $table, $struct, $create, $update, [actor], deleted_at);
+		crud!(@paginate $table, $struct);
+	};
+	(
+		$table:ident,
+		$struct:ident,
+		$create:ident,
+		$update:ident,
+		audited
+		$(, on_delete: $on_delete:path)? $(,)?
+	) => {
+		impl $struct {
+			/// Delete a row, identified by the primary key
+			pub async fn delete(
+				db: &mut rocket_db_pools::Connection<$crate::DB>,
+				actor: i32,
+				param_id: i32,
+			) -> diesel::QueryResult<usize> {
+				use $crate::db::prelude::*;
+
+				use $crate::schema::$table::dsl::*;
+
+				tracing::trace!("Deleting from {} table: {}", stringify!($table), param_id);
+				db.transaction(|mut conn| {
+					Box::pin(async move {
+						let Some(old) = $table
+							.filter(id.eq(param_id))
+							.first::<Self>(&mut conn)
+							.await
+							.optional()?
+						else {
+							return Ok(0);
+						};
+						$($on_delete(conn, param_id).await?;)?
+						let deleted = diesel::delete($table.filter(id.eq(param_id)))
+							.execute(&mut conn)
+							.await?;
+						$crate::db::audit::Audit::log(
+							conn,
+							actor,
+							stringify!($table),
+							param_id,
+							$crate::db::audit::Action::Purge,
+							Some(&old),
+							None,
+						)
+						.await?;
+						Ok(deleted)
+					})
+				})
+				.await
+			}
+		}
+
+		crud!(@common $table, $struct, $create, $update, [actor]);
+		crud!(@read $table, $struct);
+	};
+	($table:ident, $struct:ident, $create:ident, $update:ident) => {
+		impl $struct {
+			/// Delete a row, identified by the primary key
+			pub async fn delete(
+				db: &mut $crate::db::DbConnection,
+				param_id: i32,
+			) -> diesel::QueryResult<usize> {
+				use $crate::db::prelude::*;
+
+				use $crate::schema::$table::dsl::*;
+
+				tracing::trace!("Deleting from {} table: {}", stringify!($table), param_id);
+				diesel::delete($table.filter(id.eq(param_id)))
+					.execute(db)
+					.await
+			}
+		}
+
+		crud!(@common $table, $struct, $create, $update, []);
+		crud!(@read $table, $struct);
+		crud!(@paginate $table, $struct);
+	};
+	// `create` and `update` of every variant, audited with an `actor`, `update` skips rows with
+	// `$deleted_at` set
+	(
+		@common $table:ident,
+		$struct:ident,
+		$create:ident,
+		$update:ident,
+		[$($actor:ident)?]
+		$(, $deleted_at:ident)?
+	) => {
+		impl $struct {
+			/// Insert a new row with a given create struct
+			pub async fn create(
+				db: &mut $crate::db::DbConnection,
+				$($actor: i32,)?
+				item: &$create,
+			) -> diesel::QueryResult<Self> {
+				use $crate::db::prelude::*;
+
+				tracing::trace!("Inserting into {} table: {:?}", stringify!($table), item);
+				db.transaction(|mut conn| {
+					Box::pin(async move {
+						let new = insert_returning!(&mut conn, $table, item, Self)?;
+						$($crate::db::audit::Audit::log(
+							conn,
+							$actor,
+							stringify!($table),
+							new.id,
+							$crate::db::audit::Action::Create,
+							None,
+							Some(&new),
+						)
+						.await?;)?
+						Ok(new)
+					})
+				})
+				.await
+			}
+
+			/// Update a row, identified by the primary key
+			pub async fn update(
+				db: &mut $crate::db::DbConnection,
+				$($actor: i32,)?
+				param_id: i32,
+				item: &$update,
+			) -> diesel::QueryResult<Self> {
+				use $crate::db::prelude::*;
+
+				use $crate::schema::$table::dsl::*;
+
+				tracing::trace!(
+					"Updating {} table: {} with {:?}",
+					stringify!($table),
+					param_id,
+					item
+				);
+				db.transaction(|mut conn| {
+					Box::pin(async move {
+						// fails with `NotFound` before anything is changed, unaudited tables
+						// don't need the row otherwise
+						#[allow(unused_variables)]
+						let old = $table
+							.filter(id.eq(param_id))
+							$(.filter($deleted_at.is_null()))?
+							.first::<Self>(&mut conn)
+							.await?;
+						diesel::update($table.filter(id.eq(param_id)))
+							.set(item)
+							.execute(&mut conn)
+							.await?;
+						let new = $table
+							.filter(id.eq(param_id))
+							.first::<Self>(&mut conn)
+							.await?;
+						$($crate::db::audit::Audit::log(
+							conn,
+							$actor,
+							stringify!($table),
+							param_id,
+							$crate::db::audit::Action::Update,
+							Some(&old),
+							Some(&new),
+						)
+						.await?;)?
+						Ok(new)
+					})
+				})
+				.await
+			}
+		}
+	};
+	(@read $table:ident, $struct:ident) => {
+		impl $struct {
+			/// Get a row, identified by the primary key
+			pub async fn read(
+				db: &mut $crate::db::DbConnection,
+				param_id: i32,
+			) -> diesel::QueryResult<Self> {
+				use $crate::db::prelude::*;
+
+				use $crate::schema::$table::dsl::*;
+
+				tracing::trace!("Reading from {} table: {}", stringify!($table), param_id);
+				$table.filter(id.eq(param_id)).first::<Self>(db).await
+			}
+		}
+	};
+	(@paginate $table:ident, $struct:ident) => {
+		impl $struct {
+			/// Page of the rows of `query`, sorted by `list`, where page is a 0-based index
+			async fn paginate_query<'a>(
+				db: &mut rocket_db_pools::Connection<$crate::DB>,
+				query: impl Fn() -> $crate::Result<$crate::schema::$table::BoxedQuery<'a, $crate::db::Backend>>,
+				list: &$crate::db::ListQuery,
+				page: i64,
+				page_size: i64,
+			) -> $crate::Result<$crate::db::PaginationResult<Self>> {
+				use $crate::db::prelude::*;
+
+				let page_size = page_size.max(1);
+				let total_items = query()?.count().get_result(db).await?;
+				let items = list_sort(query()?, list)?
+					.limit(page_size)
+					.offset(page * page_size)
+					.load::<Self>(db)
+					.await?;
+				Ok($crate::db::PaginationResult::new(
+					items,
+					total_items,
+					page,
+					page_size,
+				))
+			}
+
+			/// Index of the last page of the rows of `query`
+			async fn last_page_query<'a>(
+				db: &mut rocket_db_pools::Connection<$crate::DB>,
+				query: $crate::schema::$table::BoxedQuery<'a, $crate::db::Backend>,
+				page_size: i64,
+			) -> $crate::Result<i64> {
+				use $crate::db::prelude::*;
+
+				tracing::trace!(
+					"Getting last page of {} table for page_size {page_size}",
+					stringify!($table)
+				);
+				let total_items: i64 = query.count().get_result(db).await?;
+				// index starts at 0, an empty table still has a first page
+				Ok(($crate::db::crud::num_pages(total_items, page_size) - 1).max(0))
+			}
+		}
+	};
+}
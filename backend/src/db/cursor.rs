@@ -0,0 +1,123 @@
+use base64::{
+	engine::general_purpose::URL_SAFE_NO_PAD,
+	Engine,
+};
+use schemars::JsonSchema;
+use serde::{
+	Deserialize,
+	Serialize,
+};
+
+use crate::{
+	Error,
+	Result,
+};
+
+/// Default number of items of a [`CursorPage`]
+const DEFAULT_LIMIT: i64 = 25;
+/// Largest number of items of a [`CursorPage`]
+const MAX_LIMIT: i64 = 100;
+
+/// Position in a list ordered by `id`, handed out to clients as an opaque string
+///
+/// Unlike an offset a cursor stays valid when rows are inserted or deleted in front of it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cursor {
+	/// Items with a greater `id`
+	Next(i32),
+	/// Items with a smaller `id`
+	Prev(i32),
+}
+
+impl Cursor {
+	pub fn encode(self) -> String {
+		let cursor = match self {
+			Self::Next(id) => format!("n{id}"),
+			Self::Prev(id) => format!("p{id}"),
+		};
+		URL_SAFE_NO_PAD.encode(cursor)
+	}
+
+	pub fn decode(cursor: &str) -> Result<Self> {
+		let invalid = || Error::bad_request("cursor.invalid").with_arg(cursor);
+		let decoded = URL_SAFE_NO_PAD
+			.decode(cursor)
+			.ok()
+			.and_then(|decoded| String::from_utf8(decoded).ok())
+			.ok_or_else(invalid)?;
+		if let Some(id) = decoded.strip_prefix('n') {
+			Ok(Self::Next(id.parse().map_err(|_| invalid())?))
+		} else if let Some(id) = decoded.strip_prefix('p') {
+			Ok(Self::Prev(id.parse().map_err(|_| invalid())?))
+		} else {
+			Err(invalid())
+		}
+	}
+}
+
+/// Result of a `.cursor` function, a page of a keyset pagination
+///
+/// `next` and `prev` are passed back as `?after=` to get the following or the preceding page,
+/// they are `None` at the ends of the list
+#[derive(Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[typeshare::typeshare]
+pub struct CursorPage<T> {
+	/// Items of the page, ordered by `id`
+	pub items: Vec<T>,
+	/// Cursor of the following page
+	pub next: Option<String>,
+	/// Cursor of the preceding page
+	pub prev: Option<String>,
+	/// The count of total items there are, only if asked for since it needs a full count
+	#[typeshare(serialized_as = "Option<I54>")]
+	pub total_items: Option<i64>,
+}
+
+impl<T> CursorPage<T> {
+	/// Builds a page from at most `limit + 1` rows loaded after `after`
+	///
+	/// Rows of a [`Cursor::Prev`] are loaded in descending order, the extra row tells whether
+	/// there is another page in the direction of the cursor
+	pub fn new(
+		mut items: Vec<T>,
+		after: Option<Cursor>,
+		limit: i64,
+		total_items: Option<i64>,
+		id: impl Fn(&T) -> i32,
+	) -> Self {
+		let more = items.len() as i64 > limit;
+		items.truncate(limit as usize);
+		let backwards = matches!(after, Some(Cursor::Prev(_)));
+		if backwards {
+			items.reverse();
+		}
+		let first = items.first().map(|item| Cursor::Prev(id(item)).encode());
+		let last = items.last().map(|item| Cursor::Next(id(item)).encode());
+		let (next, prev) = match (after, more) {
+			(None, more) => (last.filter(|_| more), None),
+			(Some(Cursor::Next(_)), more) => (last.filter(|_| more), first),
+			(Some(Cursor::Prev(_)), more) => (last, first.filter(|_| more)),
+		};
+		Self {
+			items,
+			next,
+			prev,
+			total_items,
+		}
+	}
+
+	/// Same page with other items, e.g. the middle layer structs of the rows
+	pub fn with_items<U>(self, items: Vec<U>) -> CursorPage<U> {
+		CursorPage {
+			items,
+			next: self.next,
+			prev: self.prev,
+			total_items: self.total_items,
+		}
+	}
+}
+
+/// Number of items of a page, [`DEFAULT_LIMIT`] if not given
+pub fn limit(limit: Option<i64>) -> i64 {
+	limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT)
+}
@@ -0,0 +1,654 @@
+use std::{
+	collections::{
+		BTreeMap,
+		HashMap,
+	},
+	ops::Deref,
+};
+
+use diesel::{
+	dsl,
+	insert_into,
+};
+use rocket::serde::json::{
+	serde_json,
+	Value,
+};
+use rocket_db_pools::Connection;
+use schemars::JsonSchema;
+use serde::{
+	Deserialize,
+	Serialize,
+};
+use tracing::trace;
+use typeshare::typeshare;
+
+use super::{
+	audit::{
+		Action,
+		Audit,
+	},
+	Backend,
+	DbConnection,
+	PaginationResult,
+};
+use crate::{
+	db::prelude::*,
+	schema::*,
+	Error,
+	DB,
+};
+
+pub const ENTITY_CLIENT: &str = "client";
+pub const ENTITY_PROJECT: &str = "project";
+pub const ENTITY_TRACKING: &str = "tracking";
+const ENTITIES: [&str; 3] = [ENTITY_CLIENT, ENTITY_PROJECT, ENTITY_TRACKING];
+
+pub const TYPE_TEXT: &str = "text";
+pub const TYPE_NUMBER: &str = "number";
+/// Dates are formatted as `YYYY-MM-DD`
+pub const TYPE_DATE: &str = "date";
+/// One out of the options of the field
+pub const TYPE_SELECT: &str = "select";
+const TYPES: [&str; 4] = [TYPE_TEXT, TYPE_NUMBER, TYPE_DATE, TYPE_SELECT];
+
+/// Lists are filtered by a custom field with its id, e.g. `?filter[custom_field_12]=4711`
+pub const FILTER_PREFIX: &str = "custom_field_";
+
+crud!(custom_field, CustomField, CreateCustomField, UpdateCustomField, audited, on_delete: delete_field_values);
+
+/// The values of a field go with it when it is deleted
+async fn delete_field_values(conn: &mut DbConnection, param_id: i32) -> QueryResult<usize> {
+	diesel::delete(custom_field_value::table.filter(custom_field_value::custom_field_id.eq(param_id)))
+		.execute(conn)
+		.await
+}
+
+/// Values of the custom fields of a single row, keyed by the name of the field
+// typeshare does not know `BTreeMap`, it is serialized like a `HashMap`
+#[typeshare(serialized_as = "HashMap<String, Value>")]
+pub type CustomFields = BTreeMap<String, Value>;
+
+/// The `options` column holds a JSON array, the api uses a plain list
+mod option_list {
+	use rocket::serde::json::serde_json;
+	use serde::{
+		de::Error,
+		Deserialize,
+		Deserializer,
+		Serialize,
+		Serializer,
+	};
+
+	pub fn serialize<S: Serializer>(options: &str, serializer: S) -> Result<S::Ok, S::Error> {
+		serde_json::from_str::<Vec<String>>(options)
+			.unwrap_or_default()
+			.serialize(serializer)
+	}
+
+	pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+		serde_json::to_string(&Vec::<String>::deserialize(deserializer)?).map_err(D::Error::custom)
+	}
+
+	pub mod optional {
+		use serde::{
+			Deserialize,
+			Deserializer,
+			Serializer,
+		};
+
+		pub fn serialize<S: Serializer>(
+			options: &Option<String>,
+			serializer: S,
+		) -> Result<S::Ok, S::Error> {
+			match options {
+				Some(options) => super::serialize(options, serializer),
+				None => serializer.serialize_none(),
+			}
+		}
+
+		pub fn deserialize<'de, D: Deserializer<'de>>(
+			deserializer: D,
+		) -> Result<Option<String>, D::Error> {
+			#[derive(Deserialize)]
+			struct Options(#[serde(with = "super")] String);
+
+			Ok(Option::<Options>::deserialize(deserializer)?.map(|options| options.0))
+		}
+	}
+}
+
+fn empty_options() -> String {
+	"[]".to_string()
+}
+
+/// Struct representing a row in table `custom_field`
+#[derive(
+	Debug, Clone, Serialize, Deserialize, JsonSchema, Queryable, Selectable, QueryableByName,
+)]
+#[diesel(table_name=custom_field, primary_key(id))]
+#[cfg_attr(test, derive(PartialEq))]
+#[typeshare::typeshare]
+pub struct CustomField {
+	/// Field representing column `id`
+	pub id: i32,
+	/// Field representing column `entity`, one of `client`, `project` and `tracking`
+	pub entity: String,
+	/// Field representing column `name`
+	pub name: String,
+	/// Field representing column `field_type`, one of `text`, `number`, `date` and `select`
+	pub field_type: String,
+	/// Field representing column `options`, the allowed values of a `select` field
+	#[serde(with = "option_list")]
+	#[schemars(with = "Vec<String>")]
+	#[typeshare(serialized_as = "Vec<String>")]
+	pub options: String,
+	/// Field representing column `required`
+	pub required: bool,
+	/// Field representing column `created_at`
+	pub created_at: chrono::NaiveDateTime,
+	/// Field representing column `updated_at`
+	pub updated_at: chrono::NaiveDateTime,
+}
+
+/// Create Struct for a row in table `custom_field` for [`CustomField`]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Insertable)]
+#[diesel(table_name=custom_field)]
+#[typeshare::typeshare]
+pub struct CreateCustomField {
+	/// Field representing column `entity`
+	pub entity: String,
+	/// Field representing column `name`
+	pub name: String,
+	/// Field representing column `field_type`
+	pub field_type: String,
+	/// Field representing column `options`
+	#[serde(default = "empty_options", with = "option_list")]
+	#[schemars(with = "Vec<String>")]
+	#[typeshare(serialized_as = "Vec<String>")]
+	pub options: String,
+	/// Field representing column `required`
+	#[serde(default)]
+	pub required: bool,
+}
+
+/// Update Struct for a row in table `custom_field` for [`CustomField`]
+///
+/// Entity and type are fixed, changing them would invalidate the stored values
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, AsChangeset, PartialEq, Default)]
+#[diesel(table_name=custom_field)]
+#[typeshare::typeshare]
+pub struct UpdateCustomField {
+	/// Field representing column `name`
+	pub name: Option<String>,
+	/// Field representing column `options`
+	#[serde(default, with = "option_list::optional")]
+	#[schemars(with = "Option<Vec<String>>")]
+	#[typeshare(serialized_as = "Option<Vec<String>>")]
+	pub options: Option<String>,
+	/// Field representing column `required`
+	pub required: Option<bool>,
+}
+
+/// Create Struct for a row in table `custom_field_value`
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name=custom_field_value)]
+struct CreateCustomFieldValue {
+	custom_field_id: i32,
+	entity_id: i32,
+	value: String,
+}
+
+/// A row of `client`, `project` or `tracking` with its custom fields inlined into the JSON
+///
+/// typeshare can't express `flatten`, in TypeScript this is `T & { custom_fields: CustomFields }`
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(test, derive(PartialEq))]
+pub struct WithCustomFields<T> {
+	#[serde(flatten)]
+	pub item: T,
+	#[serde(default)]
+	pub custom_fields: CustomFields,
+}
+
+impl<T> Deref for WithCustomFields<T> {
+	type Target = T;
+
+	fn deref(&self) -> &Self::Target {
+		&self.item
+	}
+}
+
+/// Hours of all entries sharing the same value of a custom field
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[typeshare::typeshare]
+pub struct CustomFieldReport {
+	/// Value of the field, `None` for entries without a value
+	pub value: Option<Value>,
+	/// Number of tracking entries
+	#[typeshare(serialized_as = "U53")]
+	pub entries: usize,
+	pub performed: f64,
+	pub billed: f64,
+}
+
+/// Numbers are stored in a single form, so `4711.0` and `4711` are the same value
+fn number_text(number: &serde_json::Number) -> String {
+	match number.as_i64() {
+		Some(integer) => integer.to_string(),
+		None => number
+			.as_f64()
+			.map_or_else(|| number.to_string(), |float| float.to_string()),
+	}
+}
+
+/// Reject a field definition that values could never be checked against
+pub fn check_definition(entity: &str, field_type: &str, options: &str) -> crate::Result<()> {
+	if !ENTITIES.contains(&entity) {
+		return Err(Error::bad_request("custom_field.unsupported_entity").with_arg(entity));
+	}
+	if !TYPES.contains(&field_type) {
+		return Err(Error::bad_request("custom_field.unknown_type").with_arg(field_type));
+	}
+	let options = serde_json::from_str::<Vec<String>>(options)?;
+	if (field_type == TYPE_SELECT) == options.is_empty() {
+		return Err(Error::bad_request("custom_field.invalid_options"));
+	}
+	Ok(())
+}
+
+impl CustomField {
+	/// All fields defined on `client`, `project` or `tracking`
+	pub async fn all(db: &mut DbConnection, param_entity: &str) -> QueryResult<Vec<Self>> {
+		use crate::schema::custom_field::dsl::*;
+
+		trace!(
+			"Reading from custom_field table for entity: {}",
+			param_entity
+		);
+		custom_field
+			.filter(entity.eq(param_entity))
+			.order(name)
+			.load::<Self>(db)
+			.await
+	}
+
+	/// Allowed values of a `select` field
+	pub fn option_list(&self) -> Vec<String> {
+		serde_json::from_str(&self.options).unwrap_or_default()
+	}
+
+	/// Turn a JSON value into the text stored in `custom_field_value`, `None` removes the value
+	fn parse(&self, value: &Value) -> crate::Result<Option<String>> {
+		let invalid = || Error::bad_request("custom_field.invalid_value").with_arg(&self.name);
+		match (self.field_type.as_str(), value) {
+			(_, Value::Null) => Ok(None),
+			(TYPE_TEXT, Value::String(text)) => Ok(Some(text.clone())),
+			(TYPE_NUMBER, Value::Number(number)) => Ok(Some(number_text(number))),
+			(TYPE_DATE, Value::String(date)) => chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d")
+				.map(|_| Some(date.clone()))
+				.map_err(|_| invalid()),
+			(TYPE_SELECT, Value::String(option)) if self.option_list().contains(option) => {
+				Ok(Some(option.clone()))
+			}
+			_ => Err(invalid()),
+		}
+	}
+
+	/// Turn a stored value back into JSON, numbers are returned as JSON numbers
+	fn to_json(&self, value: String) -> Value {
+		if self.field_type == TYPE_NUMBER {
+			if let Ok(number) = serde_json::from_str::<Value>(&value) {
+				return number;
+			}
+		}
+		Value::String(value)
+	}
+
+	/// Whether a stored value equals a value given as filter in a query string
+	fn matches(&self, stored: &str, wanted: &str) -> bool {
+		if self.field_type == TYPE_NUMBER {
+			return matches!(
+				(stored.parse::<f64>(), wanted.parse::<f64>()),
+				(Ok(stored), Ok(wanted)) if stored == wanted
+			);
+		}
+		stored == wanted
+	}
+
+	/// Ids of the rows of `entity` whose field `field_id` equals `wanted`, for the filters of lists
+	///
+	/// Numbers match by value like in [`CustomField::report`], fields of other entities match
+	/// nothing
+	pub fn matching<'a>(
+		param_entity: &'a str,
+		field_id: i32,
+		wanted: &str,
+	) -> custom_field_value::BoxedQuery<'a, Backend, diesel::sql_types::Integer> {
+		let query = custom_field_value::table
+			.filter(
+				custom_field_value::custom_field_id.eq_any(
+					custom_field::table
+						.filter(custom_field::id.eq(field_id))
+						.filter(custom_field::entity.eq(param_entity))
+						.select(custom_field::id),
+				),
+			)
+			.select(custom_field_value::entity_id)
+			.into_boxed();
+		let stored = custom_field_value::value.eq(wanted.to_string());
+		match wanted.parse::<serde_json::Number>() {
+			Ok(number) if number_text(&number) != wanted => {
+				let number_fields = custom_field::table
+					.filter(custom_field::field_type.eq(TYPE_NUMBER))
+					.select(custom_field::id);
+				query.filter(
+					stored.or(
+						custom_field_value::value
+							.eq(number_text(&number))
+							.and(custom_field_value::custom_field_id.eq_any(number_fields)),
+					),
+				)
+			}
+			_ => query.filter(stored),
+		}
+	}
+
+	/// Check the custom fields given for a new or updated row of `entity`
+	///
+	/// Unknown fields, values of the wrong type and missing required fields are rejected
+	/// with [`Error::bad_request`], on update only the given fields are checked
+	pub async fn validate(
+		db: &mut Connection<DB>,
+		entity: &str,
+		values: &CustomFields,
+		create: bool,
+	) -> crate::Result<()> {
+		trace!("Validating custom fields of {}: {:?}", entity, values);
+		let fields = Self::all(db, entity).await?;
+		if let Some(unknown) = values
+			.keys()
+			.find(|key| !fields.iter().any(|field| &field.name == *key))
+		{
+			return Err(Error::bad_request("custom_field.unknown").with_arg(unknown));
+		}
+		for field in fields {
+			let value = match values.get(&field.name) {
+				Some(value) => field.parse(value)?,
+				None if create => None,
+				None => continue,
+			};
+			if field.required && value.is_none() {
+				return Err(Error::bad_request("custom_field.missing_value").with_arg(&field.name));
+			}
+		}
+		Ok(())
+	}
+
+	/// Custom fields of the given rows of `entity`, rows without values are missing in the map
+	pub async fn values(
+		db: &mut DbConnection,
+		entity: &str,
+		entity_ids: &[i32],
+	) -> QueryResult<HashMap<i32, CustomFields>> {
+		trace!("Reading custom fields of {} {:?}", entity, entity_ids);
+		let rows = custom_field_value::table
+			.inner_join(custom_field::table)
+			.filter(custom_field::entity.eq(entity))
+			.filter(custom_field_value::entity_id.eq_any(entity_ids))
+			.select((
+				custom_field_value::entity_id,
+				custom_field_value::value,
+				CustomField::as_select(),
+			))
+			.load::<(i32, String, CustomField)>(db)
+			.await?;
+		let mut values = HashMap::<i32, CustomFields>::new();
+		for (entity_id, value, field) in rows {
+			let value = field.to_json(value);
+			values
+				.entry(entity_id)
+				.or_default()
+				.insert(field.name, value);
+		}
+		Ok(values)
+	}
+
+	/// Store already validated custom fields of a row, fields that aren't given keep their value
+	pub async fn set_values(
+		db: &mut DbConnection,
+		actor: i32,
+		entity: &str,
+		entity_id: i32,
+		values: &CustomFields,
+	) -> QueryResult<CustomFields> {
+		trace!(
+			"Setting custom fields of {} {}: {:?}",
+			entity,
+			entity_id,
+			values
+		);
+		db.transaction(|conn| {
+			Box::pin(async move {
+				let old = Self::values(conn, entity, &[entity_id])
+					.await?
+					.remove(&entity_id)
+					.unwrap_or_default();
+				if values.is_empty() {
+					return Ok(old);
+				}
+				for field in Self::all(conn, entity).await? {
+					let Some(Ok(value)) = values.get(&field.name).map(|value| field.parse(value)) else {
+						continue;
+					};
+					diesel::delete(
+						custom_field_value::table
+							.filter(custom_field_value::custom_field_id.eq(field.id))
+							.filter(custom_field_value::entity_id.eq(entity_id)),
+					)
+					.execute(conn)
+					.await?;
+					if let Some(value) = value {
+						insert_into(custom_field_value::table)
+							.values(CreateCustomFieldValue {
+								custom_field_id: field.id,
+								entity_id,
+								value,
+							})
+							.execute(conn)
+							.await?;
+					}
+				}
+				let new = Self::values(conn, entity, &[entity_id])
+					.await?
+					.remove(&entity_id)
+					.unwrap_or_default();
+				Audit::log(
+					conn,
+					actor,
+					entity,
+					entity_id,
+					Action::Update,
+					Some(&BTreeMap::from([("custom_fields", &old)])),
+					Some(&BTreeMap::from([("custom_fields", &new)])),
+				)
+				.await?;
+				Ok(new)
+			})
+		})
+		.await
+	}
+
+	/// Remove the custom fields of a row that is deleted for good
+	pub async fn delete_values(
+		db: &mut DbConnection,
+		entity: &str,
+		entity_id: i32,
+	) -> QueryResult<usize> {
+		trace!("Deleting custom fields of {} {}", entity, entity_id);
+		diesel::delete(
+			custom_field_value::table
+				.filter(custom_field_value::entity_id.eq(entity_id))
+				.filter(
+					custom_field_value::custom_field_id.eq_any(
+						custom_field::table
+							.filter(custom_field::entity.eq(entity))
+							.select(custom_field::id),
+					),
+				),
+		)
+		.execute(db)
+		.await
+	}
+
+	/// Attach the custom fields to a single row of `entity`
+	pub async fn inline<T>(
+		db: &mut DbConnection,
+		entity: &str,
+		entity_id: i32,
+		item: T,
+	) -> QueryResult<WithCustomFields<T>> {
+		let custom_fields = Self::values(db, entity, &[entity_id])
+			.await?
+			.remove(&entity_id)
+			.unwrap_or_default();
+		Ok(WithCustomFields {
+			item,
+			custom_fields,
+		})
+	}
+
+	/// Attach the custom fields to every row of a page of `entity`
+	pub async fn inline_page<T>(
+		db: &mut DbConnection,
+		entity: &str,
+		page: PaginationResult<T>,
+		entity_id: impl Fn(&T) -> i32,
+	) -> QueryResult<PaginationResult<WithCustomFields<T>>> {
+		let ids = page.items.iter().map(&entity_id).collect::<Vec<_>>();
+		let mut values = Self::values(db, entity, &ids).await?;
+		Ok(PaginationResult {
+			items: page
+				.items
+				.into_iter()
+				.map(|item| WithCustomFields {
+					custom_fields: values.remove(&entity_id(&item)).unwrap_or_default(),
+					item,
+				})
+				.collect(),
+			total_items: page.total_items,
+			page: page.page,
+			page_size: page.page_size,
+			num_pages: page.num_pages,
+		})
+	}
+
+	/// Hours of the tracking entries between `from` and `to`, grouped by the value of a field
+	///
+	/// Only entries whose fields equal every value in `filter` are part of the report.
+	/// Fields of clients and projects apply to all entries booked on them
+	pub async fn report(
+		db: &mut Connection<DB>,
+		group_by: i32,
+		filter: &HashMap<i32, String>,
+		from: chrono::NaiveDate,
+		to: chrono::NaiveDate,
+	) -> crate::Result<Vec<CustomFieldReport>> {
+		trace!(
+			"Custom field report grouped by {} with {:?} from {} to {}",
+			group_by,
+			filter,
+			from,
+			to
+		);
+		let mut fields = HashMap::new();
+		for field_id in filter.keys().copied().chain([group_by]) {
+			let field = Self::read(db, field_id)
+				.await
+				.optional()?
+				.ok_or_else(|| Error::bad_request("custom_field.unknown").with_arg(field_id))?;
+			fields.insert(field_id, field);
+		}
+		// numbers match by value, so `4711.0` finds `4711`
+		let mut wanted_values = Vec::new();
+		for (field_id, wanted) in filter {
+			let field = &fields[field_id];
+			let values = if field.field_type == TYPE_NUMBER {
+				custom_field_value::table
+					.filter(custom_field_value::custom_field_id.eq(field.id))
+					.select(custom_field_value::value)
+					.distinct()
+					.load::<String>(db)
+					.await?
+					.into_iter()
+					.filter(|stored| field.matches(stored, wanted))
+					.collect()
+			} else {
+				vec![wanted.clone()]
+			};
+			wanted_values.push((field, values));
+		}
+
+		// the values of a field are stored by the id of the client, project or entry
+		macro_rules! report_query {
+			($entity_id:expr) => {{
+				let mut query = tracking::table
+					.left_join(
+						custom_field_value::table.on(
+							custom_field_value::entity_id
+								.eq($entity_id)
+								.and(custom_field_value::custom_field_id.eq(group_by)),
+						),
+					)
+					.filter(tracking::date.between(from, to))
+					.group_by(custom_field_value::value)
+					.select((
+						custom_field_value::value.nullable(),
+						dsl::count(tracking::id),
+						dsl::sum(tracking::performed),
+						dsl::sum(tracking::billed),
+					))
+					.into_boxed();
+				for (field, values) in &wanted_values {
+					// another name, the joined table is already `custom_field_value`
+					let filter_value = diesel::alias!(custom_field_value as filter_value);
+					let matching = filter_value
+						.filter(
+							filter_value
+								.field(custom_field_value::custom_field_id)
+								.eq(field.id),
+						)
+						.filter(filter_value.field(custom_field_value::value).eq_any(values))
+						.select(filter_value.field(custom_field_value::entity_id));
+					query = match field.entity.as_str() {
+						ENTITY_CLIENT => query.filter(tracking::client_id.eq_any(matching)),
+						ENTITY_PROJECT => query.filter(tracking::project_id.eq_any(matching)),
+						_ => query.filter(tracking::id.eq_any(matching)),
+					};
+				}
+				query
+					.load::<(Option<String>, i64, Option<f32>, Option<f32>)>(db)
+					.await?
+			}};
+		}
+		let group_field = &fields[&group_by];
+		let mut groups = match group_field.entity.as_str() {
+			ENTITY_CLIENT => report_query!(tracking::client_id),
+			ENTITY_PROJECT => report_query!(tracking::project_id),
+			_ => report_query!(tracking::id),
+		};
+		// entries without a value first, databases sort `NULL` differently
+		groups.sort_by(|(a, ..), (b, ..)| a.cmp(b));
+		Ok(
+			groups
+				.into_iter()
+				.map(|(value, entries, performed, billed)| CustomFieldReport {
+					value: value.map(|value| group_field.to_json(value)),
+					entries: entries as usize,
+					performed: performed.map_or(0.0, f64::from),
+					billed: billed.map_or(0.0, f64::from),
+				})
+				.collect(),
+		)
+	}
+}
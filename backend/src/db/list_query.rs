@@ -0,0 +1,174 @@
+use std::collections::HashMap;
+
+use rocket::FromForm;
+use schemars::JsonSchema;
+use serde::{
+	Deserialize,
+	Serialize,
+};
+
+use crate::Error;
+
+/// Sort, search and filter parameters shared by every list endpoint
+///
+/// `?sort=name,-created_at&q=acme&filter[archived]=false`, only the fields whitelisted with
+/// [`list_query!`] for the entity may be used in `sort` and `filter`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema, FromForm)]
+#[typeshare::typeshare]
+pub struct ListQuery {
+	/// Comma separated fields, a leading `-` sorts descending
+	pub sort: Option<String>,
+	/// Case insensitive search in the text fields of the entity
+	pub q: Option<String>,
+	/// Exact values of fields, custom fields of clients, projects and entries as `custom_field_<id>`
+	pub filter: HashMap<String, String>,
+}
+
+impl ListQuery {
+	/// Fields of `sort` together with whether they are sorted descending
+	pub fn sort_fields(&self) -> impl Iterator<Item = (&str, bool)> {
+		self
+			.sort
+			.iter()
+			.flat_map(|sort| sort.split(','))
+			.map(str::trim)
+			.filter(|field| !field.is_empty())
+			.map(|field| match field.strip_prefix('-') {
+				Some(field) => (field, true),
+				None => (field, false),
+			})
+	}
+
+	/// `LIKE` pattern for `q`, `None` if there is nothing to search for
+	pub fn search_pattern(&self) -> Option<String> {
+		let q = self.q.as_deref().map(str::trim).unwrap_or_default();
+		if q.is_empty() {
+			return None;
+		}
+		Some(format!(
+			"%{}%",
+			q.replace('\\', "\\\\")
+				.replace('%', "\\%")
+				.replace('_', "\\_")
+		))
+	}
+}
+
+pub(crate) fn unknown_field(parameter: &str, field: &str) -> Error {
+	Error::bad_request("list.unknown_field")
+		.with_arg(parameter)
+		.with_arg(field)
+}
+
+pub(crate) fn invalid_value(field: &str, value: &str) -> Error {
+	Error::bad_request("list.invalid_value")
+		.with_arg(field)
+		.with_arg(value)
+}
+
+/// Generates `list_filter` and `list_sort` for a table, applying a [`ListQuery`] to its boxed queries
+///
+/// `search` lists the text columns `q` is looked up in, `fields` the columns that may be
+/// sorted and filtered by together with the type their filter value is parsed into.
+/// Sorting always ends with `id`, so pages stay stable. Tables with `custom_fields` can also be
+/// filtered by the custom fields of that entity, with keys starting with
+/// [`FILTER_PREFIX`](crate::db::custom_field::FILTER_PREFIX).
+///
+/// ```ignore
+/// list_query!(client, search: [name], fields: { id: i32, name: String, archived: bool });
+/// list_query!(project, search: [name], fields: { id: i32, name: String }, custom_fields: ENTITY_PROJECT);
+/// ```
+macro_rules! list_query {
+	(
+		$table:ident,
+		search: [$($search:ident),+ $(,)?],
+		fields: { $($field:ident: $type:ty),+ $(,)? }
+		$(, custom_fields: $entity:expr)? $(,)?
+	) => {
+		/// Applies the search and the filters of a [`ListQuery`](crate::db::ListQuery)
+		fn list_filter<'a>(
+			mut query: crate::schema::$table::BoxedQuery<'a, crate::db::Backend>,
+			list: &crate::db::ListQuery,
+		) -> crate::Result<crate::schema::$table::BoxedQuery<'a, crate::db::Backend>> {
+			use diesel::{
+				sql_types::{
+					Bool,
+					Nullable,
+				},
+				BoxableExpression,
+			};
+
+			use crate::schema::$table::dsl::*;
+
+			if let Some(list_pattern) = list.search_pattern() {
+				let mut list_search: Option<
+					Box<dyn BoxableExpression<$table, crate::db::Backend, SqlType = Nullable<Bool>>>,
+				> = None;
+				$(
+					#[cfg(feature = "mysql")]
+					let list_matches = $search.like(list_pattern.clone()).nullable();
+					#[cfg(feature = "postgres")]
+					let list_matches = $search.ilike(list_pattern.clone()).nullable();
+					// SQLite has no default escape character
+					#[cfg(feature = "sqlite")]
+					let list_matches = $search.like(list_pattern.clone()).escape('\\').nullable();
+					list_search = Some(match list_search {
+						Some(list_search) => Box::new(list_search.or(list_matches)),
+						None => Box::new(list_matches),
+					});
+				)+
+				if let Some(list_search) = list_search {
+					query = query.filter(list_search);
+				}
+			}
+			// bindings are prefixed, the columns of the table are in scope
+			for (list_field, list_value) in &list.filter {
+				$(
+					let list_custom_field = list_field
+						.strip_prefix(crate::db::custom_field::FILTER_PREFIX)
+						.and_then(|list_custom_field| list_custom_field.parse::<i32>().ok());
+					if let Some(list_custom_field) = list_custom_field {
+						query = query.filter(id.eq_any(crate::db::custom_field::CustomField::matching(
+							$entity,
+							list_custom_field,
+							list_value,
+						)));
+						continue;
+					}
+				)?
+				query = match list_field.as_str() {
+					$(
+						stringify!($field) => query.filter(
+							$field.eq(
+								list_value
+									.parse::<$type>()
+									.map_err(|_| crate::db::list_query::invalid_value(list_field, list_value))?,
+							),
+						),
+					)+
+					_ => return Err(crate::db::list_query::unknown_field("filter", list_field)),
+				};
+			}
+			Ok(query)
+		}
+
+		/// Applies the order of a [`ListQuery`](crate::db::ListQuery)
+		fn list_sort<'a>(
+			mut query: crate::schema::$table::BoxedQuery<'a, crate::db::Backend>,
+			list: &crate::db::ListQuery,
+		) -> crate::Result<crate::schema::$table::BoxedQuery<'a, crate::db::Backend>> {
+			use crate::schema::$table::dsl::*;
+
+			for (list_field, list_descending) in list.sort_fields() {
+				query = match (list_field, list_descending) {
+					$(
+						(stringify!($field), true) => query.then_order_by($field.desc()),
+						(stringify!($field), false) => query.then_order_by($field.asc()),
+					)+
+					_ => return Err(crate::db::list_query::unknown_field("sort", list_field)),
+				};
+			}
+			Ok(query.then_order_by(id.asc()))
+		}
+	};
+}
@@ -2,56 +2,81 @@ use rocket::{
 	Build,
 	Rocket,
 };
-use rocket_db_pools::{
-	diesel::{
-		prelude::*,
-		MysqlPool,
-	},
-	Database,
-};
+use rocket_db_pools::Database;
+use schemars::JsonSchema;
 use serde::{
 	Deserialize,
 	Serialize,
 };
 use tracing::trace;
 
+use self::prelude::*;
+
+#[macro_use]
+mod backend;
+#[macro_use]
+mod list_query;
+#[macro_use]
+mod crud;
+
 pub mod activity;
+pub mod audit;
+pub mod billing;
 pub mod client;
+pub mod client_contact;
+pub mod cursor;
+pub mod custom_field;
 pub mod project;
+pub mod project_activity;
+pub mod project_budget;
+pub mod project_member;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+pub mod tag;
+pub mod team;
 pub mod tracking;
 pub mod user;
 // pub mod helper;
 
+#[cfg(feature = "mysql")]
+pub use backend::last_insert_id;
+pub use backend::{
+	prelude,
+	Backend,
+	DbConnection,
+	SyncConnection,
+	MIGRATIONS,
+};
+pub use list_query::ListQuery;
+
 /// Result of a `.paginate` function
-#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[typeshare::typeshare]
 pub struct PaginationResult<T> {
 	/// Resulting items that are from the current page
 	pub items: Vec<T>,
 	/// The count of total items there are
+	#[typeshare(serialized_as = "I54")]
 	pub total_items: i64,
 	/// Current page, 0-based index
+	#[typeshare(serialized_as = "I54")]
 	pub page: i64,
 	/// Size of a page
+	#[typeshare(serialized_as = "I54")]
 	pub page_size: i64,
 	/// Number of total possible pages, given the `page_size` and `total_items`
+	#[typeshare(serialized_as = "I54")]
 	pub num_pages: i64,
 }
 
 #[derive(Database)]
 #[database("zeiterfassung")]
-pub struct DB(MysqlPool);
-
-diesel::sql_function!(fn last_insert_id() -> Integer);
+pub struct DB(backend::Pool);
 
 pub async fn run_migrations(rocket: Rocket<Build>) -> Rocket<Build> {
-	use diesel_migrations::{
-		embed_migrations,
-		EmbeddedMigrations,
-		MigrationHarness,
-	};
+	use diesel_migrations::MigrationHarness;
 
 	trace!("Running migrations");
-	const MIGRATIONS: EmbeddedMigrations = embed_migrations!("../migrations");
 
 	let db_url: String = rocket
 		.figment()
@@ -59,7 +84,7 @@ pub async fn run_migrations(rocket: Rocket<Build>) -> Rocket<Build> {
 		.expect("DB not configured");
 
 	rocket::tokio::task::spawn_blocking(move || {
-		diesel::MysqlConnection::establish(&db_url)
+		SyncConnection::establish(&db_url)
 			.expect("No database")
 			.run_pending_migrations(MIGRATIONS)
 			.expect("Invalid migrations");
@@ -69,3 +94,32 @@ pub async fn run_migrations(rocket: Rocket<Build>) -> Rocket<Build> {
 
 	rocket
 }
+
+/// Versions of the embedded [`MIGRATIONS`] that have not been applied to the database yet
+pub async fn pending_migrations(
+	db: &mut rocket_db_pools::Connection<DB>,
+) -> QueryResult<Vec<String>> {
+	use diesel::migration::MigrationSource;
+
+	// bookkeeping table of diesel's migration harness
+	diesel::table! {
+		__diesel_schema_migrations (version) {
+			version -> VarChar,
+		}
+	}
+
+	trace!("Checking for pending migrations");
+	let applied = __diesel_schema_migrations::table
+		.select(__diesel_schema_migrations::version)
+		.load::<String>(db)
+		.await?;
+	let embedded = MigrationSource::<Backend>::migrations(&MIGRATIONS)
+		.map_err(diesel::result::Error::QueryBuilderError)?;
+	Ok(
+		embedded
+			.iter()
+			.map(|migration| migration.name().version().to_string())
+			.filter(|version| !applied.contains(version))
+			.collect(),
+	)
+}
@@ -1,34 +1,65 @@
+use diesel::dsl::now;
 #[cfg(test)]
 use fake::{
 	faker::company::en::*,
 	Dummy,
 	Fake,
 };
-use rocket_db_pools::{
-	diesel::{
-		insert_into,
-		prelude::*,
-	},
-	Connection,
-};
+use rocket_db_pools::Connection;
 use tracing::trace;
 
 use super::{
+	audit::{
+		Action,
+		Audit,
+	},
 	client::Client,
-	last_insert_id,
+	custom_field::{
+		CustomField,
+		ENTITY_PROJECT,
+	},
+	Backend,
+	DbConnection,
+	ListQuery,
 	PaginationResult,
 };
 use crate::{
+	db::prelude::*,
 	schema::*,
+	Result,
 	DB,
 };
 
+list_query!(
+	project,
+	search: [name],
+	fields: {
+		id: i32,
+		client_id: i32,
+		name: String,
+		archived: bool,
+		start_date: chrono::NaiveDate,
+		end_date: chrono::NaiveDate,
+		created_at: chrono::NaiveDateTime,
+		updated_at: chrono::NaiveDateTime,
+	},
+	custom_fields: ENTITY_PROJECT,
+);
+
+crud!(project, Project, CreateProject, UpdateProject, soft_delete, on_purge: purge_custom_fields);
+
+/// The custom field values of a project go with it when it is purged
+async fn purge_custom_fields(conn: &mut DbConnection, param_id: i32) -> QueryResult<usize> {
+	CustomField::delete_values(conn, ENTITY_PROJECT, param_id).await
+}
+
 /// Struct representing a row in table `project`
 #[derive(
 	Debug,
 	Clone,
 	serde::Serialize,
 	serde::Deserialize,
+	schemars::JsonSchema,
 	Queryable,
 	Selectable,
 	QueryableByName,
@@ -37,6 +68,7 @@ use crate::{
 )]
 #[diesel(table_name=project, primary_key(id), belongs_to(Client, foreign_key=client_id))]
 #[cfg_attr(test, derive(PartialEq))]
+#[typeshare::typeshare]
 pub struct Project {
 	/// Field representing column `id`
 	pub id: i32,
@@ -48,12 +80,21 @@ pub struct Project {
 	pub created_at: chrono::NaiveDateTime,
 	/// Field representing column `updated_at`
 	pub updated_at: chrono::NaiveDateTime,
+	/// Field representing column `deleted_at`
+	pub deleted_at: Option<chrono::NaiveDateTime>,
+	/// Field representing column `archived`
+	pub archived: bool,
+	/// Field representing column `start_date`
+	pub start_date: Option<chrono::NaiveDate>,
+	/// Field representing column `end_date`
+	pub end_date: Option<chrono::NaiveDate>,
 }
 
 /// Create Struct for a row in table `project` for [`Project`]
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, Insertable)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, schemars::JsonSchema, Insertable)]
 #[diesel(table_name=project)]
 #[cfg_attr(test, derive(Dummy))]
+#[typeshare::typeshare]
 pub struct CreateProject {
 	/// Field representing column `client_id`
 	pub client_id: i32,
@@ -66,14 +107,46 @@ pub struct CreateProject {
 		)
 	)]
 	pub name: String,
+	/// Field representing column `start_date`
+	#[cfg_attr(test, dummy(default))]
+	pub start_date: Option<chrono::NaiveDate>,
+	/// Field representing column `end_date`
+	#[cfg_attr(test, dummy(default))]
+	pub end_date: Option<chrono::NaiveDate>,
 }
 
 /// Update Struct for a row in table `project` for [`Project`]
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, AsChangeset, PartialEq, Default)]
+#[derive(
+	Debug,
+	Clone,
+	serde::Serialize,
+	serde::Deserialize,
+	schemars::JsonSchema,
+	AsChangeset,
+	PartialEq,
+	Default,
+)]
 #[diesel(table_name=project)]
+#[typeshare::typeshare]
 pub struct UpdateProject {
 	/// Field representing column `name`
 	pub name: Option<String>,
+	/// Field representing column `archived`
+	pub archived: Option<bool>,
+	/// Field representing column `start_date`
+	#[serde(
+		default,
+		skip_serializing_if = "Option::is_none",
+		deserialize_with = "serde_with::rust::double_option::deserialize"
+	)]
+	pub start_date: Option<Option<chrono::NaiveDate>>,
+	/// Field representing column `end_date`
+	#[serde(
+		default,
+		skip_serializing_if = "Option::is_none",
+		deserialize_with = "serde_with::rust::double_option::deserialize"
+	)]
+	pub end_date: Option<Option<chrono::NaiveDate>>,
 	/// Field representing column `created_at`
 	pub created_at: Option<chrono::NaiveDateTime>,
 	/// Field representing column `updated_at`
@@ -81,105 +154,207 @@ pub struct UpdateProject {
 }
 
 impl Project {
-	/// Insert a new row into `project` with a given [`CreateProject`]
-	pub async fn create(db: &mut Connection<DB>, item: &CreateProject) -> QueryResult<Self> {
-		use crate::schema::project::dsl::*;
-
-		trace!("Inserting into project table: {:?}", item);
-		db.transaction(|mut conn| {
-			Box::pin(async move {
-				insert_into(project).values(item).execute(&mut conn).await?;
-				project
-					.select(Project::as_select())
-					.filter(id.eq(last_insert_id()))
-					.first::<Self>(&mut conn)
-					.await
-			})
-		})
-		.await
-	}
-
-	/// Get a row from `project`, identified by the primary key
-	pub async fn read(db: &mut Connection<DB>, param_id: i32) -> QueryResult<Self> {
-		use crate::schema::project::dsl::*;
-
-		trace!("Reading from project table: {:?}", param_id);
-		project.filter(id.eq(param_id)).first::<Self>(db).await
-	}
-
 	/// Paginates through the table where page is a 0-based index (i.e. page 0 is the first page)
+	///
+	/// Soft deleted rows are skipped, archived rows only show up with `include_archived`
 	pub async fn paginate(
 		db: &mut Connection<DB>,
 		page: i64,
 		page_size: i64,
-	) -> QueryResult<PaginationResult<Self>> {
-		use crate::schema::project::dsl::*;
-
+		include_archived: bool,
+		list: &ListQuery,
+	) -> Result<PaginationResult<Self>> {
 		trace!(
-			"Paginating through project table: page {}, page_size {}",
+			"Paginating through project table: page {}, page_size {}, include_archived {}, {:?}",
 			page,
-			page_size
+			page_size,
+			include_archived,
+			list
 		);
-		let page_size = if page_size < 1 { 1 } else { page_size };
-		let total_items = project.count().get_result(db).await?;
-		let items = project
-			.limit(page_size)
-			.offset(page * page_size)
-			.load::<Self>(db)
-			.await?;
-
-		Ok(PaginationResult {
-			items,
-			total_items,
+		Self::paginate_query(
+			db,
+			|| Self::listed(include_archived, list),
+			list,
 			page,
 			page_size,
-			/* ceiling division of integers */
-			num_pages: total_items / page_size + i64::from(total_items % page_size != 0),
-		})
+		)
+		.await
 	}
 
-	/// Update a row in `project`, identified by the primary key with [`UpdateProject`]
-	pub async fn update(
+	/// Rows that are listed, narrowed down by `list`
+	fn listed<'a>(
+		include_archived: bool,
+		list: &ListQuery,
+	) -> Result<project::BoxedQuery<'a, Backend>> {
+		use crate::schema::project::dsl::*;
+
+		let mut query = project.filter(deleted_at.is_null()).into_boxed();
+		if !include_archived {
+			query = query.filter(archived.eq(false));
+		}
+		list_filter(query, list)
+	}
+
+	/// Move every reference of `param_id` over to `target` and soft delete `param_id` afterwards
+	///
+	/// Members and whitelisted activities the target already has are dropped instead of
+	/// duplicated. The whitelist is only carried over if the target restricts its
+	/// activities itself. Everything happens in one transaction.
+	pub async fn merge_into(
 		db: &mut Connection<DB>,
+		actor: i32,
 		param_id: i32,
-		item: &UpdateProject,
+		target: i32,
 	) -> QueryResult<Self> {
 		use crate::schema::project::dsl::*;
 
-		trace!("Updating project table: {} with {:?}", param_id, item);
+		trace!("Merging project {} into {}", param_id, target);
 		db.transaction(|mut conn| {
 			Box::pin(async move {
+				let old = project
+					.filter(id.eq(param_id))
+					.filter(deleted_at.is_null())
+					.first::<Self>(&mut conn)
+					.await?;
+				let new_target = project
+					.filter(id.eq(target))
+					.filter(deleted_at.is_null())
+					.first::<Self>(&mut conn)
+					.await?;
+
+				diesel::update(tracking::table.filter(tracking::project_id.eq(param_id)))
+					.set(tracking::project_id.eq(target))
+					.execute(&mut conn)
+					.await?;
+				diesel::update(project_budget::table.filter(project_budget::project_id.eq(param_id)))
+					.set(project_budget::project_id.eq(target))
+					.execute(&mut conn)
+					.await?;
+
+				let target_members = project_member::table
+					.filter(project_member::project_id.eq(target))
+					.select(project_member::user_id)
+					.load::<i32>(&mut conn)
+					.await?;
+				diesel::delete(
+					project_member::table
+						.filter(project_member::project_id.eq(param_id))
+						.filter(project_member::user_id.eq_any(target_members)),
+				)
+				.execute(&mut conn)
+				.await?;
+				diesel::update(project_member::table.filter(project_member::project_id.eq(param_id)))
+					.set(project_member::project_id.eq(target))
+					.execute(&mut conn)
+					.await?;
+
+				let target_activities = project_activity::table
+					.filter(project_activity::project_id.eq(target))
+					.select(project_activity::activity_id)
+					.load::<i32>(&mut conn)
+					.await?;
+				if target_activities.is_empty() {
+					// the target allows every activity, keep it that way
+					diesel::delete(project_activity::table.filter(project_activity::project_id.eq(param_id)))
+						.execute(&mut conn)
+						.await?;
+				} else {
+					diesel::delete(
+						project_activity::table
+							.filter(project_activity::project_id.eq(param_id))
+							.filter(project_activity::activity_id.eq_any(target_activities)),
+					)
+					.execute(&mut conn)
+					.await?;
+					diesel::update(project_activity::table.filter(project_activity::project_id.eq(param_id)))
+						.set(project_activity::project_id.eq(target))
+						.execute(&mut conn)
+						.await?;
+				}
+
 				diesel::update(project.filter(id.eq(param_id)))
-					.set(item)
+					.set(deleted_at.eq(now.nullable()))
 					.execute(&mut conn)
 					.await?;
-				project
+				let new = project
 					.filter(id.eq(param_id))
 					.first::<Self>(&mut conn)
-					.await
+					.await?;
+				Audit::log(
+					conn,
+					actor,
+					"project",
+					param_id,
+					Action::Merge,
+					Some(&old),
+					Some(&new_target),
+				)
+				.await?;
+				Audit::log(
+					conn,
+					actor,
+					"project",
+					param_id,
+					Action::Delete,
+					Some(&old),
+					Some(&new),
+				)
+				.await?;
+				Ok(new_target)
 			})
 		})
 		.await
 	}
 
-	/// Delete a row in `project`, identified by the primary key
-	pub async fn delete(db: &mut Connection<DB>, param_id: i32) -> QueryResult<usize> {
-		use crate::schema::project::dsl::*;
-
-		trace!("Deleting from project table: {:?}", param_id);
-		diesel::delete(project.filter(id.eq(param_id)))
-			.execute(db)
-			.await
+	/// Count the rows of other tables that still reference a row in `project`
+	///
+	/// Only tables with at least one reference are returned
+	pub async fn references(
+		db: &mut Connection<DB>,
+		param_id: i32,
+	) -> QueryResult<Vec<(&'static str, i64)>> {
+		trace!("Counting references to project: {}", param_id);
+		let tracking: i64 = tracking::table
+			.filter(tracking::project_id.eq(param_id))
+			.count()
+			.get_result(db)
+			.await?;
+		let project_budget: i64 = project_budget::table
+			.filter(project_budget::project_id.eq(param_id))
+			.count()
+			.get_result(db)
+			.await?;
+		let project_member: i64 = project_member::table
+			.filter(project_member::project_id.eq(param_id))
+			.count()
+			.get_result(db)
+			.await?;
+		let project_activity: i64 = project_activity::table
+			.filter(project_activity::project_id.eq(param_id))
+			.count()
+			.get_result(db)
+			.await?;
+		Ok(
+			[
+				("tracking", tracking),
+				("project_budget", project_budget),
+				("project_member", project_member),
+				("project_activity", project_activity),
+			]
+			.into_iter()
+			.filter(|(_, count)| *count > 0)
+			.collect(),
+		)
 	}
 
-	pub async fn last_page(db: &mut Connection<DB>, page_size: i64) -> QueryResult<i64> {
-		use crate::schema::project::dsl::*;
-
-		trace!("Getting last page of project table for page_size {page_size}");
-
-		let total_items: i64 = project.count().get_result(db).await?;
-		// index starts at 0
-		Ok((total_items / page_size + i64::from(total_items % page_size != 0)) - 1)
+	pub async fn last_page(
+		db: &mut Connection<DB>,
+		page_size: i64,
+		include_archived: bool,
+		list: &ListQuery,
+	) -> Result<i64> {
+		let query = Self::listed(include_archived, list)?;
+		Self::last_page_query(db, query, page_size).await
 	}
 }
 
@@ -0,0 +1,141 @@
+use diesel::insert_into;
+use rocket_db_pools::Connection;
+use schemars::JsonSchema;
+use serde::{
+	Deserialize,
+	Serialize,
+};
+use tracing::trace;
+
+use super::{
+	activity::Activity,
+	audit::{
+		Action,
+		Audit,
+	},
+	project::Project,
+};
+use crate::{
+	db::prelude::*,
+	schema::*,
+	DB,
+};
+
+/// Struct representing a row in table `project_activity`
+#[derive(
+	Debug,
+	Clone,
+	Serialize,
+	Deserialize,
+	JsonSchema,
+	Queryable,
+	Selectable,
+	QueryableByName,
+	Associations,
+	Identifiable,
+)]
+#[diesel(table_name=project_activity, primary_key(id), belongs_to(Project, foreign_key=project_id), belongs_to(Activity, foreign_key=activity_id))]
+pub struct ProjectActivity {
+	/// Field representing column `id`
+	pub id: i32,
+	/// Field representing column `project_id`
+	pub project_id: i32,
+	/// Field representing column `activity_id`
+	pub activity_id: i32,
+	/// Field representing column `created_at`
+	pub created_at: chrono::NaiveDateTime,
+}
+
+/// Create Struct for a row in table `project_activity` for [`ProjectActivity`]
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name=project_activity)]
+struct CreateProjectActivity {
+	project_id: i32,
+	activity_id: i32,
+}
+
+impl ProjectActivity {
+	/// Ids of the activities that may be booked on a project
+	///
+	/// An empty list means the project has no whitelist and every activity is allowed
+	pub async fn allowed(db: &mut Connection<DB>, param_project_id: i32) -> QueryResult<Vec<i32>> {
+		use crate::schema::project_activity::dsl::*;
+
+		trace!(
+			"Reading from project_activity table for project: {}",
+			param_project_id
+		);
+		project_activity
+			.filter(project_id.eq(param_project_id))
+			.select(activity_id)
+			.order(activity_id)
+			.load::<i32>(db)
+			.await
+	}
+
+	/// Replace the whitelist of a project, an empty list allows every activity again
+	pub async fn set(
+		db: &mut Connection<DB>,
+		actor: i32,
+		param_project_id: i32,
+		activity_ids: &[i32],
+	) -> QueryResult<Vec<i32>> {
+		use crate::schema::project_activity::dsl::*;
+
+		trace!(
+			"Setting activities of project {}: {:?}",
+			param_project_id,
+			activity_ids
+		);
+		let mut new = activity_ids.to_vec();
+		new.sort_unstable();
+		new.dedup();
+		db.transaction(|mut conn| {
+			Box::pin(async move {
+				let old = project_activity
+					.filter(project_id.eq(param_project_id))
+					.select(activity_id)
+					.order(activity_id)
+					.load::<i32>(&mut conn)
+					.await?;
+				diesel::delete(project_activity.filter(project_id.eq(param_project_id)))
+					.execute(&mut conn)
+					.await?;
+				let items = new
+					.iter()
+					.map(|param_activity_id| CreateProjectActivity {
+						project_id: param_project_id,
+						activity_id: *param_activity_id,
+					})
+					.collect::<Vec<_>>();
+				#[cfg(not(feature = "sqlite"))]
+				if !items.is_empty() {
+					insert_into(project_activity)
+						.values(&items)
+						.execute(&mut conn)
+						.await?;
+				}
+				// diesel only splits a batch insert into single rows for its sync SQLite connection
+				#[cfg(feature = "sqlite")]
+				for item in &items {
+					insert_into(project_activity)
+						.values(item)
+						.execute(&mut conn)
+						.await?;
+				}
+				Audit::log(
+					conn,
+					actor,
+					"project_activity",
+					param_project_id,
+					Action::Update,
+					Some(&old),
+					Some(&new),
+				)
+				.await?;
+				Ok(new)
+			})
+		})
+		.await
+	}
+}
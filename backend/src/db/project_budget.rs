@@ -0,0 +1,454 @@
+use std::str::FromStr;
+
+use bigdecimal::{
+	BigDecimal,
+	FromPrimitive,
+	ParseBigDecimalError,
+	ToPrimitive,
+};
+#[cfg(not(feature = "sqlite"))]
+use diesel::sql_types::{
+	Json,
+	Numeric,
+};
+use diesel::{
+	deserialize::{
+		self,
+		FromSql,
+		FromSqlRow,
+	},
+	dsl::not,
+	expression::AsExpression,
+	serialize::{
+		self,
+		Output,
+		ToSql,
+	},
+};
+#[cfg(feature = "sqlite")]
+use diesel::{
+	serialize::IsNull,
+	sql_types::Text,
+};
+use rocket::serde::json::{
+	serde_json,
+	Value,
+};
+use rocket_db_pools::Connection;
+use schemars::JsonSchema;
+use serde::{
+	Deserialize,
+	Serialize,
+};
+use tracing::trace;
+
+#[cfg(feature = "sqlite")]
+use super::sqlite::sql_types::{
+	Json,
+	Numeric,
+};
+use super::{
+	activity::Activity,
+	billing::not_billed,
+	project::Project,
+	Backend,
+};
+use crate::{
+	db::prelude::*,
+	schema::*,
+	Error,
+	Result,
+	DB,
+};
+
+/// Budget is measured in billed hours
+pub const UNIT_HOURS: &str = "hours";
+/// Budget is measured in billed hours times `hourly_rate`
+pub const UNIT_MONEY: &str = "money";
+
+crud!(
+	project_budget,
+	ProjectBudget,
+	CreateProjectBudget,
+	UpdateProjectBudget,
+	audited
+);
+
+/// Struct representing a row in table `project_budget`
+#[derive(
+	Debug,
+	Clone,
+	Serialize,
+	Deserialize,
+	JsonSchema,
+	Queryable,
+	Selectable,
+	QueryableByName,
+	Associations,
+	Identifiable,
+)]
+#[diesel(table_name=project_budget, primary_key(id), belongs_to(Project, foreign_key=project_id), belongs_to(Activity, foreign_key=activity_id))]
+#[cfg_attr(test, derive(PartialEq))]
+#[typeshare::typeshare]
+pub struct ProjectBudget {
+	/// Field representing column `id`
+	pub id: i32,
+	/// Field representing column `project_id`
+	pub project_id: i32,
+	/// Field representing column `activity_id`, the budget covers the whole project if `None`
+	pub activity_id: Option<i32>,
+	/// Field representing column `unit`, either [`UNIT_HOURS`] or [`UNIT_MONEY`]
+	pub unit: String,
+	/// Field representing column `amount`, hours or money with two decimal places
+	#[typeshare(serialized_as = "String")]
+	pub amount: Decimal,
+	/// Field representing column `hourly_rate`, required for [`UNIT_MONEY`]
+	#[typeshare(serialized_as = "Option<String>")]
+	pub hourly_rate: Option<Decimal>,
+	/// Field representing column `thresholds`, percentages e.g. `[80, 100]`
+	pub thresholds: Thresholds,
+	/// Field representing column `hard_stop`, rejects new tracking entries once the budget is used up
+	pub hard_stop: bool,
+	/// Field representing column `created_at`
+	pub created_at: chrono::NaiveDateTime,
+	/// Field representing column `updated_at`
+	pub updated_at: chrono::NaiveDateTime,
+}
+
+/// Create Struct for a row in table `project_budget` for [`ProjectBudget`]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Insertable)]
+#[diesel(table_name=project_budget)]
+#[typeshare::typeshare]
+pub struct CreateProjectBudget {
+	/// Field representing column `project_id`, taken from the url
+	#[serde(skip_deserializing)]
+	pub project_id: i32,
+	/// Field representing column `activity_id`
+	pub activity_id: Option<i32>,
+	/// Field representing column `unit`
+	#[serde(default = "default_unit")]
+	pub unit: String,
+	/// Field representing column `amount`
+	#[typeshare(serialized_as = "String")]
+	pub amount: Decimal,
+	/// Field representing column `hourly_rate`
+	#[typeshare(serialized_as = "Option<String>")]
+	pub hourly_rate: Option<Decimal>,
+	/// Field representing column `thresholds`
+	#[serde(default)]
+	pub thresholds: Thresholds,
+	/// Field representing column `hard_stop`
+	#[serde(default)]
+	pub hard_stop: bool,
+}
+
+fn default_unit() -> String {
+	UNIT_HOURS.to_string()
+}
+
+/// Percentages of a budget that raise an alert once they are reached, a JSON array in the database
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, AsExpression, FromSqlRow)]
+#[diesel(sql_type = Json)]
+#[serde(transparent)]
+#[typeshare::typeshare]
+pub struct Thresholds(pub Vec<f64>);
+
+impl Default for Thresholds {
+	fn default() -> Self {
+		Self(vec![80.0, 100.0])
+	}
+}
+
+impl FromSql<Json, Backend> for Thresholds {
+	fn from_sql(
+		bytes: <Backend as diesel::backend::Backend>::RawValue<'_>,
+	) -> deserialize::Result<Self> {
+		let value = <Value as FromSql<Json, Backend>>::from_sql(bytes)?;
+		Ok(serde_json::from_value(value)?)
+	}
+}
+
+#[cfg(not(feature = "sqlite"))]
+impl ToSql<Json, Backend> for Thresholds {
+	fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Backend>) -> serialize::Result {
+		let value = serde_json::to_value(&self.0)?;
+		<Value as ToSql<Json, Backend>>::to_sql(&value, &mut out.reborrow())
+	}
+}
+
+/// SQLite takes the text of the JSON array as an owned value
+#[cfg(feature = "sqlite")]
+impl ToSql<Json, Backend> for Thresholds {
+	fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Backend>) -> serialize::Result {
+		out.set_value(serde_json::to_string(&self.0)?);
+		Ok(IsNull::No)
+	}
+}
+
+/// An exact number like `1000.10`, a decimal column in the database
+#[derive(
+	Debug,
+	Clone,
+	PartialEq,
+	Eq,
+	PartialOrd,
+	Serialize,
+	Deserialize,
+	JsonSchema,
+	AsExpression,
+	FromSqlRow,
+)]
+#[diesel(sql_type = Numeric)]
+#[serde(transparent)]
+pub struct Decimal(pub BigDecimal);
+
+impl FromStr for Decimal {
+	type Err = ParseBigDecimalError;
+
+	fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+		s.parse().map(Self)
+	}
+}
+
+#[cfg(not(feature = "sqlite"))]
+impl FromSql<Numeric, Backend> for Decimal {
+	fn from_sql(
+		bytes: <Backend as diesel::backend::Backend>::RawValue<'_>,
+	) -> deserialize::Result<Self> {
+		<BigDecimal as FromSql<Numeric, Backend>>::from_sql(bytes).map(Self)
+	}
+}
+
+#[cfg(not(feature = "sqlite"))]
+impl ToSql<Numeric, Backend> for Decimal {
+	fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Backend>) -> serialize::Result {
+		<BigDecimal as ToSql<Numeric, Backend>>::to_sql(&self.0, out)
+	}
+}
+
+/// SQLite has no decimal type, the column holds the number as text to keep its scale
+#[cfg(feature = "sqlite")]
+impl FromSql<Numeric, Backend> for Decimal {
+	fn from_sql(
+		bytes: <Backend as diesel::backend::Backend>::RawValue<'_>,
+	) -> deserialize::Result<Self> {
+		let text = <String as FromSql<Text, Backend>>::from_sql(bytes)?;
+		Ok(text.parse()?)
+	}
+}
+
+#[cfg(feature = "sqlite")]
+impl ToSql<Numeric, Backend> for Decimal {
+	fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Backend>) -> serialize::Result {
+		out.set_value(self.0.to_string());
+		Ok(IsNull::No)
+	}
+}
+
+/// Update Struct for a row in table `project_budget` for [`ProjectBudget`]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, AsChangeset, PartialEq, Default)]
+#[diesel(table_name=project_budget)]
+#[typeshare::typeshare]
+pub struct UpdateProjectBudget {
+	/// Field representing column `activity_id`
+	#[serde(
+		default,
+		skip_serializing_if = "Option::is_none",
+		deserialize_with = "serde_with::rust::double_option::deserialize"
+	)]
+	pub activity_id: Option<Option<i32>>,
+	/// Field representing column `unit`
+	pub unit: Option<String>,
+	/// Field representing column `amount`
+	#[typeshare(serialized_as = "Option<String>")]
+	pub amount: Option<Decimal>,
+	/// Field representing column `hourly_rate`
+	#[serde(
+		default,
+		skip_serializing_if = "Option::is_none",
+		deserialize_with = "serde_with::rust::double_option::deserialize"
+	)]
+	#[typeshare(serialized_as = "Option<String>")]
+	pub hourly_rate: Option<Option<Decimal>>,
+	/// Field representing column `thresholds`
+	pub thresholds: Option<Thresholds>,
+	/// Field representing column `hard_stop`
+	pub hard_stop: Option<bool>,
+}
+
+/// How much of a [`ProjectBudget`] is used up by the tracking entries of its project
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(test, derive(PartialEq))]
+#[typeshare::typeshare]
+pub struct BudgetConsumption {
+	pub budget: ProjectBudget,
+	/// Sum of `tracking.performed` in hours
+	pub performed: f64,
+	/// Sum of `tracking.billed` in hours
+	pub billed: f64,
+	/// Billed hours converted to the unit of the budget
+	#[typeshare(serialized_as = "String")]
+	pub consumed: BigDecimal,
+	/// `consumed` relative to the budget's `amount`
+	pub percentage: f64,
+	/// All configured thresholds that `percentage` has reached
+	pub reached_thresholds: Vec<f64>,
+	/// Nothing is left of the budget
+	pub exhausted: bool,
+}
+
+impl BudgetConsumption {
+	fn new(budget: ProjectBudget, performed: f64, billed: f64) -> Self {
+		let hours = BigDecimal::from_f64(billed).unwrap_or_default().round(2);
+		let consumed = if budget.unit == UNIT_MONEY {
+			(hours
+				* budget
+					.hourly_rate
+					.clone()
+					.map(|rate| rate.0)
+					.unwrap_or_default())
+			.round(2)
+		} else {
+			hours
+		};
+		let percentage = (&consumed * BigDecimal::from(100) / &budget.amount.0)
+			.to_f64()
+			.unwrap_or_default();
+		let reached_thresholds = budget
+			.thresholds
+			.0
+			.iter()
+			.copied()
+			.filter(|threshold| percentage >= *threshold)
+			.collect();
+		Self {
+			exhausted: consumed >= budget.amount.0,
+			budget,
+			performed,
+			billed,
+			consumed,
+			percentage,
+			reached_thresholds,
+		}
+	}
+}
+
+/// Reject budgets whose values don't make sense together
+pub fn check_budget(
+	unit: &str,
+	amount: &Decimal,
+	hourly_rate: Option<&Decimal>,
+	thresholds: &Thresholds,
+) -> Result<()> {
+	if unit != UNIT_HOURS && unit != UNIT_MONEY {
+		return Err(
+			Error::bad_request("budget.invalid_unit")
+				.with_arg(UNIT_HOURS)
+				.with_arg(UNIT_MONEY),
+		);
+	}
+	if amount.0 <= BigDecimal::from(0) {
+		return Err(Error::bad_request("budget.invalid_amount"));
+	}
+	if unit == UNIT_MONEY && hourly_rate.is_none() {
+		return Err(Error::bad_request("budget.hourly_rate_missing"));
+	}
+	if hourly_rate.is_some_and(|hourly_rate| hourly_rate.0 <= BigDecimal::from(0)) {
+		return Err(Error::bad_request("budget.invalid_hourly_rate"));
+	}
+	if thresholds.0.iter().any(|threshold| *threshold <= 0.0) {
+		return Err(Error::bad_request("budget.invalid_thresholds"));
+	}
+	Ok(())
+}
+
+impl ProjectBudget {
+	/// Get all budgets of a project
+	pub async fn from_project(db: &mut Connection<DB>, param_id: i32) -> QueryResult<Vec<Self>> {
+		use crate::schema::project_budget::dsl::*;
+
+		trace!(
+			"Reading from project_budget table for project: {}",
+			param_id
+		);
+		project_budget
+			.filter(project_id.eq(param_id))
+			.load::<Self>(db)
+			.await
+	}
+
+	/// Sum up the tracking entries that count against this budget
+	///
+	/// Entries that are not billed don't count, see [`not_billed`]
+	pub async fn consumption(self, db: &mut Connection<DB>) -> QueryResult<BudgetConsumption> {
+		self.consumption_except(db, None).await
+	}
+
+	/// Like [`ProjectBudget::consumption`], but without the entry `except`
+	async fn consumption_except(
+		self,
+		db: &mut Connection<DB>,
+		except: Option<i32>,
+	) -> QueryResult<BudgetConsumption> {
+		trace!("Calculating consumption of project_budget: {}", self.id);
+		let mut query = tracking::table
+			.filter(tracking::project_id.eq(self.project_id))
+			.filter(not(not_billed()))
+			.into_boxed();
+		if let Some(param_activity_id) = self.activity_id {
+			query = query.filter(
+				tracking::id.eq_any(
+					tracking_to_activity::table
+						.filter(tracking_to_activity::activity_id.eq(param_activity_id))
+						.select(tracking_to_activity::tracking_id),
+				),
+			);
+		}
+		if let Some(except) = except {
+			query = query.filter(tracking::id.ne(except));
+		}
+		let (performed, billed): (Option<f32>, Option<f32>) = query
+			.select((
+				diesel::dsl::sum(tracking::performed),
+				diesel::dsl::sum(tracking::billed),
+			))
+			.first(db)
+			.await?;
+		Ok(BudgetConsumption::new(
+			self,
+			performed.unwrap_or_default().into(),
+			billed.unwrap_or_default().into(),
+		))
+	}
+
+	/// Consumption of every budget of a project
+	///
+	/// The entry `except` is left out, so an entry that is changed doesn't count against itself
+	pub async fn project_consumption(
+		db: &mut Connection<DB>,
+		param_id: i32,
+		except: Option<i32>,
+	) -> QueryResult<Vec<BudgetConsumption>> {
+		let mut result = Vec::new();
+		for budget in Self::from_project(db, param_id).await? {
+			result.push(budget.consumption_except(db, except).await?);
+		}
+		Ok(result)
+	}
+
+	/// Consumption of every budget that reached at least one of its thresholds
+	pub async fn alerts(db: &mut Connection<DB>) -> QueryResult<Vec<BudgetConsumption>> {
+		use crate::schema::project_budget::dsl::*;
+
+		trace!("Collecting project budgets that reached a threshold");
+		let budgets = project_budget.load::<Self>(db).await?;
+		let mut result = Vec::new();
+		for budget in budgets {
+			let consumption = budget.consumption(db).await?;
+			if !consumption.reached_thresholds.is_empty() {
+				result.push(consumption);
+			}
+		}
+		Ok(result)
+	}
+}
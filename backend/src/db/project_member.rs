@@ -0,0 +1,179 @@
+use rocket_db_pools::Connection;
+use schemars::JsonSchema;
+use serde::{
+	Deserialize,
+	Serialize,
+};
+use tracing::trace;
+
+use super::{
+	project::Project,
+	user::User,
+};
+use crate::{
+	db::prelude::*,
+	schema::*,
+	DB,
+};
+
+/// May book on the project
+pub const ROLE_MEMBER: &str = "member";
+/// May book on the project and sees and approves all of its entries
+pub const ROLE_LEAD: &str = "lead";
+
+crud!(
+	project_member,
+	ProjectMember,
+	CreateProjectMember,
+	UpdateProjectMember,
+	audited
+);
+
+/// Struct representing a row in table `project_member`
+#[derive(
+	Debug,
+	Clone,
+	Serialize,
+	Deserialize,
+	JsonSchema,
+	Queryable,
+	Selectable,
+	QueryableByName,
+	Associations,
+	Identifiable,
+)]
+#[diesel(table_name=project_member, primary_key(id), belongs_to(Project, foreign_key=project_id), belongs_to(User, foreign_key=user_id))]
+#[cfg_attr(test, derive(PartialEq))]
+#[typeshare::typeshare]
+pub struct ProjectMember {
+	/// Field representing column `id`
+	pub id: i32,
+	/// Field representing column `project_id`
+	pub project_id: i32,
+	/// Field representing column `user_id`
+	pub user_id: i32,
+	/// Field representing column `role`, either [`ROLE_MEMBER`] or [`ROLE_LEAD`]
+	pub role: String,
+	/// Field representing column `created_at`
+	pub created_at: chrono::NaiveDateTime,
+	/// Field representing column `updated_at`
+	pub updated_at: chrono::NaiveDateTime,
+}
+
+/// Create Struct for a row in table `project_member` for [`ProjectMember`]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Insertable)]
+#[diesel(table_name=project_member)]
+#[typeshare::typeshare]
+pub struct CreateProjectMember {
+	/// Field representing column `project_id`, taken from the url
+	#[serde(skip_deserializing)]
+	pub project_id: i32,
+	/// Field representing column `user_id`
+	pub user_id: i32,
+	/// Field representing column `role`
+	#[serde(default = "default_role")]
+	pub role: String,
+}
+
+fn default_role() -> String {
+	ROLE_MEMBER.to_string()
+}
+
+/// Update Struct for a row in table `project_member` for [`ProjectMember`]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, AsChangeset, PartialEq, Default)]
+#[diesel(table_name=project_member)]
+#[typeshare::typeshare]
+pub struct UpdateProjectMember {
+	/// Field representing column `role`
+	pub role: Option<String>,
+}
+
+/// Whether `role` is one of [`ROLE_MEMBER`] and [`ROLE_LEAD`]
+pub fn is_valid_role(role: &str) -> bool {
+	role == ROLE_MEMBER || role == ROLE_LEAD
+}
+
+impl ProjectMember {
+	/// Get the membership of a user in a project
+	pub async fn find(
+		db: &mut Connection<DB>,
+		param_project_id: i32,
+		param_user_id: i32,
+	) -> QueryResult<Self> {
+		use crate::schema::project_member::dsl::*;
+
+		trace!(
+			"Reading from project_member table: project {}, user {}",
+			param_project_id,
+			param_user_id
+		);
+		project_member
+			.filter(project_id.eq(param_project_id))
+			.filter(user_id.eq(param_user_id))
+			.first::<Self>(db)
+			.await
+	}
+
+	/// Get all members of a project
+	pub async fn from_project(
+		db: &mut Connection<DB>,
+		param_project_id: i32,
+	) -> QueryResult<Vec<Self>> {
+		use crate::schema::project_member::dsl::*;
+
+		trace!(
+			"Reading from project_member table for project: {}",
+			param_project_id
+		);
+		project_member
+			.filter(project_id.eq(param_project_id))
+			.load::<Self>(db)
+			.await
+	}
+
+	/// Whether the user may book on the project
+	pub async fn is_member(
+		db: &mut Connection<DB>,
+		param_project_id: i32,
+		param_user_id: i32,
+	) -> QueryResult<bool> {
+		Ok(
+			Self::find(db, param_project_id, param_user_id)
+				.await
+				.optional()?
+				.is_some(),
+		)
+	}
+
+	/// Whether the user leads the project
+	pub async fn is_lead(
+		db: &mut Connection<DB>,
+		param_project_id: i32,
+		param_user_id: i32,
+	) -> QueryResult<bool> {
+		Ok(
+			Self::find(db, param_project_id, param_user_id)
+				.await
+				.optional()?
+				.is_some_and(|member| member.role == ROLE_LEAD),
+		)
+	}
+
+	/// All projects the user may currently book on
+	///
+	/// Soft deleted and archived projects are skipped
+	pub async fn bookable_projects(
+		db: &mut Connection<DB>,
+		param_user_id: i32,
+	) -> QueryResult<Vec<Project>> {
+		trace!("Reading bookable projects of user: {}", param_user_id);
+		project_member::table
+			.inner_join(project::table)
+			.filter(project_member::user_id.eq(param_user_id))
+			.filter(project::deleted_at.is_null())
+			.filter(project::archived.eq(false))
+			.select(Project::as_select())
+			.load::<Project>(db)
+			.await
+	}
+}
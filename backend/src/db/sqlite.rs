@@ -0,0 +1,333 @@
+//! The SQLite connection of the `sqlite` feature
+//!
+//! diesel only has a sync SQLite connection and diesel-async 0.4, which `rocket_db_pools` is built
+//! on, has no wrapper for it. [`AsyncSqliteConnection`] runs every statement right away on the
+//! thread of the calling task, the database is a local file after all. On a multi threaded runtime
+//! the other tasks of that thread move to another one in the meantime.
+
+use std::{
+	iter,
+	ops::{
+		Deref,
+		Range,
+	},
+	time::Duration,
+	vec,
+};
+
+use deadpool::{
+	managed::{
+		BuildError,
+		Object,
+		PoolError,
+	},
+	Runtime,
+};
+use diesel::{
+	connection::{
+		Connection,
+		LoadConnection,
+		SimpleConnection,
+	},
+	deserialize::{
+		self,
+		FromSql,
+	},
+	expression::expression_types,
+	query_builder::{
+		AsQuery,
+		AstPass,
+		Query,
+		QueryFragment,
+		QueryId,
+	},
+	row::{
+		PartialRow,
+		Row,
+		RowIndex,
+		RowSealed,
+	},
+	sql_types::Text,
+	sqlite::{
+		Sqlite,
+		SqliteConnection,
+		SqliteValue,
+	},
+	ConnectionError,
+	ConnectionResult,
+	QueryResult,
+};
+use diesel_async::{
+	pooled_connection::{
+		AsyncDieselConnectionManager,
+		PoolableConnection,
+	},
+	AnsiTransactionManager,
+	AsyncConnection,
+	SimpleAsyncConnection,
+};
+use rocket::{
+	figment::Figment,
+	futures::{
+		future,
+		stream,
+	},
+	serde::json::{
+		serde_json,
+		Value,
+	},
+	tokio::{
+		runtime::{
+			Handle,
+			RuntimeFlavor,
+		},
+		task,
+	},
+};
+use rocket_db_pools::Config;
+
+use self::sql_types::Json;
+
+/// Run on every new connection, SQLite leaves foreign keys unchecked by default
+///
+/// Other connections may hold the write lock for a moment, a statement waits up to 5 seconds for it.
+const SETUP: &str =
+	"PRAGMA foreign_keys = ON; PRAGMA busy_timeout = 5000; PRAGMA journal_mode = WAL;";
+
+/// Types of the SQLite schema that diesel only has for the other backends
+pub mod sql_types {
+	use diesel::sql_types::ops;
+
+	/// Exact decimals, stored as text to keep their scale
+	#[derive(diesel::sql_types::SqlType, diesel::query_builder::QueryId)]
+	#[diesel(sqlite_type(name = "Text"))]
+	pub struct Numeric;
+
+	impl ops::Add for Numeric {
+		type Rhs = Numeric;
+		type Output = Numeric;
+	}
+
+	impl ops::Sub for Numeric {
+		type Rhs = Numeric;
+		type Output = Numeric;
+	}
+
+	impl ops::Mul for Numeric {
+		type Rhs = Numeric;
+		type Output = Numeric;
+	}
+
+	impl ops::Div for Numeric {
+		type Rhs = Numeric;
+		type Output = Numeric;
+	}
+
+	/// JSON documents, stored as text
+	#[derive(diesel::sql_types::SqlType, diesel::query_builder::QueryId)]
+	#[diesel(sqlite_type(name = "Text"))]
+	pub struct Json;
+}
+
+/// A [`SqliteConnection`] for diesel-async
+pub struct AsyncSqliteConnection {
+	inner: SqliteConnection,
+	transaction_manager: AnsiTransactionManager,
+}
+
+#[rocket::async_trait]
+impl SimpleAsyncConnection for AsyncSqliteConnection {
+	async fn batch_execute(&mut self, query: &str) -> QueryResult<()> {
+		blocking(|| self.inner.batch_execute(query))
+	}
+}
+
+#[rocket::async_trait]
+impl AsyncConnection for AsyncSqliteConnection {
+	type Backend = Sqlite;
+	type ExecuteFuture<'conn, 'query> = future::Ready<QueryResult<usize>>;
+	type LoadFuture<'conn, 'query> = future::Ready<QueryResult<Self::Stream<'conn, 'query>>>;
+	type Row<'conn, 'query> = SqliteRow<'conn, 'query>;
+	type Stream<'conn, 'query> = stream::Iter<
+		iter::Map<
+			vec::IntoIter<Self::Row<'conn, 'query>>,
+			fn(Self::Row<'conn, 'query>) -> QueryResult<Self::Row<'conn, 'query>>,
+		>,
+	>;
+	type TransactionManager = AnsiTransactionManager;
+
+	async fn establish(database_url: &str) -> ConnectionResult<Self> {
+		blocking(|| {
+			let mut inner = SqliteConnection::establish(database_url)?;
+			inner
+				.batch_execute(SETUP)
+				.map_err(ConnectionError::CouldntSetupConfiguration)?;
+			Ok(Self {
+				inner,
+				transaction_manager: AnsiTransactionManager::default(),
+			})
+		})
+	}
+
+	fn load<'conn, 'query, T>(&'conn mut self, source: T) -> Self::LoadFuture<'conn, 'query>
+	where
+		T: AsQuery + 'query,
+		T::Query: QueryFragment<Self::Backend> + QueryId + 'query,
+	{
+		// the rows are read right away, the cursor borrows the connection and isn't `Send`. It
+		// mustn't be advanced after an error, which fails the whole query instead.
+		let rows = blocking(move || {
+			let cursor = self.inner.load(Untyped(source.as_query()))?;
+			cursor
+				.map(|row| row.map(SqliteRow))
+				.collect::<QueryResult<Vec<_>>>()
+		});
+		future::ready(rows.map(|rows| stream::iter(rows.into_iter().map(Ok as fn(_) -> _))))
+	}
+
+	fn execute_returning_count<'conn, 'query, T>(
+		&'conn mut self,
+		source: T,
+	) -> Self::ExecuteFuture<'conn, 'query>
+	where
+		T: QueryFragment<Self::Backend> + QueryId + 'query,
+	{
+		future::ready(blocking(move || {
+			self.inner.execute_returning_count(&source)
+		}))
+	}
+
+	fn transaction_state(&mut self) -> &mut AnsiTransactionManager {
+		&mut self.transaction_manager
+	}
+}
+
+impl PoolableConnection for AsyncSqliteConnection {}
+
+/// Runs a blocking call to SQLite
+fn blocking<R>(call: impl FnOnce() -> R) -> R {
+	match Handle::try_current().map(|handle| handle.runtime_flavor()) {
+		Ok(RuntimeFlavor::MultiThread) => task::block_in_place(call),
+		_ => call(),
+	}
+}
+
+/// Leaves out the SQL type of a query, SQLite reads the values of a row without knowing it
+struct Untyped<T>(T);
+
+impl<T: Query> Query for Untyped<T> {
+	type SqlType = expression_types::Untyped;
+}
+
+impl<T: QueryFragment<Sqlite>> QueryFragment<Sqlite> for Untyped<T> {
+	fn walk_ast<'b>(&'b self, pass: AstPass<'_, 'b, Sqlite>) -> QueryResult<()> {
+		self.0.walk_ast(pass)
+	}
+}
+
+impl<T: QueryId> QueryId for Untyped<T> {
+	type QueryId = T::QueryId;
+
+	const HAS_STATIC_QUERY_ID: bool = T::HAS_STATIC_QUERY_ID;
+}
+
+type InnerRow<'conn, 'query> = <SqliteConnection as LoadConnection>::Row<'conn, 'query>;
+
+/// A row loaded by an [`AsyncSqliteConnection`]
+pub struct SqliteRow<'conn, 'query>(InnerRow<'conn, 'query>);
+
+// SAFETY: the rows of a query share reference counted state with each other and with the
+// statement. All of it is created by one `load` and only handed out by the returned stream, which
+// diesel-async consumes in the future awaiting the query, so only one thread at a time uses it.
+unsafe impl Send for SqliteRow<'_, '_> {}
+
+impl RowSealed for SqliteRow<'_, '_> {}
+
+impl<'conn, 'query> Row<'conn, Sqlite> for SqliteRow<'conn, 'query> {
+	type Field<'field>
+		= <InnerRow<'conn, 'query> as Row<'conn, Sqlite>>::Field<'field>
+	where
+		'conn: 'field,
+		Self: 'field;
+	type InnerPartialRow = Self;
+
+	fn field_count(&self) -> usize {
+		self.0.field_count()
+	}
+
+	fn get<'field, I>(&'field self, idx: I) -> Option<Self::Field<'field>>
+	where
+		'conn: 'field,
+		Self: RowIndex<I>,
+	{
+		let idx = self.idx(idx)?;
+		self.0.get(idx)
+	}
+
+	fn partial_row(&self, range: Range<usize>) -> PartialRow<'_, Self> {
+		PartialRow::new(self, range)
+	}
+}
+
+impl RowIndex<usize> for SqliteRow<'_, '_> {
+	fn idx(&self, idx: usize) -> Option<usize> {
+		self.0.idx(idx)
+	}
+}
+
+impl<'name> RowIndex<&'name str> for SqliteRow<'_, '_> {
+	fn idx(&self, name: &'name str) -> Option<usize> {
+		self.0.idx(name)
+	}
+}
+
+type Manager = AsyncDieselConnectionManager<AsyncSqliteConnection>;
+
+/// Pool of [`AsyncSqliteConnection`]s, configured like the pools of `rocket_db_pools::diesel`
+pub struct SqlitePool(deadpool::managed::Pool<Manager>);
+
+#[rocket::async_trait]
+impl rocket_db_pools::Pool for SqlitePool {
+	type Connection = Object<Manager>;
+	type Error = rocket_db_pools::Error<
+		BuildError<diesel_async::pooled_connection::PoolError>,
+		PoolError<diesel_async::pooled_connection::PoolError>,
+	>;
+
+	async fn init(figment: &Figment) -> Result<Self, Self::Error> {
+		let config: Config = figment.extract()?;
+		deadpool::managed::Pool::builder(Manager::new(config.url))
+			.max_size(config.max_connections)
+			.wait_timeout(Some(Duration::from_secs(config.connect_timeout)))
+			.create_timeout(Some(Duration::from_secs(config.connect_timeout)))
+			.recycle_timeout(config.idle_timeout.map(Duration::from_secs))
+			.runtime(Runtime::Tokio1)
+			.build()
+			.map(Self)
+			.map_err(rocket_db_pools::Error::Init)
+	}
+
+	async fn get(&self) -> Result<Self::Connection, Self::Error> {
+		self.0.get().await.map_err(rocket_db_pools::Error::Get)
+	}
+
+	async fn close(&self) {
+		self.0.close()
+	}
+}
+
+/// Gives access to the state of the pool, like the deadpool pools of `rocket_db_pools::diesel`
+impl Deref for SqlitePool {
+	type Target = deadpool::managed::Pool<Manager>;
+
+	fn deref(&self) -> &Self::Target {
+		&self.0
+	}
+}
+
+impl FromSql<Json, Sqlite> for Value {
+	fn from_sql(value: SqliteValue<'_, '_, '_>) -> deserialize::Result<Self> {
+		let text = <String as FromSql<Text, Sqlite>>::from_sql(value)?;
+		Ok(serde_json::from_str(&text)?)
+	}
+}
@@ -0,0 +1,176 @@
+use std::collections::BTreeMap;
+
+use rocket_db_pools::Connection;
+use schemars::JsonSchema;
+use serde::{
+	Deserialize,
+	Serialize,
+};
+use tracing::trace;
+
+use super::{
+	tracking::Tracking,
+	DbConnection,
+};
+use crate::{
+	db::prelude::*,
+	schema::*,
+	Error,
+	Result,
+	DB,
+};
+
+/// Longest name a tag may have, the length of column `tag.name`
+const MAX_LENGTH: usize = 50;
+
+/// Struct representing a row in table `tag`
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Queryable, Selectable, Identifiable)]
+#[diesel(table_name=tag, primary_key(id))]
+#[cfg_attr(test, derive(PartialEq))]
+pub struct Tag {
+	/// Field representing column `id`
+	pub id: i32,
+	/// Field representing column `name`
+	pub name: String,
+	/// Field representing column `created_at`
+	pub created_at: chrono::NaiveDateTime,
+}
+
+/// Create Struct for a row in table `tag` for [`Tag`]
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name=tag)]
+struct CreateTag<'a> {
+	name: &'a str,
+}
+
+/// Hours of all entries carrying the same tag in a period
+///
+/// An entry with several tags is part of the group of each of its tags
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[typeshare::typeshare]
+pub struct TagReport {
+	/// Name of the tag, `None` for entries without any tag
+	pub tag: Option<String>,
+	/// Number of tracking entries
+	#[typeshare(serialized_as = "U53")]
+	pub entries: usize,
+	pub performed: f64,
+	pub billed: f64,
+}
+
+/// Tags are free labels, only empty and overlong names are rejected
+pub fn check_tags(names: &[String]) -> Result<()> {
+	match names
+		.iter()
+		.find(|name| name.trim().is_empty() || name.trim().chars().count() > MAX_LENGTH)
+	{
+		Some(name) => Err(Error::bad_request("tag.invalid").with_arg(format!("{name:?}"))),
+		None => Ok(()),
+	}
+}
+
+/// Trimmed, sorted and deduplicated names
+pub(crate) fn normalize(names: &[String]) -> Vec<String> {
+	let mut names = names
+		.iter()
+		.map(|name| name.trim().to_string())
+		.collect::<Vec<_>>();
+	names.sort_unstable();
+	names.dedup();
+	names
+}
+
+impl Tag {
+	/// Tags starting with `prefix`, ordered by name
+	pub async fn autocomplete(
+		db: &mut Connection<DB>,
+		prefix: &str,
+		limit: i64,
+	) -> QueryResult<Vec<String>> {
+		use crate::schema::tag::dsl::*;
+
+		trace!("Autocompleting tags starting with {:?}", prefix);
+		let pattern = format!(
+			"{}%",
+			prefix
+				.replace('\\', "\\\\")
+				.replace('%', "\\%")
+				.replace('_', "\\_")
+		);
+		tag
+			.filter(name.like(pattern))
+			.select(name)
+			.order(name)
+			.limit(limit)
+			.load::<String>(db)
+			.await
+	}
+
+	/// Tags with the given names, the ones that don't exist yet are created
+	pub async fn find_or_create(db: &mut DbConnection, names: &[String]) -> QueryResult<Vec<Self>> {
+		use crate::schema::tag::dsl::*;
+
+		trace!("Finding or creating tags: {:?}", names);
+		if names.is_empty() {
+			return Ok(vec![]);
+		}
+		let items = names
+			.iter()
+			.map(|item| CreateTag { name: item })
+			.collect::<Vec<_>>();
+		#[cfg(feature = "mysql")]
+		diesel::insert_or_ignore_into(tag)
+			.values(&items)
+			.execute(db)
+			.await?;
+		// diesel only splits a batch insert into single rows for its sync SQLite connection
+		#[cfg(feature = "sqlite")]
+		for item in &items {
+			diesel::insert_or_ignore_into(tag)
+				.values(item)
+				.execute(&mut *db)
+				.await?;
+		}
+		#[cfg(feature = "postgres")]
+		diesel::insert_into(tag)
+			.values(&items)
+			.on_conflict_do_nothing()
+			.execute(db)
+			.await?;
+		tag
+			.filter(name.eq_any(names))
+			.order(name)
+			.load::<Self>(db)
+			.await
+	}
+
+	/// Hours of the tracking entries between `from` and `to`, grouped by tag
+	pub async fn report(
+		db: &mut Connection<DB>,
+		from: chrono::NaiveDate,
+		to: chrono::NaiveDate,
+	) -> QueryResult<Vec<TagReport>> {
+		trace!("Tag report from {} to {}", from, to);
+		let tracking = Tracking::between(db, from, to).await?;
+		let mut groups = BTreeMap::<Option<&String>, TagReport>::new();
+		for tracking in &tracking {
+			let tags = if tracking.tags.is_empty() {
+				vec![None]
+			} else {
+				tracking.tags.iter().map(Some).collect()
+			};
+			for tag in tags {
+				let group = groups.entry(tag).or_insert_with(|| TagReport {
+					tag: tag.cloned(),
+					entries: 0,
+					performed: 0.0,
+					billed: 0.0,
+				});
+				group.entries += 1;
+				group.performed += f64::from(tracking.performed);
+				group.billed += f64::from(tracking.billed);
+			}
+		}
+		Ok(groups.into_values().collect())
+	}
+}
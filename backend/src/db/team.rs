@@ -0,0 +1,274 @@
+#[cfg(test)]
+use fake::{
+	faker::company::en::*,
+	Dummy,
+};
+use rocket_db_pools::Connection;
+use schemars::JsonSchema;
+use serde::{
+	Deserialize,
+	Serialize,
+};
+use tracing::trace;
+
+use super::{
+	audit::{
+		Action,
+		Audit,
+	},
+	tracking::Tracking,
+	DbConnection,
+	PaginationResult,
+};
+use crate::{
+	db::prelude::*,
+	schema::*,
+	DB,
+};
+
+crud!(team, Team, CreateTeam, UpdateTeam, audited, on_delete: delete_members);
+
+/// The memberships of a team go with it when it is deleted
+async fn delete_members(conn: &mut DbConnection, param_id: i32) -> QueryResult<usize> {
+	diesel::delete(team_member::table.filter(team_member::team_id.eq(param_id)))
+		.execute(conn)
+		.await
+}
+
+/// Struct representing a row in table `team`
+#[derive(
+	Debug, Clone, Serialize, Deserialize, JsonSchema, Queryable, Selectable, QueryableByName,
+)]
+#[diesel(table_name=team, primary_key(id))]
+#[cfg_attr(test, derive(PartialEq))]
+#[typeshare::typeshare]
+pub struct Team {
+	/// Field representing column `id`
+	pub id: i32,
+	/// Field representing column `name`
+	pub name: String,
+	/// Field representing column `manager_id`
+	pub manager_id: Option<i32>,
+	/// Field representing column `created_at`
+	pub created_at: chrono::NaiveDateTime,
+	/// Field representing column `updated_at`
+	pub updated_at: chrono::NaiveDateTime,
+}
+
+/// Create Struct for a row in table `team` for [`Team`]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Insertable)]
+#[diesel(table_name=team)]
+#[cfg_attr(test, derive(Dummy))]
+#[typeshare::typeshare]
+pub struct CreateTeam {
+	/// Field representing column `name`
+	#[cfg_attr(test, dummy(faker = "CompanyName()"))]
+	pub name: String,
+	/// Field representing column `manager_id`
+	#[cfg_attr(test, dummy(default))]
+	pub manager_id: Option<i32>,
+}
+
+/// Update Struct for a row in table `team` for [`Team`]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, AsChangeset, PartialEq, Default)]
+#[diesel(table_name=team)]
+#[typeshare::typeshare]
+pub struct UpdateTeam {
+	/// Field representing column `name`
+	pub name: Option<String>,
+	/// Field representing column `manager_id`
+	#[serde(
+		default,
+		skip_serializing_if = "Option::is_none",
+		deserialize_with = "serde_with::rust::double_option::deserialize"
+	)]
+	pub manager_id: Option<Option<i32>>,
+}
+
+/// Struct representing a row in table `team_member`
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Queryable, Selectable)]
+#[diesel(table_name=team_member, primary_key(id))]
+#[typeshare::typeshare]
+pub struct TeamMember {
+	/// Field representing column `id`
+	pub id: i32,
+	/// Field representing column `team_id`
+	pub team_id: i32,
+	/// Field representing column `user_id`
+	pub user_id: i32,
+	/// Field representing column `created_at`
+	pub created_at: chrono::NaiveDateTime,
+}
+
+/// Create Struct for a row in table `team_member` for [`TeamMember`]
+#[derive(Debug, Clone, Serialize, Deserialize, Insertable)]
+#[diesel(table_name=team_member)]
+struct CreateTeamMember {
+	team_id: i32,
+	user_id: i32,
+}
+
+/// Hours booked by a single member of a team in a period
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[typeshare::typeshare]
+pub struct TeamReport {
+	pub user_id: i32,
+	/// Number of tracking entries
+	#[typeshare(serialized_as = "U53")]
+	pub entries: usize,
+	pub performed: f64,
+	pub billed: f64,
+}
+
+impl Team {
+	/// Paginates through the table where page is a 0-based index (i.e. page 0 is the first page)
+	pub async fn paginate(
+		db: &mut Connection<DB>,
+		page: i64,
+		page_size: i64,
+	) -> QueryResult<PaginationResult<Self>> {
+		use crate::schema::team::dsl::*;
+
+		trace!(
+			"Paginating through team table: page {}, page_size {}",
+			page,
+			page_size
+		);
+		let page_size = page_size.max(1);
+		let total_items = team.count().get_result(db).await?;
+		let items = team
+			.order(id.asc())
+			.limit(page_size)
+			.offset(page * page_size)
+			.load::<Self>(db)
+			.await?;
+		Ok(PaginationResult::new(items, total_items, page, page_size))
+	}
+
+	/// Ids of all users in the team
+	pub async fn members(db: &mut Connection<DB>, param_id: i32) -> QueryResult<Vec<i32>> {
+		trace!("Reading members of team: {}", param_id);
+		team_member::table
+			.filter(team_member::team_id.eq(param_id))
+			.select(team_member::user_id)
+			.load::<i32>(db)
+			.await
+	}
+
+	/// Add a user to the team
+	pub async fn add_member(
+		db: &mut Connection<DB>,
+		actor: i32,
+		param_id: i32,
+		param_user_id: i32,
+	) -> QueryResult<TeamMember> {
+		trace!("Adding user {} to team {}", param_user_id, param_id);
+		let item = CreateTeamMember {
+			team_id: param_id,
+			user_id: param_user_id,
+		};
+		db.transaction(|mut conn| {
+			Box::pin(async move {
+				let new = insert_returning!(&mut conn, team_member, &item, TeamMember)?;
+				Audit::log(
+					conn,
+					actor,
+					"team_member",
+					new.id,
+					Action::Create,
+					None,
+					Some(&new),
+				)
+				.await?;
+				Ok(new)
+			})
+		})
+		.await
+	}
+
+	/// Remove a user from the team
+	pub async fn remove_member(
+		db: &mut Connection<DB>,
+		actor: i32,
+		param_id: i32,
+		param_user_id: i32,
+	) -> QueryResult<usize> {
+		trace!("Removing user {} from team {}", param_user_id, param_id);
+		db.transaction(|mut conn| {
+			Box::pin(async move {
+				let Some(old) = team_member::table
+					.filter(team_member::team_id.eq(param_id))
+					.filter(team_member::user_id.eq(param_user_id))
+					.select(TeamMember::as_select())
+					.first::<TeamMember>(&mut conn)
+					.await
+					.optional()?
+				else {
+					return Ok(0);
+				};
+				let deleted = diesel::delete(team_member::table.filter(team_member::id.eq(old.id)))
+					.execute(&mut conn)
+					.await?;
+				Audit::log(
+					conn,
+					actor,
+					"team_member",
+					old.id,
+					Action::Purge,
+					Some(&old),
+					None,
+				)
+				.await?;
+				Ok(deleted)
+			})
+		})
+		.await
+	}
+
+	/// Timesheet of the team, all entries of its members between `from` and `to`
+	pub async fn tracking(
+		db: &mut Connection<DB>,
+		param_id: i32,
+		from: chrono::NaiveDate,
+		to: chrono::NaiveDate,
+	) -> QueryResult<Vec<Tracking>> {
+		let members = Self::members(db, param_id).await?;
+		Tracking::from_users(db, &members, from, to).await
+	}
+
+	/// Sum up the entries of every member between `from` and `to`
+	///
+	/// Members without entries are part of the report with zero hours
+	pub async fn report(
+		db: &mut Connection<DB>,
+		param_id: i32,
+		from: chrono::NaiveDate,
+		to: chrono::NaiveDate,
+	) -> QueryResult<Vec<TeamReport>> {
+		let members = Self::members(db, param_id).await?;
+		let tracking = Tracking::from_users(db, &members, from, to).await?;
+		Ok(
+			members
+				.into_iter()
+				.map(|member| {
+					let entries = tracking
+						.iter()
+						.filter(|tracking| tracking.user_id == member)
+						.collect::<Vec<_>>();
+					TeamReport {
+						user_id: member,
+						entries: entries.len(),
+						performed: entries
+							.iter()
+							.map(|tracking| f64::from(tracking.performed))
+							.sum(),
+						billed: entries
+							.iter()
+							.map(|tracking| f64::from(tracking.billed))
+							.sum(),
+					}
+				})
+				.collect(),
+		)
+	}
+}
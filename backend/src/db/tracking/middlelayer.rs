@@ -1,7 +1,5 @@
-use rocket_db_pools::{
-	diesel::prelude::*,
-	Connection,
-};
+use rocket_db_pools::Connection;
+use schemars::JsonSchema;
 use serde::{
 	Deserialize,
 	Serialize,
@@ -21,17 +19,36 @@ use super::{
 		CreateTrackingToActivity as CreateTrackingToActivityDB,
 		TrackingToActivity as TrackingToActivityDB,
 	},
+	tracking_to_tag::TrackingToTag as TrackingToTagDB,
 };
 use crate::{
 	db::{
 		activity::Activity,
+		audit::{
+			Action,
+			Audit,
+		},
+		cursor::{
+			Cursor,
+			CursorPage,
+		},
+		custom_field::{
+			CustomField,
+			CustomFields,
+			ENTITY_TRACKING,
+		},
+		prelude::*,
+		tag::Tag,
+		DbConnection,
+		ListQuery,
 		PaginationResult,
 	},
 	DB,
 };
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[cfg_attr(test, derive(PartialEq))]
+#[typeshare::typeshare]
 pub struct Tracking {
 	pub id: i32,
 	pub client_id: i32,
@@ -46,10 +63,17 @@ pub struct Tracking {
 	pub description: Option<String>,
 	pub created_at: chrono::NaiveDateTime,
 	pub updated_at: chrono::NaiveDateTime,
+	/// User that approved the entry, either an admin or a lead of the project
+	pub approved_by: Option<i32>,
+	pub approved_at: Option<chrono::NaiveDateTime>,
 	pub activities: Vec<i32>,
+	/// Names of the tags, sorted by name
+	pub tags: Vec<String>,
+	pub custom_fields: CustomFields,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
+#[typeshare::typeshare]
 pub struct CreateTracking {
 	pub client_id: i32,
 	pub user_id: i32,
@@ -62,9 +86,15 @@ pub struct CreateTracking {
 	pub billed: f32,
 	pub description: Option<String>,
 	pub activities: Vec<i32>,
+	/// Names of the tags, tags that don't exist yet are created
+	#[serde(default)]
+	pub tags: Vec<String>,
+	#[serde(default)]
+	pub custom_fields: CustomFields,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Default)]
+#[typeshare::typeshare]
 pub struct UpdateTracking {
 	pub client_id: Option<i32>,
 	pub user_id: Option<i32>,
@@ -72,6 +102,11 @@ pub struct UpdateTracking {
 	pub date: Option<chrono::NaiveDate>,
 	pub begin: Option<chrono::NaiveTime>,
 	pub end: Option<chrono::NaiveTime>,
+	#[serde(
+		default,
+		skip_serializing_if = "Option::is_none",
+		deserialize_with = "serde_with::rust::double_option::deserialize"
+	)]
 	pub pause: Option<Option<chrono::NaiveTime>>,
 	pub performed: Option<f32>,
 	pub billed: Option<f32>,
@@ -79,11 +114,49 @@ pub struct UpdateTracking {
 	pub created_at: Option<chrono::NaiveDateTime>,
 	pub updated_at: Option<chrono::NaiveDateTime>,
 	pub activities: Option<Vec<i32>>,
+	pub tags: Option<Vec<String>>,
+	/// Only the given fields are changed, `null` removes a value
+	#[serde(default)]
+	pub custom_fields: CustomFields,
+}
+
+impl UpdateTracking {
+	/// The entry `old` turns into with this update, so it can be validated like a new one
+	pub fn apply(&self, old: &Tracking) -> CreateTracking {
+		let mut custom_fields = old.custom_fields.clone();
+		for (name, value) in &self.custom_fields {
+			if value.is_null() {
+				custom_fields.remove(name);
+			} else {
+				custom_fields.insert(name.clone(), value.clone());
+			}
+		}
+		CreateTracking {
+			client_id: self.client_id.unwrap_or(old.client_id),
+			user_id: self.user_id.unwrap_or(old.user_id),
+			project_id: self.project_id.unwrap_or(old.project_id),
+			date: self.date.unwrap_or(old.date),
+			begin: self.begin.unwrap_or(old.begin),
+			end: self.end.unwrap_or(old.end),
+			pause: self.pause.unwrap_or(old.pause),
+			performed: self.performed.unwrap_or(old.performed),
+			billed: self.billed.unwrap_or(old.billed),
+			description: self.description.clone().or_else(|| old.description.clone()),
+			activities: self
+				.activities
+				.clone()
+				.unwrap_or_else(|| old.activities.clone()),
+			tags: self.tags.clone().unwrap_or_else(|| old.tags.clone()),
+			custom_fields,
+		}
+	}
 }
 
 impl Tracking {
+	/// Store a new entry with its activities, tags and custom fields, all in one transaction
 	pub async fn create(
-		db: &mut Connection<DB>,
+		db: &mut DbConnection,
+		actor: i32,
 		tracking: &CreateTracking,
 	) -> Result<Tracking, diesel::result::Error> {
 		trace!("Tracking middle layer: create");
@@ -100,44 +173,78 @@ impl Tracking {
 			billed: tracking.billed,
 			description: tracking.description.to_owned(),
 		};
-		trace!("Creating Tracking");
-		let tracking_db = TrackingDB::create(db, &tracking_db).await.map_err(|e| {
-			error!("Error creating tracking: {:#?}", e);
-			e
-		})?;
-		trace!("Iterating over activities to create tracking_to_activity");
-		for i in tracking.activities.clone() {
-			let tracking_to_activity = CreateTrackingToActivityDB {
-				tracking_id: tracking_db.id,
-				activity_id: i,
-			};
-			TrackingToActivityDB::create(db, &tracking_to_activity)
-				.await
-				.map_err(|e| {
-					error!("Error creating tracking to activity: {:#?}", e);
+		db.transaction(|conn| {
+			Box::pin(async move {
+				trace!("Creating Tracking");
+				let tracking_db = TrackingDB::create(conn, &tracking_db).await.map_err(|e| {
+					error!("Error creating tracking: {:#?}", e);
 					e
 				})?;
-		}
-		Ok(Tracking {
-			id: tracking_db.id,
-			client_id: tracking_db.client_id,
-			user_id: tracking_db.user_id,
-			project_id: tracking_db.project_id,
-			date: tracking_db.date,
-			begin: tracking_db.begin,
-			end: tracking_db.end,
-			pause: tracking_db.pause,
-			performed: tracking_db.performed,
-			billed: tracking_db.billed,
-			description: tracking_db.description,
-			created_at: tracking_db.created_at,
-			updated_at: tracking_db.updated_at,
-			activities: tracking.activities.to_owned(),
+				trace!("Iterating over activities to create tracking_to_activity");
+				for i in tracking.activities.clone() {
+					let tracking_to_activity = CreateTrackingToActivityDB {
+						tracking_id: tracking_db.id,
+						activity_id: i,
+					};
+					TrackingToActivityDB::create(conn, &tracking_to_activity)
+						.await
+						.map_err(|e| {
+							error!("Error creating tracking to activity: {:#?}", e);
+							e
+						})?;
+				}
+				let tags = TrackingToTagDB::set(conn, tracking_db.id, &tracking.tags)
+					.await
+					.map_err(|e| {
+						error!("Error setting tags: {:#?}", e);
+						e
+					})?;
+				let custom_fields = CustomField::set_values(
+					conn,
+					actor,
+					ENTITY_TRACKING,
+					tracking_db.id,
+					&tracking.custom_fields,
+				)
+				.await?;
+				let tracking = Tracking {
+					id: tracking_db.id,
+					client_id: tracking_db.client_id,
+					user_id: tracking_db.user_id,
+					project_id: tracking_db.project_id,
+					date: tracking_db.date,
+					begin: tracking_db.begin,
+					end: tracking_db.end,
+					pause: tracking_db.pause,
+					performed: tracking_db.performed,
+					billed: tracking_db.billed,
+					description: tracking_db.description,
+					created_at: tracking_db.created_at,
+					updated_at: tracking_db.updated_at,
+					approved_by: tracking_db.approved_by,
+					approved_at: tracking_db.approved_at,
+					activities: tracking.activities.to_owned(),
+					tags,
+					custom_fields,
+				};
+				Audit::log(
+					conn,
+					actor,
+					"tracking",
+					tracking.id,
+					Action::Create,
+					None,
+					Some(&tracking),
+				)
+				.await?;
+				Ok(tracking)
+			})
 		})
+		.await
 	}
 
 	pub async fn read(
-		db: &mut Connection<DB>,
+		db: &mut DbConnection,
 		param_id: i32,
 	) -> Result<Tracking, diesel::result::Error> {
 		trace!("Tracking middle layer: read");
@@ -151,6 +258,16 @@ impl Tracking {
 				error!("Error getting activities: {:#?}", e);
 				e
 			})?;
+		let tags = TrackingToTagDB::get_tag_names(db, param_id)
+			.await
+			.map_err(|e| {
+				error!("Error getting tags: {:#?}", e);
+				e
+			})?;
+		let custom_fields = CustomField::values(db, ENTITY_TRACKING, &[param_id])
+			.await?
+			.remove(&param_id)
+			.unwrap_or_default();
 		Ok(Tracking {
 			id: tracking_db.id,
 			client_id: tracking_db.client_id,
@@ -165,32 +282,134 @@ impl Tracking {
 			description: tracking_db.description,
 			created_at: tracking_db.created_at,
 			updated_at: tracking_db.updated_at,
+			approved_by: tracking_db.approved_by,
+			approved_at: tracking_db.approved_at,
 			activities,
+			tags,
+			custom_fields,
 		})
 	}
 
+	/// Paginates through all entries, only the ones carrying every tag of `tags` if given
 	pub async fn paginate(
 		db: &mut Connection<DB>,
+		tags: &[String],
+		list: &ListQuery,
 		page: i64,
 		page_size: i64,
-	) -> Result<PaginationResult<Tracking>, diesel::result::Error> {
+	) -> crate::Result<PaginationResult<Tracking>> {
 		trace!("Tracking middle layer: paginate");
-		use rocket_db_pools::diesel::prelude::*;
+		Self::paginate_filtered(db, None, tags, list, page, page_size).await
+	}
 
-		use crate::schema::activity;
+	/// Paginates through the entries booked on a single project
+	pub async fn paginate_project(
+		db: &mut Connection<DB>,
+		project_id: i32,
+		page: i64,
+		page_size: i64,
+	) -> crate::Result<PaginationResult<Tracking>> {
+		trace!("Tracking middle layer: paginate_project");
+		Self::paginate_filtered(
+			db,
+			Some(project_id),
+			&[],
+			&ListQuery::default(),
+			page,
+			page_size,
+		)
+		.await
+	}
 
+	async fn paginate_filtered(
+		db: &mut Connection<DB>,
+		project_id: Option<i32>,
+		tags: &[String],
+		list: &ListQuery,
+		page: i64,
+		page_size: i64,
+	) -> crate::Result<PaginationResult<Tracking>> {
 		trace!(
-			"Paginating through tracking table: page {}, page_size {}",
+			"Paginating through tracking table: project {:?}, tags {:?}, {:?}, page {}, page_size {}",
+			project_id,
+			tags,
+			list,
 			page,
 			page_size
 		);
-		let pagination = TrackingDB::paginate(db, page, page_size)
+		let pagination = TrackingDB::paginate(db, project_id, tags, list, page, page_size)
 			.await
 			.map_err(|e| {
 				error!("Error paginating tracking: {:#?}", e);
 				e
 			})?;
-		let tracking_db = pagination.items;
+		Ok(PaginationResult {
+			items: Self::with_activities(db, pagination.items).await?,
+			total_items: pagination.total_items,
+			page: pagination.page,
+			page_size: pagination.page_size,
+			num_pages: pagination.num_pages,
+		})
+	}
+
+	/// Keyset pagination through all entries, see [`TrackingDB::cursor`]
+	pub async fn cursor(
+		db: &mut Connection<DB>,
+		tags: &[String],
+		list: &ListQuery,
+		after: Option<Cursor>,
+		limit: i64,
+		total: bool,
+	) -> crate::Result<CursorPage<Tracking>> {
+		trace!("Tracking middle layer: cursor");
+		let mut page = TrackingDB::cursor(db, None, tags, list, after, limit, total).await?;
+		let items = Self::with_activities(db, std::mem::take(&mut page.items)).await?;
+		Ok(page.with_items(items))
+	}
+
+	/// All entries of the given users between `from` and `to`, both inclusive
+	pub async fn from_users(
+		db: &mut Connection<DB>,
+		user_ids: &[i32],
+		from: chrono::NaiveDate,
+		to: chrono::NaiveDate,
+	) -> Result<Vec<Tracking>, diesel::result::Error> {
+		trace!("Tracking middle layer: from_users");
+		let tracking_db = TrackingDB::from_users(db, user_ids, from, to)
+			.await
+			.map_err(|e| {
+				error!("Error reading tracking of users: {:#?}", e);
+				e
+			})?;
+		Self::with_activities(db, tracking_db).await
+	}
+
+	/// All entries between `from` and `to`, both inclusive
+	pub async fn between(
+		db: &mut Connection<DB>,
+		from: chrono::NaiveDate,
+		to: chrono::NaiveDate,
+	) -> Result<Vec<Tracking>, diesel::result::Error> {
+		trace!("Tracking middle layer: between");
+		let tracking_db = TrackingDB::between(db, from, to).await.map_err(|e| {
+			error!(
+				"Error reading tracking between {} and {}: {:#?}",
+				from, to, e
+			);
+			e
+		})?;
+		Self::with_activities(db, tracking_db).await
+	}
+
+	async fn with_activities(
+		db: &mut Connection<DB>,
+		tracking_db: Vec<TrackingDB>,
+	) -> Result<Vec<Tracking>, diesel::result::Error> {
+		use crate::schema::{
+			activity,
+			tag,
+		};
+
 		trace!("Getting all activities belonging to each tracking");
 		let activities = TrackingToActivityDB::belonging_to(&tracking_db)
 			.inner_join(activity::table)
@@ -202,31 +421,49 @@ impl Tracking {
 				e
 			})?;
 
-		let activities_per_tracking: Vec<(TrackingDB, Vec<Activity>)> = activities
+		trace!("Getting all tags belonging to each tracking");
+		let mut tags = TrackingToTagDB::belonging_to(&tracking_db)
+			.inner_join(tag::table)
+			.select((TrackingToTagDB::as_select(), Tag::as_select()))
+			.order(tag::name)
+			.load(db)
+			.await
+			.map_err(|e| {
+				error!("Error getting tags: {:#?}", e);
+				e
+			})?
 			.grouped_by(&tracking_db)
-			.into_iter()
-			.zip(tracking_db)
-			.map(|(activities, tracking)| {
-				(
-					tracking,
-					activities
-						.into_iter()
-						.map(|(_, activity)| activity)
-						.collect(),
-				)
-			})
-			.collect();
+			.into_iter();
+		let ids = tracking_db
+			.iter()
+			.map(|tracking| tracking.id)
+			.collect::<Vec<_>>();
+		let mut custom_fields = CustomField::values(db, ENTITY_TRACKING, &ids).await?;
 
-		Ok(PaginationResult {
-			items: activities_per_tracking
+		Ok(
+			activities
+				.grouped_by(&tracking_db)
 				.into_iter()
-				.map(|(tracking, activities)| Self::new(tracking, activities))
+				.zip(tracking_db)
+				.map(|(activities, tracking)| {
+					let mut tracking = Self::new(
+						tracking,
+						activities
+							.into_iter()
+							.map(|(_, activity)| activity)
+							.collect(),
+					);
+					tracking.tags = tags
+						.next()
+						.unwrap_or_default()
+						.into_iter()
+						.map(|(_, tag)| tag.name)
+						.collect();
+					tracking.custom_fields = custom_fields.remove(&tracking.id).unwrap_or_default();
+					tracking
+				})
 				.collect(),
-			total_items: pagination.total_items,
-			page: pagination.page,
-			page_size: pagination.page_size,
-			num_pages: pagination.num_pages,
-		})
+		)
 	}
 
 	fn new(tracking_db: TrackingDB, activities: Vec<Activity>) -> Tracking {
@@ -244,7 +481,11 @@ impl Tracking {
 			description: tracking_db.description,
 			created_at: tracking_db.created_at,
 			updated_at: tracking_db.updated_at,
+			approved_by: tracking_db.approved_by,
+			approved_at: tracking_db.approved_at,
 			activities: activities.into_iter().map(|activity| activity.id).collect(),
+			tags: vec![],
+			custom_fields: CustomFields::new(),
 		}
 	}
 
@@ -263,17 +504,22 @@ impl Tracking {
 			description: tracking_db.description,
 			created_at: tracking_db.created_at,
 			updated_at: tracking_db.updated_at,
+			approved_by: tracking_db.approved_by,
+			approved_at: tracking_db.approved_at,
 			activities: vec![],
+			tags: vec![],
+			custom_fields: CustomFields::new(),
 		}
 	}
 
+	/// Change an entry with its activities, tags and custom fields, all in one transaction
 	pub async fn update(
-		db: &mut Connection<DB>,
+		db: &mut DbConnection,
+		actor: i32,
 		param_id: i32,
 		tracking: &UpdateTracking,
 	) -> Result<Tracking, diesel::result::Error> {
 		trace!("Tracking middle layer: update");
-
 		let tracking_db = UpdateTrackingDB {
 			client_id: tracking.client_id,
 			user_id: tracking.user_id,
@@ -288,64 +534,163 @@ impl Tracking {
 			created_at: tracking.created_at,
 			updated_at: tracking.updated_at,
 		};
-		let mut tracking_update;
-		let default_update = UpdateTrackingDB::default();
-		if tracking_db == default_update {
-			trace!("No update needed for tracking");
-			let tracking_db = TrackingDB::read(db, param_id).await.map_err(|e| {
-				error!("Error reading tracking: {:#?}", e);
-				e
-			})?;
-			tracking_update = Self::from_tracking(tracking_db);
-		} else {
-			let tracking_db = TrackingDB::update(db, param_id, &tracking_db)
-				.await
-				.map_err(|e| {
-					error!("Error updating tracking: {:#?}", e);
-					e
-				})?;
-			tracking_update = Self::from_tracking(tracking_db);
-		}
-		if tracking.activities.is_some() {
-			let activities = tracking.activities.clone().unwrap();
-			// just drop the old ones and add the new ones
-			TrackingToActivityDB::delete_by_tracking_id(db, param_id)
-				.await
-				.map_err(|e| {
-					error!("Error deleting tracking to activity: {:#?}", e);
-					e
-				})?;
-			for i in activities {
-				let tracking_to_activity = CreateTrackingToActivityDB {
-					tracking_id: param_id,
-					activity_id: i,
+		db.transaction(|conn| {
+			Box::pin(async move {
+				let old = Self::read(conn, param_id).await?;
+				let mut tracking_update;
+				let default_update = UpdateTrackingDB::default();
+				if tracking_db == default_update {
+					trace!("No update needed for tracking");
+					let tracking_db = TrackingDB::read(conn, param_id).await.map_err(|e| {
+						error!("Error reading tracking: {:#?}", e);
+						e
+					})?;
+					tracking_update = Self::from_tracking(tracking_db);
+				} else {
+					let tracking_db = TrackingDB::update(conn, param_id, &tracking_db)
+						.await
+						.map_err(|e| {
+							error!("Error updating tracking: {:#?}", e);
+							e
+						})?;
+					tracking_update = Self::from_tracking(tracking_db);
+				}
+				if let Some(activities) = &tracking.activities {
+					// just drop the old ones and add the new ones
+					TrackingToActivityDB::delete_by_tracking_id(conn, param_id)
+						.await
+						.map_err(|e| {
+							error!("Error deleting tracking to activity: {:#?}", e);
+							e
+						})?;
+					for i in activities.clone() {
+						let tracking_to_activity = CreateTrackingToActivityDB {
+							tracking_id: param_id,
+							activity_id: i,
+						};
+						TrackingToActivityDB::create(conn, &tracking_to_activity)
+							.await
+							.map_err(|e| {
+								error!("Error creating tracking to activity: {:#?}", e);
+								e
+							})?;
+					}
+					tracking_update.activities = activities.clone();
+				} else {
+					tracking_update.activities = old.activities.clone();
+				}
+				tracking_update.tags = match &tracking.tags {
+					Some(tags) => TrackingToTagDB::set(conn, param_id, tags)
+						.await
+						.map_err(|e| {
+							error!("Error setting tags: {:#?}", e);
+							e
+						})?,
+					None => old.tags.clone(),
 				};
-				TrackingToActivityDB::create(db, &tracking_to_activity)
+				tracking_update.custom_fields = old.custom_fields.clone();
+				Audit::log(
+					conn,
+					actor,
+					"tracking",
+					param_id,
+					Action::Update,
+					Some(&old),
+					Some(&tracking_update),
+				)
+				.await?;
+				tracking_update.custom_fields = CustomField::set_values(
+					conn,
+					actor,
+					ENTITY_TRACKING,
+					param_id,
+					&tracking.custom_fields,
+				)
+				.await?;
+				Ok(tracking_update)
+			})
+		})
+		.await
+	}
+
+	/// Delete an entry with its activities, tags and custom fields, all in one transaction
+	pub async fn delete(db: &mut DbConnection, actor: i32, param_id: i32) -> QueryResult<usize> {
+		trace!("Tracking middle layer: delete");
+		db.transaction(|conn| {
+			Box::pin(async move {
+				let Some(old) = Self::read(conn, param_id).await.optional()? else {
+					return Ok(0);
+				};
+				trace!(
+					"Tracking middle layer tracking to activity delete by tracking id {}",
+					param_id
+				);
+				TrackingToActivityDB::delete_by_tracking_id(conn, param_id).await?;
+				TrackingToTagDB::delete_by_tracking_id(conn, param_id).await?;
+				CustomField::delete_values(conn, ENTITY_TRACKING, param_id).await?;
+				trace!("Tracking middle layer tracking delete by id {}", param_id);
+				let deleted = TrackingDB::delete(conn, param_id).await?;
+				Audit::log::<Tracking>(
+					conn,
+					actor,
+					"tracking",
+					param_id,
+					Action::Purge,
+					Some(&old),
+					None,
+				)
+				.await?;
+				Ok(deleted)
+			})
+		})
+		.await
+	}
+
+	/// Approve an entry, the approval is recorded as an update in the audit log
+	pub async fn approve(db: &mut DbConnection, actor: i32, param_id: i32) -> QueryResult<Tracking> {
+		trace!("Tracking middle layer: approve");
+		db.transaction(|conn| {
+			Box::pin(async move {
+				let old = Self::read(conn, param_id).await?;
+				let tracking_db = TrackingDB::approve(conn, param_id, actor)
 					.await
 					.map_err(|e| {
-						error!("Error creating tracking to activity: {:#?}", e);
+						error!("Error approving tracking: {:#?}", e);
 						e
 					})?;
-			}
-			tracking_update.activities = tracking.activities.to_owned().unwrap();
-		}
-		Ok(tracking_update)
+				let mut tracking = Self::from_tracking(tracking_db);
+				tracking.activities = old.activities.clone();
+				tracking.tags = old.tags.clone();
+				tracking.custom_fields = old.custom_fields.clone();
+				Audit::log(
+					conn,
+					actor,
+					"tracking",
+					param_id,
+					Action::Update,
+					Some(&old),
+					Some(&tracking),
+				)
+				.await?;
+				Ok(tracking)
+			})
+		})
+		.await
 	}
 
-	pub async fn delete(db: &mut Connection<DB>, param_id: i32) -> QueryResult<usize> {
-		trace!("Tracking middle layer: delete");
-		trace!(
-			"Tracking middle layer tracking to activity delete by tracking id {}",
-			param_id
-		);
-		TrackingToActivityDB::delete_by_tracking_id(db, param_id).await?;
-		trace!("Tracking middle layer tracking delete by id {}", param_id);
-		TrackingDB::delete(db, param_id).await
+	pub async fn last_page(
+		db: &mut Connection<DB>,
+		page_size: i64,
+		tags: &[String],
+		list: &ListQuery,
+	) -> crate::Result<i64> {
+		trace!("Tracking middle layer: last_page");
+		TrackingDB::last_page(db, page_size, tags, list).await
 	}
 
-	pub async fn last_page(db: &mut Connection<DB>, page_size: i64) -> QueryResult<i64> {
-		trace!("Tracking middle layer: last_page");
-		TrackingDB::last_page(db, page_size).await
+	pub async fn count_created_today(db: &mut Connection<DB>) -> QueryResult<i64> {
+		trace!("Tracking middle layer: count_created_today");
+		TrackingDB::count_created_today(db).await
 	}
 }
 
@@ -363,5 +708,7 @@ impl PartialEq<CreateTracking> for Tracking {
 			&& self.billed == other.billed
 			&& self.description == other.description
 			&& self.activities == other.activities
+			&& self.tags == other.tags
+			&& self.custom_fields == other.custom_fields
 	}
 }
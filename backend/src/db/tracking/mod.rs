@@ -4,6 +4,8 @@ mod middlelayer;
 #[allow(clippy::module_inception)]
 mod tracking;
 mod tracking_to_activity;
+mod tracking_to_tag;
+mod validation;
 
 pub use middlelayer::{
 	CreateTracking,
@@ -1,30 +1,56 @@
-use rocket_db_pools::{
-	diesel::{
-		insert_into,
-		prelude::*,
-	},
-	Connection,
-};
+use diesel::dsl::now;
+use rocket_db_pools::Connection;
 use tracing::trace;
 
 use crate::{
 	db::{
 		client::Client,
-		last_insert_id,
+		cursor::{
+			Cursor,
+			CursorPage,
+		},
+		prelude::*,
 		project::Project,
 		user::User,
+		Backend,
+		DbConnection,
+		ListQuery,
 		PaginationResult,
 	},
 	schema::*,
+	Result,
 	DB,
 };
 
+list_query!(
+	tracking,
+	search: [description],
+	fields: {
+		id: i32,
+		client_id: i32,
+		user_id: i32,
+		project_id: i32,
+		date: chrono::NaiveDate,
+		begin: chrono::NaiveTime,
+		end: chrono::NaiveTime,
+		performed: f32,
+		billed: f32,
+		approved_by: i32,
+		created_at: chrono::NaiveDateTime,
+		updated_at: chrono::NaiveDateTime,
+	},
+	custom_fields: crate::db::custom_field::ENTITY_TRACKING,
+);
+
+crud!(tracking, Tracking, CreateTracking, UpdateTracking);
+
 /// Struct representing a row in table `tracking`
 #[derive(
 	Debug,
 	Clone,
 	serde::Serialize,
 	serde::Deserialize,
+	schemars::JsonSchema,
 	Queryable,
 	Selectable,
 	QueryableByName,
@@ -60,10 +86,14 @@ pub struct Tracking {
 	pub created_at: chrono::NaiveDateTime,
 	/// Field representing column `updated_at`
 	pub updated_at: chrono::NaiveDateTime,
+	/// Field representing column `approved_by`
+	pub approved_by: Option<i32>,
+	/// Field representing column `approved_at`
+	pub approved_at: Option<chrono::NaiveDateTime>,
 }
 
 /// Create Struct for a row in table `tracking` for [`Tracking`]
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, Insertable)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, schemars::JsonSchema, Insertable)]
 #[diesel(table_name=tracking)]
 pub struct CreateTracking {
 	/// Field representing column `client_id`
@@ -89,7 +119,16 @@ pub struct CreateTracking {
 }
 
 /// Update Struct for a row in table `tracking` for [`Tracking`]
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, AsChangeset, PartialEq, Default)]
+#[derive(
+	Debug,
+	Clone,
+	serde::Serialize,
+	serde::Deserialize,
+	schemars::JsonSchema,
+	AsChangeset,
+	PartialEq,
+	Default,
+)]
 #[diesel(table_name=tracking)]
 pub struct UpdateTracking {
 	/// Field representing column `client_id`
@@ -105,6 +144,11 @@ pub struct UpdateTracking {
 	/// Field representing column `end`
 	pub end: Option<chrono::NaiveTime>,
 	/// Field representing column `pause`
+	#[serde(
+		default,
+		skip_serializing_if = "Option::is_none",
+		deserialize_with = "serde_with::rust::double_option::deserialize"
+	)]
 	pub pause: Option<Option<chrono::NaiveTime>>,
 	/// Field representing column `performed`
 	pub performed: Option<f32>,
@@ -119,79 +163,156 @@ pub struct UpdateTracking {
 }
 
 impl Tracking {
-	/// Insert a new row into `tracking` with a given [`CreateTracking`]
-	pub async fn create(db: &mut Connection<DB>, item: &CreateTracking) -> QueryResult<Self> {
+	/// Paginates through the table where page is a 0-based index (i.e. page 0 is the first page)
+	///
+	/// Only rows of `param_project_id` are returned if it is given
+	pub async fn paginate(
+		db: &mut Connection<DB>,
+		param_project_id: Option<i32>,
+		tags: &[String],
+		list: &ListQuery,
+		page: i64,
+		page_size: i64,
+	) -> Result<PaginationResult<Self>> {
+		trace!(
+			"Paginating through tracking table: project {:?}, tags {:?}, {:?}, page {}, page_size {}",
+			param_project_id,
+			tags,
+			list,
+			page,
+			page_size
+		);
+		Self::paginate_query(
+			db,
+			|| Self::filtered(param_project_id, tags, list),
+			list,
+			page,
+			page_size,
+		)
+		.await
+	}
+
+	/// Keyset pagination ordered by `id`, continuing after the cursor `after`
+	///
+	/// Only `q` and `filter` of `list` apply, the order is given by the cursor
+	pub async fn cursor(
+		db: &mut Connection<DB>,
+		param_project_id: Option<i32>,
+		tags: &[String],
+		list: &ListQuery,
+		after: Option<Cursor>,
+		limit: i64,
+		total: bool,
+	) -> Result<CursorPage<Self>> {
 		use crate::schema::tracking::dsl::*;
 
-		trace!("Inserting into tracking table: {:?}", item);
-		db.transaction(|mut conn| {
-			Box::pin(async move {
-				insert_into(tracking)
-					.values(item)
-					.execute(&mut conn)
-					.await?;
-				tracking
-					.select(Tracking::as_select())
-					.filter(id.eq(last_insert_id()))
-					.first::<Self>(&mut conn)
-					.await
-			})
-		})
-		.await
+		trace!(
+			"Paginating through tracking table: project {:?}, tags {:?}, {:?}, after {:?}, limit {}",
+			param_project_id,
+			tags,
+			list,
+			after,
+			limit
+		);
+		if list.sort.is_some() {
+			return Err(crate::Error::bad_request("cursor.sorted"));
+		}
+		let total_items = if total {
+			Some(
+				Self::filtered(param_project_id, tags, list)?
+					.count()
+					.get_result(db)
+					.await?,
+			)
+		} else {
+			None
+		};
+		let query = Self::filtered(param_project_id, tags, list)?;
+		// one more row than asked for tells whether there is another page
+		let query = match after {
+			None => query.order(id.asc()),
+			Some(Cursor::Next(after)) => query.filter(id.gt(after)).order(id.asc()),
+			Some(Cursor::Prev(before)) => query.filter(id.lt(before)).order(id.desc()),
+		};
+		let items = query.limit(limit + 1).load::<Self>(db).await?;
+		Ok(CursorPage::new(items, after, limit, total_items, |item| {
+			item.id
+		}))
 	}
 
-	/// Get a row from `tracking`, identified by the primary key
-	pub async fn read(db: &mut Connection<DB>, param_id: i32) -> QueryResult<Self> {
+	/// Rows of a project carrying every one of `tags`, narrowed down by `list`
+	fn filtered<'a>(
+		param_project_id: Option<i32>,
+		tags: &[String],
+		list: &ListQuery,
+	) -> Result<tracking::BoxedQuery<'a, Backend>> {
 		use crate::schema::tracking::dsl::*;
 
-		trace!("Reading from tracking table: {}", param_id);
-		tracking.filter(id.eq(param_id)).first::<Self>(db).await
+		let mut query = tracking.into_boxed();
+		if let Some(param_project_id) = param_project_id {
+			query = query.filter(project_id.eq(param_project_id));
+		}
+		for param_tag in tags {
+			query = query.filter(
+				id.eq_any(
+					tracking_to_tag::table
+						.inner_join(tag::table)
+						.filter(tag::name.eq(param_tag.trim().to_string()))
+						.select(tracking_to_tag::tracking_id),
+				),
+			);
+		}
+		list_filter(query, list)
 	}
 
-	/// Paginates through the table where page is a 0-based index (i.e. page 0 is the first page)
-	pub async fn paginate(
+	/// Get all rows of the given users between `from` and `to`, both inclusive
+	pub async fn from_users(
 		db: &mut Connection<DB>,
-		page: i64,
-		page_size: i64,
-	) -> QueryResult<PaginationResult<Self>> {
+		user_ids: &[i32],
+		from: chrono::NaiveDate,
+		to: chrono::NaiveDate,
+	) -> QueryResult<Vec<Self>> {
 		use crate::schema::tracking::dsl::*;
 
 		trace!(
-			"Paginating through tracking table: page {}, page_size {}",
-			page,
-			page_size
+			"Reading from tracking table for users {:?} from {} to {}",
+			user_ids,
+			from,
+			to
 		);
-		let page_size = if page_size < 1 { 1 } else { page_size };
-		let total_items = tracking.count().get_result(db).await?;
-		let items = tracking
-			.limit(page_size)
-			.offset(page * page_size)
+		tracking
+			.filter(user_id.eq_any(user_ids))
+			.filter(date.between(from, to))
+			.order((date, begin))
 			.load::<Self>(db)
-			.await?;
-
-		Ok(PaginationResult {
-			items,
-			total_items,
-			page,
-			page_size,
-			/* ceiling division of integers */
-			num_pages: total_items / page_size + i64::from(total_items % page_size != 0),
-		})
+			.await
 	}
 
-	/// Update a row in `tracking`, identified by the primary key with [`UpdateTracking`]
-	pub async fn update(
+	/// All entries between `from` and `to`, both inclusive
+	pub async fn between(
 		db: &mut Connection<DB>,
-		param_id: i32,
-		item: &UpdateTracking,
-	) -> QueryResult<Self> {
+		from: chrono::NaiveDate,
+		to: chrono::NaiveDate,
+	) -> QueryResult<Vec<Self>> {
 		use crate::schema::tracking::dsl::*;
 
-		trace!("Updating tracking table: {} with {:?}", param_id, item);
+		trace!("Reading from tracking table from {} to {}", from, to);
+		tracking
+			.filter(date.between(from, to))
+			.order((date, begin))
+			.load::<Self>(db)
+			.await
+	}
+
+	/// Mark a row in `tracking` as approved by `approver`
+	pub async fn approve(db: &mut DbConnection, param_id: i32, approver: i32) -> QueryResult<Self> {
+		use crate::schema::tracking::dsl::*;
+
+		trace!("Approving tracking: {} by user {}", param_id, approver);
 		db.transaction(|mut conn| {
 			Box::pin(async move {
 				diesel::update(tracking.filter(id.eq(param_id)))
-					.set(item)
+					.set((approved_by.eq(approver), approved_at.eq(now.nullable())))
 					.execute(&mut conn)
 					.await?;
 				tracking
@@ -203,24 +324,33 @@ impl Tracking {
 		.await
 	}
 
-	/// Delete a row in `tracking`, identified by the primary key
-	pub async fn delete(db: &mut Connection<DB>, param_id: i32) -> QueryResult<usize> {
+	/// Number of rows created today
+	///
+	/// The day comes from the clock of the database, the one that sets `created_at`.
+	pub async fn count_created_today(db: &mut Connection<DB>) -> QueryResult<i64> {
+		use diesel::dsl::{
+			date,
+			today,
+		};
+
 		use crate::schema::tracking::dsl::*;
 
-		trace!("Deleting from tracking table: {}", param_id);
-		diesel::delete(tracking.filter(id.eq(param_id)))
-			.execute(db)
+		trace!("Counting trackings created today");
+		tracking
+			.filter(date(created_at).eq(today))
+			.count()
+			.get_result(db)
 			.await
 	}
 
-	pub async fn last_page(db: &mut Connection<DB>, page_size: i64) -> QueryResult<i64> {
-		use crate::schema::tracking::dsl::*;
-
-		trace!("Getting last page of project table for page_size {page_size}");
-
-		let total_items: i64 = tracking.count().get_result(db).await?;
-		// index starts at 0
-		Ok((total_items / page_size + i64::from(total_items % page_size != 0)) - 1)
+	pub async fn last_page(
+		db: &mut Connection<DB>,
+		page_size: i64,
+		tags: &[String],
+		list: &ListQuery,
+	) -> Result<i64> {
+		let query = Self::filtered(None, tags, list)?;
+		Self::last_page_query(db, query, page_size).await
 	}
 }
 
@@ -1,21 +1,14 @@
-use rocket_db_pools::{
-	diesel::{
-		insert_into,
-		prelude::*,
-	},
-	Connection,
-};
 use tracing::trace;
 
 use super::tracking::Tracking;
 use crate::{
 	db::{
 		activity::Activity,
-		last_insert_id,
+		prelude::*,
+		DbConnection,
 		PaginationResult,
 	},
 	schema::*,
-	DB,
 };
 
 /// Struct representing a row in table `tracking_to_activity`
@@ -24,6 +17,7 @@ use crate::{
 	Clone,
 	serde::Serialize,
 	serde::Deserialize,
+	schemars::JsonSchema,
 	Queryable,
 	Selectable,
 	QueryableByName,
@@ -41,7 +35,7 @@ pub struct TrackingToActivity {
 }
 
 /// Create Struct for a row in table `tracking_to_activity` for [`TrackingToActivity`]
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, Insertable)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, schemars::JsonSchema, Insertable)]
 #[diesel(table_name=tracking_to_activity)]
 pub struct CreateTrackingToActivity {
 	/// Field representing column `tracking_id`
@@ -51,7 +45,16 @@ pub struct CreateTrackingToActivity {
 }
 
 /// Update Struct for a row in table `tracking_to_activity` for [`TrackingToActivity`]
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, AsChangeset, PartialEq, Default)]
+#[derive(
+	Debug,
+	Clone,
+	serde::Serialize,
+	serde::Deserialize,
+	schemars::JsonSchema,
+	AsChangeset,
+	PartialEq,
+	Default,
+)]
 #[diesel(table_name=tracking_to_activity)]
 pub struct UpdateTrackingToActivity {
 	/// Field representing column `tracking_id`
@@ -62,31 +65,16 @@ pub struct UpdateTrackingToActivity {
 
 impl TrackingToActivity {
 	/// Insert a new row into `tracking_to_activity` with a given [`CreateTrackingToActivity`]
-	pub async fn create(
-		db: &mut Connection<DB>,
-		item: &CreateTrackingToActivity,
-	) -> QueryResult<Self> {
-		use crate::schema::tracking_to_activity::dsl::*;
-
+	pub async fn create(db: &mut DbConnection, item: &CreateTrackingToActivity) -> QueryResult<Self> {
 		trace!("Inserting into tracking_to_activity table: {:?}", item);
 		db.transaction(|mut conn| {
-			Box::pin(async move {
-				insert_into(tracking_to_activity)
-					.values(item)
-					.execute(&mut conn)
-					.await?;
-				tracking_to_activity
-					.select(TrackingToActivity::as_select())
-					.filter(id.eq(last_insert_id()))
-					.first::<Self>(&mut conn)
-					.await
-			})
+			Box::pin(async move { insert_returning!(&mut conn, tracking_to_activity, item, Self) })
 		})
 		.await
 	}
 
 	/// Get a row from `tracking_to_activity`, identified by the primary key
-	pub async fn read(db: &mut Connection<DB>, param_id: i32) -> QueryResult<Self> {
+	pub async fn read(db: &mut DbConnection, param_id: i32) -> QueryResult<Self> {
 		use crate::schema::tracking_to_activity::dsl::*;
 
 		trace!("Reading from tracking_to_activity table: {:?}", param_id);
@@ -98,7 +86,7 @@ impl TrackingToActivity {
 
 	/// Paginates through the table where page is a 0-based index (i.e. page 0 is the first page)
 	pub async fn paginate(
-		db: &mut Connection<DB>,
+		db: &mut DbConnection,
 		page: i64,
 		page_size: i64,
 	) -> QueryResult<PaginationResult<Self>> {
@@ -129,7 +117,7 @@ impl TrackingToActivity {
 
 	/// Update a row in `tracking_to_activity`, identified by the primary key with [`UpdateTrackingToActivity`]
 	pub async fn update(
-		db: &mut Connection<DB>,
+		db: &mut DbConnection,
 		param_id: i32,
 		item: &UpdateTrackingToActivity,
 	) -> QueryResult<Self> {
@@ -156,7 +144,7 @@ impl TrackingToActivity {
 	}
 
 	/// Delete a row in `tracking_to_activity`, identified by the primary key
-	pub async fn delete(db: &mut Connection<DB>, param_id: i32) -> QueryResult<usize> {
+	pub async fn delete(db: &mut DbConnection, param_id: i32) -> QueryResult<usize> {
 		use crate::schema::tracking_to_activity::dsl::*;
 
 		trace!("Deleting from tracking_to_activity table: {}", param_id);
@@ -165,7 +153,7 @@ impl TrackingToActivity {
 			.await
 	}
 
-	pub async fn delete_by_tracking_id(db: &mut Connection<DB>, param_id: i32) -> QueryResult<usize> {
+	pub async fn delete_by_tracking_id(db: &mut DbConnection, param_id: i32) -> QueryResult<usize> {
 		use crate::schema::tracking_to_activity::dsl::*;
 
 		trace!(
@@ -177,7 +165,7 @@ impl TrackingToActivity {
 			.await
 	}
 
-	pub async fn from_tracking(db: &mut Connection<DB>, param_id: i32) -> QueryResult<Vec<Self>> {
+	pub async fn from_tracking(db: &mut DbConnection, param_id: i32) -> QueryResult<Vec<Self>> {
 		use crate::schema::tracking_to_activity::dsl::*;
 
 		trace!("Reading from tracking_to_activity table: {:?}", param_id);
@@ -187,7 +175,7 @@ impl TrackingToActivity {
 			.await
 	}
 
-	pub async fn get_activity_ids(db: &mut Connection<DB>, param_id: i32) -> QueryResult<Vec<i32>> {
+	pub async fn get_activity_ids(db: &mut DbConnection, param_id: i32) -> QueryResult<Vec<i32>> {
 		let activities = Self::from_tracking(db, param_id).await?;
 		let mut result = Vec::new();
 		for i in activities {
@@ -196,10 +184,7 @@ impl TrackingToActivity {
 		Ok(result)
 	}
 
-	pub async fn get_activities(
-		db: &mut Connection<DB>,
-		param_id: i32,
-	) -> QueryResult<Vec<Activity>> {
+	pub async fn get_activities(db: &mut DbConnection, param_id: i32) -> QueryResult<Vec<Activity>> {
 		let activities = Self::from_tracking(db, param_id).await?;
 		let mut result = Vec::new();
 		for i in activities {
@@ -0,0 +1,124 @@
+use diesel::insert_into;
+use tracing::trace;
+
+use super::tracking::Tracking;
+use crate::{
+	db::{
+		prelude::*,
+		tag::{
+			normalize,
+			Tag,
+		},
+		DbConnection,
+	},
+	schema::*,
+};
+
+/// Struct representing a row in table `tracking_to_tag`
+#[derive(
+	Debug,
+	Clone,
+	serde::Serialize,
+	serde::Deserialize,
+	schemars::JsonSchema,
+	Queryable,
+	Selectable,
+	QueryableByName,
+	Associations,
+	Identifiable,
+)]
+#[diesel(table_name=tracking_to_tag, primary_key(id), belongs_to(Tag, foreign_key=tag_id) , belongs_to(Tracking, foreign_key=tracking_id))]
+pub struct TrackingToTag {
+	/// Field representing column `id`
+	pub id: i32,
+	/// Field representing column `tracking_id`
+	pub tracking_id: i32,
+	/// Field representing column `tag_id`
+	pub tag_id: i32,
+}
+
+/// Create Struct for a row in table `tracking_to_tag` for [`TrackingToTag`]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, schemars::JsonSchema, Insertable)]
+#[diesel(table_name=tracking_to_tag)]
+pub struct CreateTrackingToTag {
+	/// Field representing column `tracking_id`
+	pub tracking_id: i32,
+	/// Field representing column `tag_id`
+	pub tag_id: i32,
+}
+
+impl TrackingToTag {
+	/// Replace the tags of an entry, tags that don't exist yet are created
+	///
+	/// Returns the names of the tags of the entry, sorted by name
+	pub async fn set(
+		db: &mut DbConnection,
+		param_tracking_id: i32,
+		names: &[String],
+	) -> QueryResult<Vec<String>> {
+		use crate::schema::tracking_to_tag::dsl::*;
+
+		trace!(
+			"Setting tags of tracking {}: {:?}",
+			param_tracking_id,
+			names
+		);
+		let names = normalize(names);
+		db.transaction(|mut conn| {
+			Box::pin(async move {
+				diesel::delete(tracking_to_tag.filter(tracking_id.eq(param_tracking_id)))
+					.execute(&mut conn)
+					.await?;
+				let tags = Tag::find_or_create(conn, &names).await?;
+				let items = tags
+					.iter()
+					.map(|tag| CreateTrackingToTag {
+						tracking_id: param_tracking_id,
+						tag_id: tag.id,
+					})
+					.collect::<Vec<_>>();
+				#[cfg(not(feature = "sqlite"))]
+				if !items.is_empty() {
+					insert_into(tracking_to_tag)
+						.values(&items)
+						.execute(&mut conn)
+						.await?;
+				}
+				// diesel only splits a batch insert into single rows for its sync SQLite connection
+				#[cfg(feature = "sqlite")]
+				for item in &items {
+					insert_into(tracking_to_tag)
+						.values(item)
+						.execute(&mut conn)
+						.await?;
+				}
+				Ok(tags.into_iter().map(|tag| tag.name).collect())
+			})
+		})
+		.await
+	}
+
+	pub async fn delete_by_tracking_id(db: &mut DbConnection, param_id: i32) -> QueryResult<usize> {
+		use crate::schema::tracking_to_tag::dsl::*;
+
+		trace!(
+			"Deleting from tracking_to_tag table with tracking_id: {}",
+			param_id
+		);
+		diesel::delete(tracking_to_tag.filter(tracking_id.eq(param_id)))
+			.execute(db)
+			.await
+	}
+
+	/// Names of the tags of an entry, sorted by name
+	pub async fn get_tag_names(db: &mut DbConnection, param_id: i32) -> QueryResult<Vec<String>> {
+		trace!("Reading tags of tracking: {}", param_id);
+		tracking_to_tag::table
+			.inner_join(tag::table)
+			.filter(tracking_to_tag::tracking_id.eq(param_id))
+			.select(tag::name)
+			.order(tag::name)
+			.load::<String>(db)
+			.await
+	}
+}
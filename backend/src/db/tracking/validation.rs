@@ -0,0 +1,193 @@
+use rocket_db_pools::Connection;
+use tracing::trace;
+
+use super::{
+	CreateTracking,
+	Tracking,
+	UpdateTracking,
+};
+use crate::{
+	db::{
+		client::Client,
+		prelude::*,
+		project::Project,
+		project_activity::ProjectActivity,
+		project_budget::ProjectBudget,
+		project_member::ProjectMember,
+	},
+	schema::activity,
+	Error,
+	Result,
+	DB,
+};
+
+impl Tracking {
+	/// Check a new tracking entry against the rules that can't be expressed in the schema
+	///
+	/// Returns a `tracking.*` coded [`Error`] with the reason if the entry must not be booked.
+	/// Soft deleted projects, clients and activities count as missing.
+	pub async fn validate(db: &mut Connection<DB>, tracking: &CreateTracking) -> Result<()> {
+		trace!(
+			"Validating tracking for project {} on {}",
+			tracking.project_id,
+			tracking.date
+		);
+		let project = bookable_project(db, tracking.project_id).await?;
+		check_date(&project, tracking.date)?;
+		check_member(db, project.id, tracking.user_id).await?;
+		check_activities(db, project.id, &tracking.activities).await?;
+		check_client(db, tracking.client_id).await?;
+		check_budgets(db, project.id, &tracking.activities, None).await
+	}
+
+	/// Check a change of an existing entry, like [`Tracking::validate`] but only for what changes
+	///
+	/// A correction of the description stays possible after the project got archived, ended or
+	/// ran out of budget. The entry doesn't count against the budgets it is checked for.
+	pub async fn validate_update(
+		db: &mut Connection<DB>,
+		old: &Tracking,
+		update: &UpdateTracking,
+	) -> Result<()> {
+		trace!("Validating update of tracking {}", old.id);
+		let new = update.apply(old);
+		let moved = new.project_id != old.project_id;
+		let project = if moved {
+			Some(bookable_project(db, new.project_id).await?)
+		} else {
+			Project::read(db, new.project_id).await.optional()?
+		};
+		if let Some(project) = project.filter(|_| moved || new.date != old.date) {
+			check_date(&project, new.date)?;
+		}
+		if moved || new.user_id != old.user_id {
+			check_member(db, new.project_id, new.user_id).await?;
+		}
+		// on the same project only added activities have to exist and be allowed
+		let activities = new
+			.activities
+			.iter()
+			.copied()
+			.filter(|activity| moved || !old.activities.contains(activity))
+			.collect::<Vec<_>>();
+		check_activities(db, new.project_id, &activities).await?;
+		if new.client_id != old.client_id {
+			check_client(db, new.client_id).await?;
+		}
+		if moved || new.billed != old.billed || new.activities != old.activities {
+			check_budgets(db, new.project_id, &new.activities, Some(old.id)).await?;
+		}
+		Ok(())
+	}
+}
+
+/// The project, as long as entries may be booked on it
+async fn bookable_project(db: &mut Connection<DB>, project_id: i32) -> Result<Project> {
+	let project = Project::read(db, project_id)
+		.await
+		.optional()?
+		.ok_or_else(|| {
+			Error::bad_request("tracking.project_missing").with_detail("project_id", "does_not_exist")
+		})?;
+	if project.archived {
+		return Err(
+			Error::bad_request("tracking.project_archived").with_detail("project_id", "archived"),
+		);
+	}
+	Ok(project)
+}
+
+fn check_date(project: &Project, date: chrono::NaiveDate) -> Result<()> {
+	if project
+		.start_date
+		.is_some_and(|start_date| date < start_date)
+	{
+		return Err(
+			Error::bad_request("tracking.before_project_start")
+				.with_detail("date", "before_project_start"),
+		);
+	}
+	if project.end_date.is_some_and(|end_date| date > end_date) {
+		return Err(
+			Error::bad_request("tracking.after_project_end").with_detail("date", "after_project_end"),
+		);
+	}
+	Ok(())
+}
+
+async fn check_member(db: &mut Connection<DB>, project_id: i32, user_id: i32) -> Result<()> {
+	if !ProjectMember::is_member(db, project_id, user_id).await? {
+		return Err(Error::bad_request("tracking.not_member").with_detail("user_id", "not_member"));
+	}
+	Ok(())
+}
+
+async fn check_activities(
+	db: &mut Connection<DB>,
+	project_id: i32,
+	activities: &[i32],
+) -> Result<()> {
+	if activities.is_empty() {
+		return Ok(());
+	}
+	let existing = activity::table
+		.filter(activity::id.eq_any(activities))
+		.filter(activity::deleted_at.is_null())
+		.select(activity::id)
+		.load::<i32>(db)
+		.await?;
+	if activities
+		.iter()
+		.any(|activity| !existing.contains(activity))
+	{
+		return Err(
+			Error::bad_request("tracking.activity_missing").with_detail("activities", "does_not_exist"),
+		);
+	}
+
+	let allowed = ProjectActivity::allowed(db, project_id).await?;
+	if !allowed.is_empty()
+		&& activities
+			.iter()
+			.any(|activity| !allowed.contains(activity))
+	{
+		return Err(
+			Error::bad_request("tracking.activity_not_allowed").with_detail("activities", "not_allowed"),
+		);
+	}
+	Ok(())
+}
+
+async fn check_client(db: &mut Connection<DB>, client_id: i32) -> Result<()> {
+	let client = Client::read(db, client_id)
+		.await
+		.optional()?
+		.ok_or_else(|| {
+			Error::bad_request("tracking.client_missing").with_detail("client_id", "does_not_exist")
+		})?;
+	if client.archived {
+		return Err(
+			Error::bad_request("tracking.client_archived").with_detail("client_id", "archived"),
+		);
+	}
+	Ok(())
+}
+
+/// Hard stop budgets that are used up take no more entries, the entry `except` is left out
+async fn check_budgets(
+	db: &mut Connection<DB>,
+	project_id: i32,
+	activities: &[i32],
+	except: Option<i32>,
+) -> Result<()> {
+	for consumption in ProjectBudget::project_consumption(db, project_id, except).await? {
+		let budget = &consumption.budget;
+		let covered = budget
+			.activity_id
+			.is_none_or(|activity_id| activities.contains(&activity_id));
+		if budget.hard_stop && consumption.exhausted && covered {
+			return Err(Error::bad_request("tracking.budget_exhausted"));
+		}
+	}
+	Ok(())
+}
@@ -1,3 +1,8 @@
+use std::{
+	fmt,
+	sync::OnceLock,
+};
+
 use argon2::password_hash::{
 	PasswordHash,
 	PasswordVerifier,
@@ -8,13 +13,12 @@ use fake::{
 	faker::name::en::*,
 	Dummy,
 };
-use rocket_db_pools::{
-	diesel::{
-		insert_into,
-		prelude::*,
-	},
-	Connection,
+use rocket::{
+	Build,
+	Rocket,
 };
+use rocket_db_pools::Connection;
+use schemars::JsonSchema;
 use serde::{
 	Deserialize,
 	Serialize,
@@ -26,19 +30,60 @@ use tracing::{
 };
 
 use super::{
-	last_insert_id,
+	ListQuery,
 	PaginationResult,
 };
 use crate::{
+	auth::Tokenizer,
+	db::prelude::*,
 	schema::*,
 	Error,
 	Result,
 	DB,
 };
 
+list_query!(
+	user,
+	search: [username, firstname, lastname, email],
+	fields: {
+		id: i32,
+		username: String,
+		firstname: String,
+		lastname: String,
+		email: String,
+		sys_role: String,
+		manager_id: i32,
+		created_at: chrono::NaiveDateTime,
+		updated_at: chrono::NaiveDateTime,
+	}
+);
+
+crud!(user, User, CreateUser, UpdateUser, soft_delete);
+
+/// Hash the passwords of unknown emails are verified against, see [`User::check_credentials`]
+static DUMMY_HASH: OnceLock<String> = OnceLock::new();
+
+fn dummy_hash() -> &'static str {
+	DUMMY_HASH.get_or_init(|| {
+		Tokenizer::hash_password(b"not the password of anyone").expect("Could not hash")
+	})
+}
+
+/// Hashes the dummy password at startup
+///
+/// Otherwise the first login with an unknown email takes longer than the following ones, which
+/// tells that the email is unknown.
+pub async fn prepare_dummy_hash(rocket: Rocket<Build>) -> Rocket<Build> {
+	rocket::tokio::task::spawn_blocking(dummy_hash)
+		.await
+		.expect("Could not hash");
+	rocket
+}
+
 /// Struct representing a row in table `user`
-#[derive(Debug, Clone, Serialize, Deserialize, Queryable, Selectable, QueryableByName)]
+#[derive(Clone, Serialize, Deserialize, JsonSchema, Queryable, Selectable, QueryableByName)]
 #[diesel(table_name=user, primary_key(id))]
+#[typeshare::typeshare]
 pub struct User {
 	/// Field representing column `id`
 	pub id: i32,
@@ -51,6 +96,11 @@ pub struct User {
 	/// Field representing column `email`
 	pub email: String,
 	/// Field representing column `hash`
+	///
+	/// Never sent, neither in responses nor in the claims of the login token
+	#[serde(skip_serializing, default)]
+	#[schemars(skip)]
+	#[typeshare(skip)]
 	pub hash: String,
 	/// Field representing column `sys_role`
 	pub sys_role: String,
@@ -58,12 +108,17 @@ pub struct User {
 	pub created_at: chrono::NaiveDateTime,
 	/// Field representing column `updated_at`
 	pub updated_at: chrono::NaiveDateTime,
+	/// Field representing column `deleted_at`
+	pub deleted_at: Option<chrono::NaiveDateTime>,
+	/// Field representing column `manager_id`
+	pub manager_id: Option<i32>,
 }
 
 /// Create Struct for a row in table `user` for [`User`]
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, Insertable)]
+#[derive(Clone, serde::Serialize, serde::Deserialize, schemars::JsonSchema, Insertable)]
 #[cfg_attr(test, derive(Dummy))]
 #[diesel(table_name=user)]
+#[typeshare::typeshare]
 pub struct CreateUser {
 	/// Field representing column `username`
 	#[cfg_attr(test, dummy(faker = "Username()"))]
@@ -84,11 +139,17 @@ pub struct CreateUser {
 	/// Field representing column `sys_role`
 	#[cfg_attr(test, dummy(expr = "\"user\".into()"))]
 	pub sys_role: String,
+	/// Field representing column `manager_id`
+	#[cfg_attr(test, dummy(default))]
+	pub manager_id: Option<i32>,
 }
 
 /// Update Struct for a row in table `user` for [`User`]
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, AsChangeset, PartialEq, Default)]
+#[derive(
+	Clone, serde::Serialize, serde::Deserialize, schemars::JsonSchema, AsChangeset, PartialEq, Default,
+)]
 #[diesel(table_name=user)]
+#[typeshare::typeshare]
 pub struct UpdateUser {
 	/// Field representing column `username`
 	pub username: Option<String>,
@@ -103,16 +164,76 @@ pub struct UpdateUser {
 	pub password: Option<String>,
 	/// Field representing column `sys_role`
 	pub sys_role: Option<String>,
+	/// Field representing column `manager_id`
+	#[serde(
+		default,
+		skip_serializing_if = "Option::is_none",
+		deserialize_with = "serde_with::rust::double_option::deserialize"
+	)]
+	pub manager_id: Option<Option<i32>>,
 	/// Field representing column `created_at`
 	pub created_at: Option<chrono::NaiveDateTime>,
 	/// Field representing column `updated_at`
 	pub updated_at: Option<chrono::NaiveDateTime>,
 }
 
+// The user structs are logged, e.g. by `crud!`, the password and its hash must not end up there
+
+impl fmt::Debug for User {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("User")
+			.field("id", &self.id)
+			.field("username", &self.username)
+			.field("firstname", &self.firstname)
+			.field("lastname", &self.lastname)
+			.field("email", &self.email)
+			.field("hash", &"**REDACTED**")
+			.field("sys_role", &self.sys_role)
+			.field("created_at", &self.created_at)
+			.field("updated_at", &self.updated_at)
+			.field("deleted_at", &self.deleted_at)
+			.field("manager_id", &self.manager_id)
+			.finish()
+	}
+}
+
+impl fmt::Debug for CreateUser {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("CreateUser")
+			.field("username", &self.username)
+			.field("firstname", &self.firstname)
+			.field("lastname", &self.lastname)
+			.field("email", &self.email)
+			.field("password", &"**REDACTED**")
+			.field("sys_role", &self.sys_role)
+			.field("manager_id", &self.manager_id)
+			.finish()
+	}
+}
+
+impl fmt::Debug for UpdateUser {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("UpdateUser")
+			.field("username", &self.username)
+			.field("firstname", &self.firstname)
+			.field("lastname", &self.lastname)
+			.field("email", &self.email)
+			.field("password", &self.password.as_ref().map(|_| "**REDACTED**"))
+			.field("sys_role", &self.sys_role)
+			.field("manager_id", &self.manager_id)
+			.field("created_at", &self.created_at)
+			.field("updated_at", &self.updated_at)
+			.finish()
+	}
+}
+
 impl User {
 	/// Gets the Hash from the database where email matches,
 	/// hashes the password and compares newly generated hash
 	/// with hash from the database
+	///
+	/// Unknown emails fail with [`Error::WrongCredentials`] as well, after verifying the password
+	/// against a dummy hash so they take as long as a wrong password.
 	pub async fn check_credentials(
 		db: &mut Connection<DB>,
 		email: &str,
@@ -123,18 +244,24 @@ impl User {
 		trace!("Checking credentials for {}", email);
 		let rec = dsl::user
 			.filter(dsl::email.eq(email))
+			.filter(dsl::deleted_at.is_null())
 			.first::<Self>(db)
-			.await?;
+			.await
+			.optional()?;
+		let Some(rec) = rec else {
+			let hash = PasswordHash::new(dummy_hash()).expect("Dummy hash is valid");
+			let _ = argon2::Argon2::default().verify_password(password.as_bytes(), &hash);
+			trace!("No user with email {}", email);
+			return Err(Error::WrongCredentials);
+		};
 		debug!("Found user: {:?}", rec.username);
-		let hash = PasswordHash::new(&rec.hash);
-		if let Err(err) = hash {
-			error!(
-				"Hash is invalid.\nuser: {}\nhash: {}\npassword: {}",
-				rec.username, rec.hash, password
-			);
-			return Err(Error::Argon2PasswordHash(err));
-		}
-		let hash = hash.unwrap();
+		let hash = match PasswordHash::new(&rec.hash) {
+			Ok(hash) => hash,
+			Err(err) => {
+				error!("Hash of user {} is invalid: {}", rec.id, err);
+				return Err(Error::Argon2PasswordHash(err));
+			}
+		};
 		match argon2::Argon2::default().verify_password(password.as_bytes(), &hash) {
 			Ok(_) => {
 				trace!("Logged in as {}", rec.username);
@@ -147,101 +274,122 @@ impl User {
 		}
 	}
 
-	/// Insert a new row into `user` with a given [`CreateUser`]
-	pub async fn create(db: &mut Connection<DB>, item: &CreateUser) -> QueryResult<Self> {
-		use crate::schema::user::dsl::*;
-
-		trace!("Inserting into user table: {:?}", item);
-		db.transaction(|mut conn| {
-			Box::pin(async move {
-				insert_into(user).values(item).execute(&mut conn).await?;
-				user
-					.select(User::as_select())
-					.filter(id.eq(last_insert_id()))
-					.first::<Self>(&mut conn)
-					.await
-			})
-		})
-		.await
-	}
-
-	/// Get a row from `user`, identified by the primary key
-	pub async fn read(db: &mut Connection<DB>, param_id: i32) -> QueryResult<Self> {
-		use crate::schema::user::dsl::*;
-		trace!("Reading from user table: {}", param_id);
-		user.filter(id.eq(param_id)).first::<Self>(db).await
-	}
-
 	/// Paginates through the table where page is a 0-based index (i.e. page 0 is the first page)
+	///
+	/// Soft deleted rows are skipped
 	pub async fn paginate(
 		db: &mut Connection<DB>,
 		page: i64,
 		page_size: i64,
-	) -> QueryResult<PaginationResult<Self>> {
+		list: &ListQuery,
+	) -> Result<PaginationResult<Self>> {
 		use crate::schema::user::dsl::*;
 
 		trace!(
-			"Paginating through user table: page {}, page_size {}",
+			"Paginating through user table: page {}, page_size {}, {:?}",
 			page,
-			page_size
+			page_size,
+			list
 		);
-		let page_size = if page_size < 1 { 1 } else { page_size };
-		let total_items = user.count().get_result(db).await?;
-		let items = user
-			.limit(page_size)
-			.offset(page * page_size)
-			.load::<Self>(db)
-			.await?;
-
-		Ok(PaginationResult {
-			items,
-			total_items,
+		Self::paginate_query(
+			db,
+			|| list_filter(user.filter(deleted_at.is_null()).into_boxed(), list),
+			list,
 			page,
 			page_size,
-			/* ceiling division of integers */
-			num_pages: total_items / page_size + i64::from(total_items % page_size != 0),
-		})
+		)
+		.await
 	}
 
-	/// Update a row in `user`, identified by the primary key with [`UpdateUser`]
-	pub async fn update(
+	/// Count the rows of other tables that still reference a row in `user`
+	///
+	/// Only tables with at least one reference are returned
+	pub async fn references(
 		db: &mut Connection<DB>,
 		param_id: i32,
-		item: &UpdateUser,
-	) -> QueryResult<Self> {
-		use crate::schema::user::dsl::*;
-
-		trace!("Updating user: {} with {:?}", param_id, item);
-		db.transaction(|mut conn| {
-			Box::pin(async move {
-				diesel::update(user.filter(id.eq(param_id)))
-					.set(item)
-					.execute(&mut conn)
-					.await?;
-				user.filter(id.eq(param_id)).first::<Self>(&mut conn).await
-			})
-		})
-		.await
+	) -> QueryResult<Vec<(&'static str, i64)>> {
+		trace!("Counting references to user: {}", param_id);
+		let tracking: i64 = tracking::table
+			.filter(
+				tracking::user_id
+					.eq(param_id)
+					.or(tracking::approved_by.eq(param_id)),
+			)
+			.count()
+			.get_result(db)
+			.await?;
+		let project_member: i64 = project_member::table
+			.filter(project_member::user_id.eq(param_id))
+			.count()
+			.get_result(db)
+			.await?;
+		let reports: i64 = user::table
+			.filter(user::manager_id.eq(param_id))
+			.count()
+			.get_result(db)
+			.await?;
+		let team: i64 = team::table
+			.filter(team::manager_id.eq(param_id))
+			.count()
+			.get_result(db)
+			.await?;
+		let team_member: i64 = team_member::table
+			.filter(team_member::user_id.eq(param_id))
+			.count()
+			.get_result(db)
+			.await?;
+		Ok(
+			[
+				("tracking", tracking),
+				("project_member", project_member),
+				("user", reports),
+				("team", team),
+				("team_member", team_member),
+			]
+			.into_iter()
+			.filter(|(_, count)| *count > 0)
+			.collect(),
+		)
 	}
 
-	/// Delete a row in `user`, identified by the primary key
-	pub async fn delete(db: &mut Connection<DB>, param_id: i32) -> QueryResult<usize> {
+	/// Whether `manager` is somewhere above `param_id` in the reporting chain
+	///
+	/// Follows `manager_id` upwards, a cycle ends the walk instead of looping forever
+	pub async fn reports_to(
+		db: &mut Connection<DB>,
+		param_id: i32,
+		manager: i32,
+	) -> QueryResult<bool> {
 		use crate::schema::user::dsl::*;
 
-		trace!("Deleting from user table: {}", param_id);
-		diesel::delete(user.filter(id.eq(param_id)))
-			.execute(db)
+		trace!("Checking if user {} reports to {}", param_id, manager);
+		let mut visited = vec![param_id];
+		let mut current = param_id;
+		while let Some(next) = user
+			.filter(id.eq(current))
+			.select(manager_id)
+			.first::<Option<i32>>(db)
 			.await
+			.optional()?
+			.flatten()
+		{
+			if next == manager {
+				return Ok(true);
+			}
+			if visited.contains(&next) {
+				return Ok(false);
+			}
+			visited.push(next);
+			current = next;
+		}
+		Ok(false)
 	}
 
-	pub async fn last_page(db: &mut Connection<DB>, page_size: i64) -> QueryResult<i64> {
+	pub async fn last_page(db: &mut Connection<DB>, page_size: i64, list: &ListQuery) -> Result<i64> {
 		use crate::schema::user::dsl::*;
 
-		trace!("Getting last page of user table for page_size {page_size}");
-
-		let total_items: i64 = user.count().get_result(db).await?;
-		// index starts at 0
-		Ok((total_items / page_size + i64::from(total_items % page_size != 0)) - 1)
+		let query = list_filter(user.filter(deleted_at.is_null()).into_boxed(), list)?;
+		Self::last_page_query(db, query, page_size).await
 	}
 }
 
@@ -253,10 +401,11 @@ impl PartialEq for User {
 			&& self.firstname == other.firstname
 			&& self.lastname == other.lastname
 			&& self.email == other.email
-			&& self.hash == other.hash
 			&& self.sys_role == other.sys_role
 			&& self.created_at == other.created_at
 			&& self.updated_at == other.updated_at
+			&& self.deleted_at == other.deleted_at
+			&& self.manager_id == other.manager_id
 	}
 }
 
@@ -268,5 +417,6 @@ impl PartialEq<CreateUser> for User {
 			&& self.lastname == other.lastname
 			&& self.email == other.email
 			&& self.sys_role == other.sys_role
+			&& self.manager_id == other.manager_id
 	}
 }
@@ -1,3 +1,7 @@
+use diesel::result::{
+	DatabaseErrorInformation,
+	DatabaseErrorKind,
+};
 use rocket::{
 	http::{
 		ContentType,
@@ -10,15 +14,34 @@ use rocket::{
 	},
 	serde::json::Json,
 };
-use serde::{
-	ser::{
-		SerializeStruct,
-		Serializer,
+use rocket_okapi::{
+	gen::OpenApiGenerator,
+	okapi::{
+		map,
+		openapi3::{
+			MediaType,
+			Response,
+			Responses,
+		},
 	},
+	response::OpenApiResponderInner,
+};
+use schemars::JsonSchema;
+use serde::{
+	ser::Serializer,
 	Deserialize,
 	Serialize,
 };
 use thiserror::Error;
+use tracing::error;
+
+use crate::{
+	i18n::{
+		Arg,
+		Lang,
+	},
+	request_id::RequestId,
+};
 
 pub type Result<T> = std::result::Result<T, Error>;
 
@@ -36,7 +59,14 @@ pub enum Error {
 	#[error("Error setting up CORS: {0}")]
 	RocketCors(#[from] rocket_cors::Error),
 	#[error("Launch Failed: {0}")]
-	Rocket(#[from] rocket::Error),
+	Rocket(#[from] Box<rocket::Error>),
+	#[error("Invalid configuration: {0}")]
+	Config(Box<rocket::figment::Error>),
+	#[error("Could not open the log directory {}: {1}", .0.display())]
+	LogDir(
+		std::path::PathBuf,
+		#[source] tracing_appender::rolling::InitError,
+	),
 	#[error("Could not sign token: {0}")]
 	JWTSign(#[source] jwt_simple::Error),
 	#[error("Could not verify token: {0}")]
@@ -55,37 +85,352 @@ pub enum Error {
 	UnauthenticatedUser,
 	#[error("User does not have access rights")]
 	ForbiddenAccess,
-	#[error("{0}")]
-	BadRequest(String),
 	#[error("Wrong Credentials")]
 	WrongCredentials,
 	#[error("Unauthorized")]
 	Unauthorized,
+	/// A rejected request with a stable `code` clients can match on, the message is the one
+	/// the [`Lang`] catalogs have for the code
+	#[error("{code}")]
+	Coded {
+		status: Status,
+		code: &'static str,
+		args: Vec<Arg>,
+		details: Vec<ErrorDetail>,
+	},
 }
 
 impl Error {
+	/// Rejects the request with `400 Bad Request`
+	pub fn bad_request(code: &'static str) -> Self {
+		Self::coded(Status::BadRequest, code)
+	}
+
+	/// Rejects the request with `409 Conflict`
+	pub fn conflict(code: &'static str) -> Self {
+		Self::coded(Status::Conflict, code)
+	}
+
+	/// Rejects the request with `429 Too Many Requests`
+	pub fn too_many_requests(code: &'static str) -> Self {
+		Self::coded(Status::TooManyRequests, code)
+	}
+
+	fn coded(status: Status, code: &'static str) -> Self {
+		Self::Coded {
+			status,
+			code,
+			args: Vec::new(),
+			details: Vec::new(),
+		}
+	}
+
+	/// Adds the next value of the message of a [`Error::Coded`], other errors are returned as is
+	pub fn with_arg(self, value: impl ToString) -> Self {
+		self.push_arg(Arg::Value(value.to_string()))
+	}
+
+	/// Adds a table as the next value of the message, in the language of the response
+	pub fn with_entity(self, table: &str) -> Self {
+		self.push_arg(Arg::Entity(table.to_string()))
+	}
+
+	/// Adds the numbers of rows in other tables as the next value of the message
+	pub fn with_entities(self, counts: Vec<(i64, String)>) -> Self {
+		self.push_arg(Arg::Entities(counts))
+	}
+
+	fn push_arg(mut self, arg: Arg) -> Self {
+		if let Self::Coded { args, .. } = &mut self {
+			args.push(arg);
+		}
+		self
+	}
+
+	/// Names the request field that caused a [`Error::Coded`] and the problem with it
+	pub fn with_detail(mut self, field: &str, code: &str) -> Self {
+		if let Self::Coded { details, .. } = &mut self {
+			details.push(ErrorDetail::new(field, code));
+		}
+		self
+	}
+
 	fn to_status(&self) -> Status {
 		match self {
-			Self::NotFound => Status::NotFound,
+			Self::NotFound | Self::Database(diesel::result::Error::NotFound) => Status::NotFound,
 			Self::UnauthenticatedUser | Self::WrongCredentials | Self::Unauthorized => {
 				Status::Unauthorized
 			}
 			Self::ForbiddenAccess => Status::Forbidden,
-			Self::BadRequest(_) | Self::JWT(_) => Status::BadRequest,
+			Self::JWT(_) => Status::BadRequest,
+			Self::Coded { status, .. } => *status,
+			Self::Database(diesel::result::Error::DatabaseError(kind, info)) => {
+				Violation::new(kind, info.as_ref()).map_or(Status::InternalServerError, |v| v.status)
+			}
 			_ => Status::InternalServerError,
 		}
 	}
+
+	/// The body of the error response
+	pub fn to_json(&self) -> ErrorJson {
+		let status = self.to_status();
+		// the message comes from the code, the details of server side failures only go to the log
+		let (error_code, args, details) = match self {
+			Self::WrongCredentials => (
+				"login.wrong_credentials".to_string(),
+				Vec::new(),
+				Vec::new(),
+			),
+			Self::Database(diesel::result::Error::DatabaseError(kind, info)) => {
+				match Violation::new(kind, info.as_ref()) {
+					Some(violation) => (violation.code, violation.args, violation.details),
+					None => ("database".to_string(), Vec::new(), Vec::new()),
+				}
+			}
+			Self::Coded {
+				code,
+				args,
+				details,
+				..
+			} => (code.to_string(), args.clone(), details.clone()),
+			_ => (status_code(status), Vec::new(), Vec::new()),
+		};
+		ErrorJson {
+			error: Lang::En
+				.message(&error_code, &args)
+				.unwrap_or_else(|| error_code.clone()),
+			code: status.code,
+			error_code,
+			details,
+			request_id: None,
+			args,
+		}
+	}
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-pub struct ErrorJson<'a> {
-	error: &'a str,
-	code: u16,
+/// A constraint the database refused a statement for
+struct Violation {
+	status: Status,
+	code: String,
+	/// The table the message is about
+	args: Vec<Arg>,
+	details: Vec<ErrorDetail>,
 }
 
-impl<'a> ErrorJson<'a> {
-	pub fn new(code: u16, error: &'a str) -> Self {
-		Self { error, code }
+impl Violation {
+	/// Classifies a database error, `None` for errors the client can't do anything about
+	///
+	/// Postgres names the table and columns in the error fields, mysql and SQLite only in the message
+	fn new(kind: &DatabaseErrorKind, info: &dyn DatabaseErrorInformation) -> Option<Self> {
+		let message = info.message();
+		let details = info.details().unwrap_or_default();
+		match kind {
+			DatabaseErrorKind::UniqueViolation => {
+				// postgres: `Key (name)=(acme) already exists.`
+				// mysql: `Duplicate entry 'acme' for key 'client.name'`
+				// sqlite: `UNIQUE constraint failed: project_member.project_id, project_member.user_id`
+				let (table, columns) = if let Some(columns) = between(details, "Key (", ")=") {
+					(
+						info.table_name().map(str::to_string),
+						columns.split(", ").map(str::to_string).collect(),
+					)
+				} else if let Some(columns) = message.strip_prefix("UNIQUE constraint failed: ") {
+					let columns = columns
+						.split(", ")
+						.filter_map(|column| column.split_once('.'))
+						.collect::<Vec<_>>();
+					(
+						columns.first().map(|(table, _)| table.to_string()),
+						columns
+							.iter()
+							.map(|(_, column)| column.to_string())
+							.collect(),
+					)
+				} else {
+					let key = between(message, "for key '", "'").unwrap_or_default();
+					match key.split_once('.') {
+						Some((table, key)) => (Some(table.to_string()), vec![key.to_string()]),
+						None => (None, vec![key.to_string()]),
+					}
+				};
+				let table = table.unwrap_or_else(|| "entry".to_string());
+				// mysql names a unique index after its first column
+				let column = columns.first().cloned().unwrap_or_default();
+				Some(Self {
+					status: Status::Conflict,
+					code: format!("{table}.{column}_taken"),
+					args: vec![Arg::Entity(table)],
+					details: columns
+						.iter()
+						.map(|column| ErrorDetail::new(column, "taken"))
+						.collect(),
+				})
+			}
+			DatabaseErrorKind::ForeignKeyViolation => {
+				// postgres: `Key (project_id)=(5) is not present in table "project".`
+				// or `Key (id)=(5) is still referenced from table "tracking".`
+				if let Some(referenced) = between(details, "is not present in table \"", "\"") {
+					let column = between(details, "Key (", ")=").unwrap_or_default();
+					return Some(Self::missing(referenced, column));
+				}
+				if let Some(referencing) = between(details, "referenced from table \"", "\"") {
+					return Some(Self::referenced(referencing));
+				}
+				// sqlite names neither the table nor whether the row is missing or still referenced
+				if message == "FOREIGN KEY constraint failed" {
+					return Some(Self {
+						status: Status::BadRequest,
+						code: "fk.violation".to_string(),
+						args: Vec::new(),
+						details: Vec::new(),
+					});
+				}
+				// mysql: `Cannot add or update a child row: a foreign key constraint fails
+				// (`db`.`tracking`, CONSTRAINT `…` FOREIGN KEY (`project_id`) REFERENCES `project` (`id`))`
+				let referencing = between(message, "`.`", "`").unwrap_or_default();
+				if message.starts_with("Cannot delete or update a parent row") {
+					return Some(Self::referenced(referencing));
+				}
+				let referenced = between(message, "REFERENCES `", "`").unwrap_or_default();
+				let column = between(message, "FOREIGN KEY (`", "`)").unwrap_or_default();
+				Some(Self::missing(referenced, column))
+			}
+			DatabaseErrorKind::NotNullViolation => {
+				// mysql: `Column 'name' cannot be null`
+				// sqlite: `NOT NULL constraint failed: client.name`
+				let column = info
+					.column_name()
+					.or_else(|| between(message, "Column '", "'"))
+					.or_else(|| {
+						message
+							.strip_prefix("NOT NULL constraint failed: ")
+							.and_then(|column| column.split_once('.'))
+							.map(|(_, column)| column)
+					})
+					.unwrap_or_default();
+				Some(Self {
+					status: Status::BadRequest,
+					code: format!("{column}_required"),
+					args: Vec::new(),
+					details: vec![ErrorDetail::new(column, "required")],
+				})
+			}
+			DatabaseErrorKind::CheckViolation => Some(Self {
+				status: Status::BadRequest,
+				code: "check_violation".to_string(),
+				args: Vec::new(),
+				details: Vec::new(),
+			}),
+			_ => None,
+		}
+	}
+
+	/// An inserted or updated row points to a row that does not exist
+	fn missing(referenced: &str, column: &str) -> Self {
+		Self {
+			status: Status::BadRequest,
+			code: format!("fk.{referenced}_missing"),
+			args: vec![Arg::Entity(referenced.to_string())],
+			details: vec![ErrorDetail::new(column, "does_not_exist")],
+		}
+	}
+
+	/// A deleted row is still in use by `referencing`
+	fn referenced(referencing: &str) -> Self {
+		Self {
+			status: Status::Conflict,
+			code: format!("fk.{referencing}_references"),
+			args: vec![Arg::Entity(referencing.to_string())],
+			details: Vec::new(),
+		}
+	}
+}
+
+/// The code of errors without a more specific one, `Not Found` as `not_found`
+fn status_code(status: Status) -> String {
+	status
+		.reason_lossy()
+		.to_lowercase()
+		.replace([' ', '-'], "_")
+}
+
+/// The part of `haystack` between `start` and the following `end`
+fn between<'a>(haystack: &'a str, start: &str, end: &str) -> Option<&'a str> {
+	let rest = &haystack[haystack.find(start)? + start.len()..];
+	Some(&rest[..rest.find(end)?])
+}
+
+/// A message about a single field of the request
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[typeshare::typeshare]
+pub struct ErrorDetail {
+	pub field: String,
+	/// Stable identifier of the problem, e.g. `taken` or `does_not_exist`
+	pub code: String,
+	pub message: String,
+}
+
+impl ErrorDetail {
+	fn new(field: &str, code: &str) -> Self {
+		Self {
+			field: field.to_string(),
+			code: code.to_string(),
+			message: Lang::En.detail(code).unwrap_or_else(|| code.to_string()),
+		}
+	}
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, JsonSchema)]
+#[typeshare::typeshare]
+pub struct ErrorJson {
+	/// Human readable message
+	pub error: String,
+	/// HTTP status
+	pub code: u16,
+	/// Stable identifier of the error, e.g. `client.name_taken` or `fk.project_missing`
+	pub error_code: String,
+	pub details: Vec<ErrorDetail>,
+	/// Id of the request in the server logs, also sent as `X-Request-Id` header
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub request_id: Option<String>,
+	/// Values of the message, to translate it
+	#[serde(skip)]
+	#[schemars(skip)]
+	#[typeshare(skip)]
+	args: Vec<Arg>,
+}
+
+impl ErrorJson {
+	pub fn new(code: u16, error: &str) -> Self {
+		Self {
+			error: error.to_string(),
+			code,
+			error_code: status_code(Status::new(code)),
+			details: Vec::new(),
+			request_id: None,
+			args: Vec::new(),
+		}
+	}
+
+	/// Adds the id the `RequestIds` fairing assigned to `request`
+	pub fn for_request(mut self, request: &Request<'_>) -> Self {
+		self.request_id = RequestId::of(request);
+		self.localized(Lang::negotiate(request))
+	}
+
+	/// Translates the messages, the codes stay as they are
+	///
+	/// Messages of codes missing from the catalog, like rare HTTP status, stay english.
+	pub fn localized(mut self, lang: Lang) -> Self {
+		if let Some(error) = lang.message(&self.error_code, &self.args) {
+			self.error = error;
+		}
+		for detail in &mut self.details {
+			if let Some(message) = lang.detail(&detail.code) {
+				detail.message = message;
+			}
+		}
+		self
 	}
 }
 
@@ -94,11 +439,13 @@ impl Serialize for Error {
 	where
 		S: Serializer,
 	{
-		let mut state = serializer.serialize_struct("Error", 2)?;
-		state.serialize_field("error", &self.to_string())?;
-		state.serialize_field("code", &self.to_status().code)?;
+		self.to_json().serialize(serializer)
+	}
+}
 
-		state.end()
+impl From<rocket::figment::Error> for Error {
+	fn from(e: rocket::figment::Error) -> Self {
+		Error::Config(Box::new(e))
 	}
 }
 
@@ -111,9 +458,190 @@ impl From<argon2::password_hash::Error> for Error {
 impl<'r> Responder<'r, 'static> for Error {
 	fn respond_to(self, request: &'r Request<'_>) -> response::Result<'static> {
 		let status = self.to_status();
-		response::Response::build_from(Json(self).respond_to(request)?)
+		let json = self.to_json().for_request(request);
+		if status == Status::InternalServerError {
+			let request_id = json.request_id.as_deref().unwrap_or("none");
+			error!(error_code = %json.error_code, request_id = %request_id, "{self}");
+		}
+		response::Response::build_from(Json(json).respond_to(request)?)
 			.status(status)
 			.header(ContentType::JSON)
 			.ok()
 	}
 }
+
+impl OpenApiResponderInner for Error {
+	/// Every status an [`Error`] may end up as, all of them with an [`ErrorJson`] body
+	fn responses(gen: &mut OpenApiGenerator) -> rocket_okapi::Result<Responses> {
+		let schema = gen.json_schema::<ErrorJson>();
+		let responses = [400, 401, 403, 404, 409, 429, 500]
+			.into_iter()
+			.map(|code| {
+				let response = Response {
+					description: Status::new(code).reason_lossy().to_string(),
+					content: map! {
+						"application/json".to_string() => MediaType {
+							schema: Some(schema.clone()),
+							..Default::default()
+						}
+					},
+					..Default::default()
+				};
+				(code.to_string(), response.into())
+			})
+			.collect();
+		Ok(Responses {
+			responses,
+			..Default::default()
+		})
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use diesel::result::{
+		DatabaseErrorKind,
+		Error as DieselError,
+	};
+	use pretty_assertions::assert_eq;
+	use rocket::{
+		local::blocking::Client,
+		response::Responder,
+	};
+
+	use super::{
+		Error,
+		ErrorDetail,
+		ErrorJson,
+	};
+	use crate::request_id::RequestId;
+
+	/// The response to a database error that, like mysql and SQLite, only comes with a message
+	fn mysql(kind: DatabaseErrorKind, message: &str) -> ErrorJson {
+		Error::Database(DieselError::DatabaseError(
+			kind,
+			Box::new(message.to_string()),
+		))
+		.to_json()
+	}
+
+	#[test]
+	fn mysql_unique_violation() {
+		let json = mysql(
+			DatabaseErrorKind::UniqueViolation,
+			"Duplicate entry 'acme' for key 'client.name'",
+		);
+		assert_eq!(json.code, 409);
+		assert_eq!(json.error_code, "client.name_taken");
+		assert_eq!(json.error, "Client already exists");
+		assert_eq!(json.details, [ErrorDetail::new("name", "taken")]);
+	}
+
+	#[test]
+	fn mysql_foreign_key_violation() {
+		let json = mysql(
+			DatabaseErrorKind::ForeignKeyViolation,
+			"Cannot add or update a child row: a foreign key constraint fails (`zeit`.`tracking`, \
+			 CONSTRAINT `tracking_ibfk_1` FOREIGN KEY (`project_id`) REFERENCES `project` (`id`))",
+		);
+		assert_eq!(json.code, 400);
+		assert_eq!(json.error_code, "fk.project_missing");
+		assert_eq!(json.error, "Project does not exist");
+		assert_eq!(
+			json.details,
+			[ErrorDetail::new("project_id", "does_not_exist")]
+		);
+
+		let json = mysql(
+			DatabaseErrorKind::ForeignKeyViolation,
+			"Cannot delete or update a parent row: a foreign key constraint fails \
+			 (`zeit`.`project_member`, CONSTRAINT `project_member_ibfk_1` FOREIGN KEY (`project_id`) \
+			 REFERENCES `project` (`id`))",
+		);
+		assert_eq!(json.code, 409);
+		assert_eq!(json.error_code, "fk.project_member_references");
+		assert_eq!(json.error, "Still referenced by project member");
+		assert!(json.details.is_empty());
+	}
+
+	#[test]
+	fn mysql_not_null_violation() {
+		let json = mysql(
+			DatabaseErrorKind::NotNullViolation,
+			"Column 'name' cannot be null",
+		);
+		assert_eq!(json.code, 400);
+		assert_eq!(json.error_code, "name_required");
+		assert_eq!(json.details, [ErrorDetail::new("name", "required")]);
+	}
+
+	#[test]
+	fn sqlite_unique_violation() {
+		let json = mysql(
+			DatabaseErrorKind::UniqueViolation,
+			"UNIQUE constraint failed: project_member.project_id, project_member.user_id",
+		);
+		assert_eq!(json.code, 409);
+		assert_eq!(json.error_code, "project_member.project_id_taken");
+		assert_eq!(
+			json.details,
+			[
+				ErrorDetail::new("project_id", "taken"),
+				ErrorDetail::new("user_id", "taken")
+			]
+		);
+	}
+
+	#[test]
+	fn sqlite_foreign_key_violation() {
+		let json = mysql(
+			DatabaseErrorKind::ForeignKeyViolation,
+			"FOREIGN KEY constraint failed",
+		);
+		assert_eq!(json.code, 400);
+		assert_eq!(json.error_code, "fk.violation");
+		assert_eq!(
+			json.error,
+			"A referenced entry does not exist or is still in use"
+		);
+		assert!(json.details.is_empty());
+	}
+
+	#[test]
+	fn sqlite_not_null_violation() {
+		let json = mysql(
+			DatabaseErrorKind::NotNullViolation,
+			"NOT NULL constraint failed: client.name",
+		);
+		assert_eq!(json.code, 400);
+		assert_eq!(json.error_code, "name_required");
+		assert_eq!(json.details, [ErrorDetail::new("name", "required")]);
+	}
+
+	#[test]
+	fn unclassified_database_error() {
+		let json = mysql(
+			DatabaseErrorKind::SerializationFailure,
+			"Deadlock found when trying to get lock; try restarting transaction",
+		);
+		assert_eq!(json.code, 500);
+		assert_eq!(json.error_code, "database");
+		// the database message stays in the log
+		assert_eq!(json.error, "Internal error");
+	}
+
+	#[tracing_test::traced_test]
+	#[test]
+	fn internal_error_logs_request_id() {
+		let client = Client::untracked(rocket::build()).unwrap();
+		let request = client.get("/");
+		request
+			.inner()
+			.local_cache(|| Some(RequestId("ticket-500".to_string())));
+
+		let response = Error::Internal.respond_to(request.inner()).unwrap();
+		assert_eq!(response.status().code, 500);
+		assert!(logs_contain("error_code=internal_server_error"));
+		assert!(logs_contain("request_id=ticket-500"));
+	}
+}
@@ -9,11 +9,27 @@ use rocket::{
 	},
 	State,
 };
+use rocket_db_pools::Connection;
+use rocket_okapi::{
+	gen::OpenApiGenerator,
+	okapi::openapi3::{
+		SecurityRequirement,
+		SecurityScheme,
+		SecuritySchemeData,
+	},
+	request::{
+		OpenApiFromRequest,
+		RequestHeaderInput,
+	},
+};
 
 use crate::{
 	auth::Tokenizer,
 	db::user::User,
 	error::Error,
+	request_id::RequestUser,
+	Result,
+	DB,
 };
 
 #[async_trait]
@@ -26,7 +42,7 @@ impl<'r> FromRequest<'r> for User {
 			.await
 			.map_error(|_| { (Status::Unauthorized, Error::UnauthenticatedUser) }));
 
-		let token: Result<User, Error> = req
+		let token: Result<User> = req
 			.headers()
 			.get_one("Authorization")
 			.map(|header| header.split("Bearer").collect::<Vec<_>>())
@@ -40,9 +56,72 @@ impl<'r> FromRequest<'r> for User {
 				tokenizer.verify(token)
 			});
 
-		match token {
-			Ok(user) => Outcome::Success(user),
-			Err(err) => Outcome::Error((Status::Unauthorized, err)),
+		let user = match token {
+			Ok(user) => user,
+			Err(err) => return Outcome::Error((Status::Unauthorized, err)),
+		};
+
+		// tokens outlive a soft delete of their user, `read` skips deleted users
+		let mut db = try_outcome!(req
+			.guard::<Connection<DB>>()
+			.await
+			.map_error(|(status, _)| (status, Error::Internal)));
+		match User::read(&mut db, user.id).await {
+			Ok(user) => {
+				req.local_cache(|| RequestUser(Some(user.id)));
+				Outcome::Success(user)
+			}
+			Err(diesel::result::Error::NotFound) => {
+				Outcome::Error((Status::Unauthorized, Error::UnauthenticatedUser))
+			}
+			Err(err) => Outcome::Error((Status::InternalServerError, err.into())),
+		}
+	}
+}
+
+impl<'r> OpenApiFromRequest<'r> for User {
+	fn from_request_input(
+		_gen: &mut OpenApiGenerator,
+		_name: String,
+		_required: bool,
+	) -> rocket_okapi::Result<RequestHeaderInput> {
+		let scheme = SecurityScheme {
+			description: Some("Token returned by `POST /login`".to_string()),
+			data: SecuritySchemeData::Http {
+				scheme: "bearer".to_string(),
+				bearer_format: Some("JWT".to_string()),
+			},
+			extensions: Default::default(),
+		};
+		let mut requirement = SecurityRequirement::new();
+		requirement.insert("bearer".to_string(), Vec::new());
+		Ok(RequestHeaderInput::Security(
+			"bearer".to_string(),
+			scheme,
+			requirement,
+		))
+	}
+}
+
+impl User {
+	/// Whether `user_id` is this user or reports to them, directly or through other managers
+	///
+	/// The chain is resolved from the database, `manager_id` in the token might be outdated
+	pub async fn in_reporting_chain(&self, db: &mut Connection<DB>, user_id: i32) -> Result<bool> {
+		Ok(user_id == self.id || User::reports_to(db, user_id, self.id).await?)
+	}
+
+	/// Fails with [`Error::ForbiddenAccess`] unless the user is an admin
+	/// or `user_id` is in their reporting chain
+	pub async fn ensure_in_reporting_chain(
+		&self,
+		db: &mut Connection<DB>,
+		user_id: i32,
+	) -> Result<()> {
+		if self.sys_role == "admin" || self.in_reporting_chain(db, user_id).await? {
+			Ok(())
+		} else {
+			Err(Error::ForbiddenAccess)
 		}
 	}
 }
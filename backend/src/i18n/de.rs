@@ -0,0 +1,168 @@
+//! German catalog, `{}` marks a value inserted into the message
+
+use super::Catalog;
+
+pub(super) const CATALOG: Catalog = Catalog {
+	messages: &[
+		// HTTP status, errors without a more specific code and the default catcher
+		("bad_request", "Ungültige Anfrage"),
+		("unauthorized", "Nicht angemeldet"),
+		("forbidden", "Benutzer hat keine Zugriffsrechte"),
+		("not_found", "Nicht gefunden"),
+		("method_not_allowed", "Methode nicht erlaubt"),
+		("conflict", "Konflikt"),
+		("unsupported_media_type", "Nicht unterstütztes Format"),
+		("unprocessable_entity", "Ungültige Daten"),
+		("too_many_requests", "Zu viele Anfragen"),
+		("internal_server_error", "Interner Serverfehler"),
+		("service_unavailable", "Dienst nicht verfügbar"),
+		("database", "Interner Fehler"),
+		// login
+		("login.wrong_credentials", "Falsche Zugangsdaten"),
+		(
+			"login.locked",
+			"Zu viele fehlgeschlagene Anmeldungen, erneut versuchen in {} Sekunden",
+		),
+		(
+			"login.rate_limited",
+			"Zu viele Anmeldeversuche, erneut versuchen in {} Sekunden",
+		),
+		// database constraints, the entity is the table of the constraint
+		("*_taken", "{} existiert bereits"),
+		("fk.*_missing", "{} existiert nicht"),
+		("fk.*_references", "Wird noch verwendet von {}"),
+		(
+			"fk.violation",
+			"Ein verknüpfter Eintrag existiert nicht oder wird noch verwendet",
+		),
+		("*_required", "Ein Pflichtwert fehlt"),
+		(
+			"check_violation",
+			"Ein Wert ist außerhalb des erlaubten Bereichs",
+		),
+		("purge.referenced", "{} wird noch verwendet von: {}"),
+		// tracking validation
+		("tracking.project_missing", "Projekt existiert nicht"),
+		("tracking.project_archived", "Projekt ist archiviert"),
+		(
+			"tracking.before_project_start",
+			"Datum liegt vor dem Projektbeginn",
+		),
+		(
+			"tracking.after_project_end",
+			"Datum liegt nach dem Projektende",
+		),
+		(
+			"tracking.not_member",
+			"Benutzer ist kein Mitglied des Projekts",
+		),
+		("tracking.activity_missing", "Tätigkeit existiert nicht"),
+		(
+			"tracking.activity_not_allowed",
+			"Tätigkeit ist in diesem Projekt nicht erlaubt",
+		),
+		("tracking.client_missing", "Kunde existiert nicht"),
+		("tracking.client_archived", "Kunde ist archiviert"),
+		(
+			"tracking.budget_exhausted",
+			"Budget des Projekts ist aufgebraucht",
+		),
+		(
+			"tracking.approved",
+			"Eintrag ist freigegeben und kann nicht geändert werden",
+		),
+		// request validation
+		("invalid_date", "Ungültiges Datum: {}"),
+		("cursor.invalid", "Ungültiger Cursor: {}"),
+		(
+			"cursor.sorted",
+			"Seiten per Cursor sind immer nach ID sortiert",
+		),
+		("list.unknown_field", "Unbekanntes Feld in {}: {}"),
+		("list.invalid_value", "Ungültiger Wert für {}: {}"),
+		("tag.invalid", "Ungültiges Schlagwort: {}"),
+		(
+			"custom_field.invalid_value",
+			"Ungültiger Wert für das Feld {}",
+		),
+		(
+			"custom_field.unknown",
+			"Unbekanntes benutzerdefiniertes Feld: {}",
+		),
+		(
+			"custom_field.unknown_type",
+			"Unbekannter Typ für benutzerdefinierte Felder: {}",
+		),
+		("custom_field.missing_value", "Feld {} ist ein Pflichtfeld"),
+		(
+			"custom_field.unsupported_entity",
+			"Benutzerdefinierte Felder werden für {} nicht unterstützt",
+		),
+		(
+			"custom_field.invalid_options",
+			"Optionen sind für Auswahlfelder nötig und nur dort erlaubt",
+		),
+		("project_member.invalid_role", "Rolle muss {} oder {} sein"),
+		("budget.invalid_unit", "Einheit muss {} oder {} sein"),
+		("budget.invalid_amount", "Betrag muss größer als 0 sein"),
+		(
+			"budget.hourly_rate_missing",
+			"Geldbudgets brauchen einen Stundensatz",
+		),
+		(
+			"budget.invalid_hourly_rate",
+			"Stundensatz muss größer als 0 sein",
+		),
+		(
+			"budget.invalid_thresholds",
+			"Schwellen müssen Prozentwerte größer als 0 sein",
+		),
+		(
+			"client.invalid_currency",
+			"Währung muss ein ISO-4217-Code wie EUR sein",
+		),
+		(
+			"client.invalid_payment_terms",
+			"Zahlungsziel darf nicht negativ sein",
+		),
+		("client_contact.invalid_email", "Ungültige E-Mail-Adresse"),
+		(
+			"merge.into_itself",
+			"{} kann nicht mit sich selbst zusammengeführt werden",
+		),
+		(
+			"merge.different_clients",
+			"Projekte gehören zu verschiedenen Kunden",
+		),
+		(
+			"user.manager_cycle",
+			"Vorgesetzter würde einen Zyklus in der Berichtskette erzeugen",
+		),
+	],
+	details: &[
+		("taken", "Bereits vergeben"),
+		("does_not_exist", "Existiert nicht"),
+		("required", "Pflichtfeld"),
+		("archived", "Archiviert"),
+		("before_project_start", "Vor dem Projektbeginn"),
+		("after_project_end", "Nach dem Projektende"),
+		("not_member", "Kein Mitglied des Projekts"),
+		("not_allowed", "In diesem Projekt nicht erlaubt"),
+	],
+	entities: &[
+		("activity", "Tätigkeit"),
+		("client", "Kunde"),
+		("client_contact", "Kundenkontakt"),
+		("custom_field", "Benutzerdefiniertes Feld"),
+		("entry", "Eintrag"),
+		("project", "Projekt"),
+		("project_activity", "Projekttätigkeit"),
+		("project_budget", "Projektbudget"),
+		("project_member", "Projektmitglied"),
+		("tag", "Schlagwort"),
+		("team", "Team"),
+		("team_member", "Teammitglied"),
+		("tracking", "Zeiteintrag"),
+		("user", "Benutzer"),
+	],
+};
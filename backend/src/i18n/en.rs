@@ -0,0 +1,153 @@
+//! English catalog, `{}` marks a value inserted into the message
+
+use super::Catalog;
+
+pub(super) const CATALOG: Catalog = Catalog {
+	messages: &[
+		// HTTP status, errors without a more specific code and the default catcher
+		("bad_request", "Bad Request"),
+		("unauthorized", "Unauthorized"),
+		("forbidden", "User does not have access rights"),
+		("not_found", "Not Found"),
+		("method_not_allowed", "Method Not Allowed"),
+		("conflict", "Conflict"),
+		("unsupported_media_type", "Unsupported Media Type"),
+		("unprocessable_entity", "Unprocessable Entity"),
+		("too_many_requests", "Too Many Requests"),
+		("internal_server_error", "Internal Server Error"),
+		("service_unavailable", "Service Unavailable"),
+		("database", "Internal error"),
+		// login
+		("login.wrong_credentials", "Wrong Credentials"),
+		(
+			"login.locked",
+			"Too many failed logins, try again in {} seconds",
+		),
+		(
+			"login.rate_limited",
+			"Too many login attempts, try again in {} seconds",
+		),
+		// database constraints, the entity is the table of the constraint
+		("*_taken", "{} already exists"),
+		("fk.*_missing", "{} does not exist"),
+		("fk.*_references", "Still referenced by {}"),
+		(
+			"fk.violation",
+			"A referenced entry does not exist or is still in use",
+		),
+		("*_required", "A required value is missing"),
+		("check_violation", "A value is out of range"),
+		("purge.referenced", "{} is still referenced by: {}"),
+		// tracking validation
+		("tracking.project_missing", "Project does not exist"),
+		("tracking.project_archived", "Project is archived"),
+		(
+			"tracking.before_project_start",
+			"Date is before the start of the project",
+		),
+		(
+			"tracking.after_project_end",
+			"Date is after the end of the project",
+		),
+		("tracking.not_member", "User is not a member of the project"),
+		("tracking.activity_missing", "Activity does not exist"),
+		(
+			"tracking.activity_not_allowed",
+			"Activity is not allowed on this project",
+		),
+		("tracking.client_missing", "Client does not exist"),
+		("tracking.client_archived", "Client is archived"),
+		(
+			"tracking.budget_exhausted",
+			"Budget of the project is exhausted",
+		),
+		(
+			"tracking.approved",
+			"Tracking entry is approved and can't be changed",
+		),
+		// request validation
+		("invalid_date", "Invalid date: {}"),
+		("cursor.invalid", "Invalid cursor: {}"),
+		("cursor.sorted", "Cursor pagination is always sorted by id"),
+		("list.unknown_field", "Unknown field in {}: {}"),
+		("list.invalid_value", "Invalid value for {}: {}"),
+		("tag.invalid", "Invalid tag: {}"),
+		(
+			"custom_field.invalid_value",
+			"Invalid value for custom field {}",
+		),
+		("custom_field.unknown", "Unknown custom field: {}"),
+		("custom_field.unknown_type", "Unknown custom field type: {}"),
+		("custom_field.missing_value", "Custom field {} is required"),
+		(
+			"custom_field.unsupported_entity",
+			"Custom fields are not supported on {}",
+		),
+		(
+			"custom_field.invalid_options",
+			"Options are required for select fields and only allowed there",
+		),
+		(
+			"project_member.invalid_role",
+			"Role must be either {} or {}",
+		),
+		("budget.invalid_unit", "Unit must be either {} or {}"),
+		("budget.invalid_amount", "Amount must be greater than 0"),
+		(
+			"budget.hourly_rate_missing",
+			"Money budgets need an hourly rate",
+		),
+		(
+			"budget.invalid_hourly_rate",
+			"Hourly rate must be greater than 0",
+		),
+		(
+			"budget.invalid_thresholds",
+			"Thresholds must be percentages greater than 0",
+		),
+		(
+			"client.invalid_currency",
+			"Currency must be an ISO 4217 code like EUR",
+		),
+		(
+			"client.invalid_payment_terms",
+			"Payment terms must not be negative",
+		),
+		("client_contact.invalid_email", "Invalid email address"),
+		("merge.into_itself", "Cannot merge a {} into itself"),
+		(
+			"merge.different_clients",
+			"Projects belong to different clients",
+		),
+		(
+			"user.manager_cycle",
+			"Manager would create a cycle in the reporting chain",
+		),
+	],
+	details: &[
+		("taken", "Already taken"),
+		("does_not_exist", "Does not exist"),
+		("required", "Required"),
+		("archived", "Archived"),
+		("before_project_start", "Before the start of the project"),
+		("after_project_end", "After the end of the project"),
+		("not_member", "Not a member of the project"),
+		("not_allowed", "Not allowed on this project"),
+	],
+	entities: &[
+		("activity", "activity"),
+		("client", "client"),
+		("client_contact", "client contact"),
+		("custom_field", "custom field"),
+		("entry", "entry"),
+		("project", "project"),
+		("project_activity", "project activity"),
+		("project_budget", "project budget"),
+		("project_member", "project member"),
+		("tag", "tag"),
+		("team", "team"),
+		("team_member", "team member"),
+		("tracking", "tracking"),
+		("user", "user"),
+	],
+};
@@ -0,0 +1,222 @@
+use rocket::request::Request;
+
+mod de;
+mod en;
+
+/// Language of the messages in error responses
+///
+/// Every language has a catalog that maps the `error_code` of an error and the codes of its
+/// details to a message, so rewording a message never loses its translations.
+/// Machine readable codes are never translated.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Lang {
+	#[default]
+	En,
+	De,
+}
+
+/// Messages of a language, keyed by code
+///
+/// Message templates contain a `{}` placeholder for every [`Arg`] of the error.
+/// A `*` in a key stands for any part of a code, for codes like `client.name_taken` that name
+/// the table and column of a database constraint. Exact keys win over those.
+pub(crate) struct Catalog {
+	/// By `error_code`
+	pub messages: &'static [(&'static str, &'static str)],
+	/// By the `code` of an [`ErrorDetail`](crate::error::ErrorDetail)
+	pub details: &'static [(&'static str, &'static str)],
+	/// Tables as they are called in messages, by table name
+	pub entities: &'static [(&'static str, &'static str)],
+}
+
+/// A value inserted into a message
+#[derive(Debug, Clone, PartialEq)]
+pub enum Arg {
+	/// Inserted as is, e.g. a value of the request
+	Value(String),
+	/// A table, inserted as what it is called in the language
+	Entity(String),
+	/// Numbers of rows in tables, inserted like `2 tracking, 1 project`
+	Entities(Vec<(i64, String)>),
+}
+
+impl Lang {
+	pub const ALL: [Self; 2] = [Self::En, Self::De];
+
+	/// The preferred supported language of the `Accept-Language` header, english without one
+	pub fn negotiate(request: &Request<'_>) -> Self {
+		request
+			.headers()
+			.get_one("Accept-Language")
+			.map(Self::from_accept_language)
+			.unwrap_or_default()
+	}
+
+	/// Picks the supported language with the highest quality, e.g. from `fr, de;q=0.8, en;q=0.5`
+	fn from_accept_language(header: &str) -> Self {
+		let mut ranges = header
+			.split(',')
+			.filter_map(|range| {
+				let mut params = range.split(';');
+				let tag = params.next()?.trim();
+				let quality = match params.find_map(|param| param.trim().strip_prefix("q=")) {
+					Some(quality) => quality.parse::<f32>().ok()?,
+					None => 1.0,
+				};
+				Some((quality, tag))
+			})
+			.filter(|(quality, _)| *quality > 0.0)
+			.collect::<Vec<_>>();
+		// stable, so equal qualities keep the order of the header
+		ranges.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+		ranges
+			.into_iter()
+			.find_map(|(_, tag)| Self::from_tag(tag))
+			.unwrap_or_default()
+	}
+
+	/// `de-AT` as [`Lang::De`], only the primary subtag counts
+	fn from_tag(tag: &str) -> Option<Self> {
+		let primary = tag.split('-').next()?;
+		if primary.eq_ignore_ascii_case("de") {
+			Some(Self::De)
+		} else if primary.eq_ignore_ascii_case("en") {
+			Some(Self::En)
+		} else {
+			None
+		}
+	}
+
+	fn catalog(self) -> &'static Catalog {
+		match self {
+			Self::En => &en::CATALOG,
+			Self::De => &de::CATALOG,
+		}
+	}
+
+	/// The message for an `error_code`, `None` for codes missing from the catalog
+	pub fn message(self, code: &str, args: &[Arg]) -> Option<String> {
+		let template = lookup(self.catalog().messages, code)?;
+		let mut args = args.iter().map(|arg| self.arg(arg));
+		let mut parts = template.split("{}");
+		let mut message = parts.next().unwrap_or_default().to_string();
+		for part in parts {
+			message.push_str(&args.next().unwrap_or_default());
+			message.push_str(part);
+		}
+		Some(capitalize(&message))
+	}
+
+	/// The message for the code of an error detail
+	pub fn detail(self, code: &str) -> Option<String> {
+		lookup(self.catalog().details, code).map(str::to_string)
+	}
+
+	fn arg(self, arg: &Arg) -> String {
+		match arg {
+			Arg::Value(value) => value.clone(),
+			Arg::Entity(table) => self.entity(table),
+			Arg::Entities(counts) => counts
+				.iter()
+				.map(|(count, table)| format!("{count} {}", self.entity(table)))
+				.collect::<Vec<_>>()
+				.join(", "),
+		}
+	}
+
+	/// `project_member` as `project member`, tables missing from the catalog stay as they are
+	fn entity(self, table: &str) -> String {
+		lookup(self.catalog().entities, table)
+			.map(str::to_string)
+			.unwrap_or_else(|| table.replace('_', " "))
+	}
+}
+
+/// The entry for `code`, exact keys first
+fn lookup(entries: &[(&str, &'static str)], code: &str) -> Option<&'static str> {
+	entries
+		.iter()
+		.find(|(key, _)| *key == code)
+		.or_else(|| entries.iter().find(|(key, _)| matches(key, code)))
+		.map(|(_, message)| *message)
+}
+
+/// Whether the key `fk.*_missing` matches `code`, like `fk.project_missing`
+fn matches(key: &str, code: &str) -> bool {
+	match key.split_once('*') {
+		Some((prefix, suffix)) => {
+			code.len() > prefix.len() + suffix.len() && code.starts_with(prefix) && code.ends_with(suffix)
+		}
+		None => false,
+	}
+}
+
+/// Messages start upper case, also when they start with an entity
+fn capitalize(message: &str) -> String {
+	let mut chars = message.chars();
+	chars
+		.next()
+		.map(|first| first.to_uppercase().chain(chars).collect())
+		.unwrap_or_default()
+}
+
+#[cfg(test)]
+mod test {
+	use pretty_assertions::assert_eq;
+
+	use super::{
+		Arg,
+		Lang,
+	};
+
+	/// The keys of `entries` with the number of placeholders of their message
+	fn keys(entries: &[(&str, &str)]) -> Vec<(String, usize)> {
+		let mut keys = entries
+			.iter()
+			.map(|(key, message)| (key.to_string(), message.matches("{}").count()))
+			.collect::<Vec<_>>();
+		keys.sort();
+		keys
+	}
+
+	#[test]
+	fn every_code_translated() {
+		let en = Lang::En.catalog();
+		for lang in Lang::ALL {
+			let catalog = lang.catalog();
+			assert_eq!(keys(catalog.messages), keys(en.messages), "{lang:?}");
+			assert_eq!(keys(catalog.details), keys(en.details), "{lang:?}");
+			assert_eq!(keys(catalog.entities), keys(en.entities), "{lang:?}");
+		}
+	}
+
+	#[test]
+	fn message_args() {
+		let taken = [Arg::Entity("project_member".to_string())];
+		assert_eq!(
+			Lang::En.message("project_member.user_id_taken", &taken),
+			Some("Project member already exists".to_string())
+		);
+		assert_eq!(
+			Lang::De.message("project_member.user_id_taken", &taken),
+			Some("Projektmitglied existiert bereits".to_string())
+		);
+		let referenced = [
+			Arg::Entity("client".to_string()),
+			Arg::Entities(vec![
+				(2, "project".to_string()),
+				(1, "tracking".to_string()),
+			]),
+		];
+		assert_eq!(
+			Lang::De.message("purge.referenced", &referenced),
+			Some("Kunde wird noch verwendet von: 2 Projekt, 1 Zeiteintrag".to_string())
+		);
+		let locked = [Arg::Value("30".to_string())];
+		assert_eq!(
+			Lang::De.message("login.locked", &locked),
+			Some("Zu viele fehlgeschlagene Anmeldungen, erneut versuchen in 30 Sekunden".to_string())
+		);
+		assert_eq!(Lang::De.message("unknown.code", &[]), None);
+	}
+}
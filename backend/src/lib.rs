@@ -6,9 +6,13 @@ use rocket::{
 use rocket_db_pools::Database;
 mod auth;
 mod catchers;
+pub mod config;
 mod db;
 mod error;
 mod guard;
+mod i18n;
+mod metrics;
+mod request_id;
 mod routes;
 mod schema;
 #[cfg(test)]
@@ -22,30 +26,18 @@ pub use error::{
 	Error,
 	Result,
 };
-use rocket_cors::CorsOptions;
 
 pub fn rocket() -> Rocket<rocket::Build> {
-	let allowed_origins = rocket_cors::AllowedOrigins::some_exact(&["http://localhost:5173"]);
 	Rocket::build()
-		.attach(
-			CorsOptions {
-				allowed_origins,
-				..Default::default()
-			}
-			.to_cors()
-			.expect("error while building CORS"),
-		)
+		.attach(config::fairing())
+		.attach(request_id::RequestIds)
+		.attach(metrics::Metrics::new())
 		.attach(DB::init())
 		.attach(AdHoc::on_ignite("Run Migrations", db::run_migrations))
-		.attach(routes::login::mount())
-		.attach(routes::activity::mount())
-		.attach(routes::user::mount())
-		.attach(routes::client::mount())
-		.attach(routes::project::mount())
-		.attach(routes::tracking::mount())
-		.manage(auth::Tokenizer::new(std::time::Duration::new(
-			5 * 24 * 60 * 60,
-			0,
-		)))
+		.attach(AdHoc::on_ignite(
+			"Prepare Dummy Hash",
+			db::user::prepare_dummy_hash,
+		))
+		.attach(routes::mount())
 		.register("/", catchers![catchers::default_catcher])
 }
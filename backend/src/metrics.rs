@@ -0,0 +1,321 @@
+use std::time::Instant;
+
+use base64::{
+	engine::general_purpose::STANDARD,
+	Engine,
+};
+use prometheus::{
+	Encoder,
+	HistogramOpts,
+	HistogramVec,
+	IntCounterVec,
+	IntGauge,
+	Opts,
+	Registry,
+	TextEncoder,
+};
+use rocket::{
+	fairing::{
+		Fairing,
+		Info,
+		Kind,
+	},
+	get,
+	http::{
+		ContentType,
+		Header,
+	},
+	request::{
+		FromRequest,
+		Outcome,
+	},
+	routes,
+	Build,
+	Data,
+	Request,
+	Responder,
+	Response,
+	Rocket,
+	State,
+};
+use rocket_db_pools::Connection;
+use subtle::ConstantTimeEq;
+use tracing::{
+	trace,
+	warn,
+};
+
+use crate::{
+	config::Config,
+	db::tracking::Tracking,
+	DB,
+};
+
+/// Prometheus metrics of the backend, served at `/metrics`
+///
+/// Attached as fairing it times every request and manages itself, so handlers can record
+/// their own events through `&State<Metrics>`.
+#[derive(Clone)]
+pub struct Metrics {
+	registry: Registry,
+	requests: IntCounterVec,
+	latency: HistogramVec,
+	logins: IntCounterVec,
+	pool_size: IntGauge,
+	pool_available: IntGauge,
+	pool_max_size: IntGauge,
+	trackings_today: IntGauge,
+}
+
+impl Metrics {
+	pub fn new() -> Self {
+		let registry = Registry::new_custom(Some("zeiterfassung".to_string()), None)
+			.expect("Invalid metrics prefix");
+		let labels = ["method", "route", "status"];
+		let requests = IntCounterVec::new(
+			Opts::new("http_requests_total", "Handled requests"),
+			&labels,
+		)
+		.expect("Invalid metric");
+		let latency = HistogramVec::new(
+			HistogramOpts::new("http_request_duration_seconds", "Time to handle a request"),
+			&labels,
+		)
+		.expect("Invalid metric");
+		let logins = IntCounterVec::new(
+			Opts::new("logins_total", "Login attempts by result"),
+			&["result"],
+		)
+		.expect("Invalid metric");
+		let pool_size =
+			IntGauge::new("db_pool_connections", "Open database connections").expect("Invalid metric");
+		let pool_available = IntGauge::new(
+			"db_pool_available",
+			"Idle database connections, negative while requests wait for one",
+		)
+		.expect("Invalid metric");
+		let pool_max_size =
+			IntGauge::new("db_pool_max_connections", "Size limit of the pool").expect("Invalid metric");
+		let trackings_today = IntGauge::new(
+			"trackings_created_today",
+			"Tracking entries created since midnight of the database clock",
+		)
+		.expect("Invalid metric");
+
+		let metrics = Self {
+			registry,
+			requests,
+			latency,
+			logins,
+			pool_size,
+			pool_available,
+			pool_max_size,
+			trackings_today,
+		};
+		let collectors: [Box<dyn prometheus::core::Collector>; 7] = [
+			Box::new(metrics.requests.clone()),
+			Box::new(metrics.latency.clone()),
+			Box::new(metrics.logins.clone()),
+			Box::new(metrics.pool_size.clone()),
+			Box::new(metrics.pool_available.clone()),
+			Box::new(metrics.pool_max_size.clone()),
+			Box::new(metrics.trackings_today.clone()),
+		];
+		for collector in collectors {
+			metrics
+				.registry
+				.register(collector)
+				.expect("Metric registered twice");
+		}
+		metrics
+	}
+
+	/// Counts a login attempt
+	pub fn login(&self, success: bool) {
+		let result = if success { "success" } else { "failure" };
+		self.logins.with_label_values(&[result]).inc();
+	}
+
+	/// Updates the gauges and renders everything in the Prometheus text format
+	async fn render(&self, pool: &DB, db: Option<Connection<DB>>) -> String {
+		let status = pool.status();
+		self.pool_size.set(status.size as i64);
+		self.pool_available.set(status.available as i64);
+		self.pool_max_size.set(status.max_size as i64);
+
+		match db {
+			Some(mut db) => match Tracking::count_created_today(&mut db).await {
+				Ok(count) => self.trackings_today.set(count),
+				Err(e) => warn!("Could not count today's trackings: {}", e),
+			},
+			None => warn!("No database connection for the metrics"),
+		}
+
+		let mut buffer = Vec::new();
+		TextEncoder::new()
+			.encode(&self.registry.gather(), &mut buffer)
+			.expect("Could not encode metrics");
+		String::from_utf8(buffer).expect("Metrics are UTF-8")
+	}
+}
+
+impl Default for Metrics {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+/// Start of a request, cached in the request for [`Metrics::on_response`]
+struct RequestStart(Instant);
+
+#[rocket::async_trait]
+impl Fairing for Metrics {
+	fn info(&self) -> Info {
+		Info {
+			name: "Metrics",
+			kind: Kind::Ignite | Kind::Request | Kind::Response,
+		}
+	}
+
+	async fn on_ignite(&self, rocket: Rocket<Build>) -> rocket::fairing::Result {
+		Ok(rocket.manage(self.clone()).mount("/", routes![metrics]))
+	}
+
+	async fn on_request(&self, request: &mut Request<'_>, _: &mut Data<'_>) {
+		request.local_cache(|| RequestStart(Instant::now()));
+	}
+
+	async fn on_response<'r>(&self, request: &'r Request<'_>, response: &mut Response<'r>) {
+		let elapsed = request
+			.local_cache(|| RequestStart(Instant::now()))
+			.0
+			.elapsed();
+		// the route template keeps the number of label values small, unlike the path
+		let route = request
+			.route()
+			.map_or("unmatched", |route| route.uri.as_str());
+		let status = response.status().code.to_string();
+		let labels = [request.method().as_str(), route, status.as_str()];
+		self.requests.with_label_values(&labels).inc();
+		self
+			.latency
+			.with_label_values(&labels)
+			.observe(elapsed.as_secs_f64());
+	}
+}
+
+/// Whether the request may read the metrics, see [`crate::config::MetricsConfig`]
+struct MetricsAccess(bool);
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for MetricsAccess {
+	type Error = ();
+
+	async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+		let config = &request
+			.rocket()
+			.state::<Config>()
+			.expect("Config is managed")
+			.metrics;
+		let (Some(username), Some(password)) = (&config.username, &config.password) else {
+			return Outcome::Success(Self(true));
+		};
+		let expected = STANDARD.encode(format!("{username}:{password}"));
+		let given = request
+			.headers()
+			.get_one("Authorization")
+			.and_then(|header| header.strip_prefix("Basic "));
+		// constant time, so the response time doesn't tell how much of the credentials is right
+		let granted =
+			given.is_some_and(|given| bool::from(given.as_bytes().ct_eq(expected.as_bytes())));
+		Outcome::Success(Self(granted))
+	}
+}
+
+#[derive(Responder)]
+enum MetricsResponse {
+	Metrics(String, ContentType),
+	#[response(status = 401)]
+	Unauthorized(&'static str, Header<'static>),
+}
+
+#[get("/metrics")]
+async fn metrics(
+	access: MetricsAccess,
+	metrics: &State<Metrics>,
+	pool: &State<DB>,
+	db: Option<Connection<DB>>,
+) -> MetricsResponse {
+	if !access.0 {
+		trace!("Rejected metrics scrape without valid credentials");
+		return MetricsResponse::Unauthorized(
+			"Unauthorized",
+			Header::new("WWW-Authenticate", "Basic realm=\"metrics\""),
+		);
+	}
+	let content_type =
+		ContentType::parse_flexible(prometheus::TEXT_FORMAT).unwrap_or(ContentType::Plain);
+	MetricsResponse::Metrics(metrics.render(pool, db).await, content_type)
+}
+
+#[cfg(test)]
+mod test {
+	use base64::{
+		engine::general_purpose::STANDARD,
+		Engine,
+	};
+	use rocket::http::{
+		Header,
+		Status,
+	};
+
+	use crate::test::TestApp;
+
+	#[tracing_test::traced_test]
+	#[test]
+	fn metrics() {
+		let app = TestApp::new();
+		app.admin_token();
+		app.fixture().tracking().create();
+
+		let res = app.client.get("/metrics").dispatch();
+		assert_eq!(res.status(), Status::Ok);
+		let metrics = res.into_string().unwrap();
+		assert!(metrics.contains("zeiterfassung_logins_total{result=\"success\"} 1"));
+		assert!(metrics.contains(
+			"zeiterfassung_http_requests_total{method=\"POST\",route=\"/login\",status=\"200\"} 1"
+		));
+		assert!(metrics.contains("zeiterfassung_db_pool_max_connections"));
+		assert!(metrics.contains("zeiterfassung_trackings_created_today 1"));
+	}
+
+	#[tracing_test::traced_test]
+	#[test]
+	fn metrics_basic_auth() {
+		let app = TestApp::configured(|figment| {
+			figment
+				.merge(("metrics.username", "prometheus"))
+				.merge(("metrics.password", "secret"))
+		});
+
+		let res = app.client.get("/metrics").dispatch();
+		assert_eq!(res.status(), Status::Unauthorized);
+		assert!(res.headers().get_one("WWW-Authenticate").is_some());
+
+		let wrong = format!("Basic {}", STANDARD.encode("prometheus:wrong"));
+		let res = app
+			.client
+			.get("/metrics")
+			.header(Header::new("Authorization", wrong))
+			.dispatch();
+		assert_eq!(res.status(), Status::Unauthorized);
+
+		let right = format!("Basic {}", STANDARD.encode("prometheus:secret"));
+		let res = app
+			.client
+			.get("/metrics")
+			.header(Header::new("Authorization", right))
+			.dispatch();
+		assert_eq!(res.status(), Status::Ok);
+	}
+}
@@ -0,0 +1,227 @@
+use std::time::Instant;
+
+use rocket::{
+	fairing::{
+		Fairing,
+		Info,
+		Kind,
+	},
+	http::Header,
+	route::{
+		Handler,
+		Outcome,
+	},
+	Data,
+	Request,
+	Response,
+	Route,
+};
+use tracing::{
+	field::Empty,
+	info,
+	info_span,
+	warn,
+	Instrument,
+	Span,
+};
+
+/// Header a request id is read from and returned in
+pub const HEADER: &str = "X-Request-Id";
+
+/// Id of a request, taken from the `X-Request-Id` header of the client or generated
+#[derive(Debug, Clone)]
+pub struct RequestId(pub String);
+
+impl RequestId {
+	/// Id of `request`, assigned by the [`RequestIds`] fairing
+	pub fn of(request: &Request<'_>) -> Option<String> {
+		request
+			.local_cache(|| None::<Self>)
+			.as_ref()
+			.map(|id| id.0.clone())
+	}
+
+	/// Ids of other services are kept if they can't mess up the logs
+	fn is_valid(id: &str) -> bool {
+		!id.is_empty()
+			&& id.len() <= 128
+			&& id
+				.chars()
+				.all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+	}
+}
+
+/// Id of the authenticated user, cached by the `User` guard for the access log
+pub struct RequestUser(pub Option<i32>);
+
+/// Span and start of a request, see [`RequestIds::on_response`]
+struct RequestSpan {
+	span: Span,
+	start: Instant,
+}
+
+impl RequestSpan {
+	/// Span the [`RequestIds`] fairing created for `request`
+	fn of(request: &Request<'_>) -> Span {
+		request
+			.local_cache(|| RequestSpan {
+				span: Span::none(),
+				start: Instant::now(),
+			})
+			.span
+			.clone()
+	}
+}
+
+/// Runs the handlers of `routes` in the span of their request
+///
+/// Everything logged while a request is handled, including its guards and errors, then
+/// carries the request id
+pub fn instrument(routes: Vec<Route>) -> Vec<Route> {
+	routes
+		.into_iter()
+		.map(|mut route| {
+			route.handler = Box::new(Instrumented(route.handler));
+			route
+		})
+		.collect()
+}
+
+#[derive(Clone)]
+struct Instrumented(Box<dyn Handler>);
+
+#[rocket::async_trait]
+impl Handler for Instrumented {
+	async fn handle<'r>(&self, request: &'r Request<'_>, data: Data<'r>) -> Outcome<'r> {
+		self
+			.0
+			.handle(request, data)
+			.instrument(RequestSpan::of(request))
+			.await
+	}
+}
+
+/// Assigns every request an id and logs it once it is answered
+///
+/// The id is returned in the `X-Request-Id` header and in error responses, so a report of a
+/// user can be matched to the log. Each request gets a span with the id, method, route, user,
+/// status and latency, the access log entry is written in it.
+pub struct RequestIds;
+
+#[rocket::async_trait]
+impl Fairing for RequestIds {
+	fn info(&self) -> Info {
+		Info {
+			name: "Request IDs",
+			kind: Kind::Request | Kind::Response,
+		}
+	}
+
+	async fn on_request(&self, request: &mut Request<'_>, _: &mut Data<'_>) {
+		let id = match request.headers().get_one(HEADER) {
+			Some(id) if RequestId::is_valid(id) => id.to_string(),
+			Some(_) => {
+				warn!("Replacing invalid {} of the client", HEADER);
+				uuid::Uuid::new_v4().to_string()
+			}
+			None => uuid::Uuid::new_v4().to_string(),
+		};
+		let span = info_span!(
+			"request",
+			request_id = %id,
+			method = %request.method(),
+			uri = %request.uri(),
+			route = Empty,
+			user_id = Empty,
+			status = Empty,
+			latency_ms = Empty,
+		);
+		request.local_cache(|| Some(RequestId(id)));
+		request.local_cache(|| RequestSpan {
+			span,
+			start: Instant::now(),
+		});
+	}
+
+	async fn on_response<'r>(&self, request: &'r Request<'_>, response: &mut Response<'r>) {
+		if let Some(id) = RequestId::of(request) {
+			response.set_header(Header::new(HEADER, id));
+		}
+		let RequestSpan { span, start } = request.local_cache(|| RequestSpan {
+			span: Span::none(),
+			start: Instant::now(),
+		});
+		if let Some(route) = request.route() {
+			span.record("route", route.uri.as_str());
+		}
+		if let RequestUser(Some(user_id)) = request.local_cache(|| RequestUser(None)) {
+			span.record("user_id", user_id);
+		}
+		let status = response.status().code;
+		let latency_ms = start.elapsed().as_secs_f64() * 1000.0;
+		span.record("status", status);
+		span.record("latency_ms", latency_ms);
+		span.in_scope(|| {
+			info!(
+				"{} {} answered with {} in {:.1}ms",
+				request.method(),
+				request.uri(),
+				status,
+				latency_ms
+			);
+		});
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use pretty_assertions::assert_eq;
+	use rocket::http::{
+		Header,
+		Status,
+	};
+
+	use super::HEADER;
+	use crate::{
+		error::ErrorJson,
+		test::TestApp,
+	};
+
+	#[tracing_test::traced_test]
+	#[test]
+	fn request_id() {
+		let app = TestApp::new();
+
+		let res = app
+			.client
+			.get("/user/page/10/0")
+			.header(Header::new(HEADER, "ticket-4711"))
+			.dispatch();
+		assert_eq!(res.status(), Status::Unauthorized);
+		assert_eq!(res.headers().get_one(HEADER), Some("ticket-4711"));
+		let error = res.into_json::<ErrorJson>().unwrap();
+		assert_eq!(error.request_id.as_deref(), Some("ticket-4711"));
+		assert!(logs_contain("request_id=ticket-4711"));
+
+		let res = app
+			.client
+			.get("/user/page/10/0")
+			.header(Header::new(HEADER, "no spaces please"))
+			.dispatch();
+		let id = res.headers().get_one(HEADER).unwrap().to_string();
+		assert_ne!(id, "no spaces please");
+		assert_eq!(res.into_json::<ErrorJson>().unwrap().request_id, Some(id));
+
+		let res = app
+			.client
+			.get("/user/page/10/0")
+			.header(Header::new(
+				"Authorization",
+				format!("Bearer {}", app.admin_token()),
+			))
+			.dispatch();
+		assert_eq!(res.status(), Status::Ok);
+		assert_eq!(res.headers().get_one(HEADER).map(str::len), Some(36));
+		assert!(logs_contain("user_id="));
+	}
+}
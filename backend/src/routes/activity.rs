@@ -1,22 +1,25 @@
+use diesel::OptionalExtension;
 use rocket::{
 	delete,
-	fairing::AdHoc,
 	get,
 	patch,
 	post,
-	routes,
 	serde::json::Json,
+	Route,
 };
 use rocket_db_pools::Connection;
+use rocket_okapi::{
+	okapi::openapi3::OpenApi,
+	openapi,
+	openapi_get_routes_spec,
+	settings::OpenApiSettings,
+};
 
 use crate::{
-	db::{
-		activity::{
-			Activity,
-			CreateActivity,
-			UpdateActivity,
-		},
-		PaginationResult,
+	db::activity::{
+		Activity,
+		CreateActivity,
+		UpdateActivity,
 	},
 	Error,
 	Result,
@@ -24,6 +27,7 @@ use crate::{
 	DB,
 };
 
+#[openapi(tag = "Activity")]
 #[post("/", data = "<create_activity>")]
 async fn create(
 	user: User,
@@ -33,66 +37,50 @@ async fn create(
 	if user.sys_role != "admin" {
 		return Err(Error::ForbiddenAccess);
 	}
-	let activity = Activity::create(&mut db, &create_activity).await;
-	if let Ok(activity) = activity {
-		Ok(Json(activity))
-	} else {
-		Err(Error::BadRequest("Activity already exists".to_string()))
-	}
+	let activity = Activity::create(&mut db, user.id, &create_activity).await?;
+	Ok(Json(activity))
 }
 
+#[openapi(tag = "Activity")]
 #[patch("/<id>", data = "<update_activity>")]
 async fn update(
-	_user: User,
+	user: User,
 	mut db: Connection<DB>,
 	update_activity: Json<UpdateActivity>,
 	id: i32,
 ) -> Result<Json<Activity>> {
-	Ok(Json(Activity::update(&mut db, id, &update_activity).await?))
-}
-
-#[get("/<id>")]
-async fn get(_user: User, mut db: Connection<DB>, id: i32) -> Result<Json<Activity>> {
-	Ok(Json(Activity::read(&mut db, id).await?))
-}
-
-#[get("/page/<page_size>/<page>")]
-async fn get_page(
-	_user: User,
-	mut db: Connection<DB>,
-	page_size: i64,
-	page: i64,
-) -> Result<Json<PaginationResult<Activity>>> {
-	Ok(Json(Activity::paginate(&mut db, page, page_size).await?))
-}
-
-#[get("/page/<page_size>/last", rank = 2)]
-async fn get_last_page(
-	_user: User,
-	mut db: Connection<DB>,
-	page_size: i64,
-) -> Result<Json<PaginationResult<Activity>>> {
-	let last_page = Activity::last_page(&mut db, page_size).await?;
 	Ok(Json(
-		Activity::paginate(&mut db, last_page, page_size).await?,
+		Activity::update(&mut db, user.id, id, &update_activity).await?,
 	))
 }
 
-#[delete("/<id>")]
-async fn delete(user: User, mut db: Connection<DB>, id: i32) -> Result<Json<usize>> {
+/// Moves every reference over to `target` and soft deletes this activity
+#[openapi(tag = "Activity")]
+#[post("/<id>/merge_into/<target>")]
+async fn merge_into(
+	user: User,
+	mut db: Connection<DB>,
+	id: i32,
+	target: i32,
+) -> Result<Json<Activity>> {
 	if user.sys_role != "admin" {
 		return Err(Error::ForbiddenAccess);
 	}
-	Ok(Json(Activity::delete(&mut db, id).await?))
+	if id == target {
+		return Err(Error::bad_request("merge.into_itself").with_entity("activity"));
+	}
+	Ok(Json(
+		Activity::merge_into(&mut db, user.id, id, target)
+			.await
+			.optional()?
+			.ok_or(Error::NotFound)?,
+	))
 }
 
-pub fn mount() -> AdHoc {
-	AdHoc::on_ignite("Activity", |rocket| async {
-		rocket.mount(
-			"/activity",
-			routes![create, get, update, delete, get_page, get_last_page,],
-		)
-	})
+crud_routes!(Activity, "Activity", read, delete);
+
+pub fn routes(settings: &OpenApiSettings) -> (Vec<Route>, OpenApi) {
+	openapi_get_routes_spec![settings: create, get, update, delete, get_page, get_last_page, restore, purge, merge_into]
 }
 
 #[cfg(test)]
@@ -103,7 +91,6 @@ mod test {
 	};
 	use rocket::{
 		http::Status,
-		local::blocking::Client,
 		serde::json::to_string,
 	};
 
@@ -113,10 +100,10 @@ mod test {
 				Activity,
 				UpdateActivity,
 			},
+			tracking::Tracking,
 			PaginationResult,
 		},
 		error::ErrorJson,
-		rocket,
 		test::{
 			generate_activity,
 			methods::{
@@ -125,48 +112,42 @@ mod test {
 				patch,
 				post,
 			},
-			token::{
-				get_token_admin,
-				get_token_user,
-			},
+			TestApp,
 		},
 	};
 
 	#[tracing_test::traced_test]
 	#[test]
 	fn activity_single() {
-		let client = Client::tracked(rocket()).unwrap();
+		let app = TestApp::new();
 		let activity = generate_activity();
 		let base_url = String::from("/activity");
 
 		// Test unauthorized
-		let res = client
+		let res = app
+			.client
 			.post("/activity")
 			.body(to_string(&activity).unwrap())
 			.dispatch();
 		assert_eq!(res.status(), Status::Unauthorized);
 
 		// Test empty token
-		let res = post(&client, &base_url, to_string(&activity).unwrap(), "");
+		let res = post(&app.client, &base_url, to_string(&activity).unwrap(), "");
 		assert_eq!(res.status(), Status::Unauthorized);
 
-		let token = get_token_admin(&client);
+		let token = app.admin_token();
 		// Create new activity
-		let res = post(&client, &base_url, to_string(&activity).unwrap(), token);
+		let res = post(&app.client, &base_url, to_string(&activity).unwrap(), token);
 		let inserted_activity = res.into_json::<Activity>().unwrap();
 		assert_eq!(inserted_activity, activity);
 		let activity_id = inserted_activity.id;
 
 		// Check duplicate activity insert
-		let res = post(&client, &base_url, to_string(&activity).unwrap(), token);
-		assert_eq!(res.status(), Status::BadRequest);
-		assert_eq!(
-			res.into_string(),
-			Some(
-				to_string(&ErrorJson::new(400, "Activity already exists",))
-					.expect("Could not serialize ErrorJson")
-			)
-		);
+		let res = post(&app.client, &base_url, to_string(&activity).unwrap(), token);
+		assert_eq!(res.status(), Status::Conflict);
+		let error = res.into_json::<ErrorJson>().unwrap();
+		assert_eq!(error.error_code, "activity.name_taken");
+		assert_eq!(error.details[0].field, "name");
 
 		// Update activity
 		let mut new_company_name = activity.name.clone();
@@ -177,22 +158,27 @@ mod test {
 			..Default::default()
 		};
 		let url = format!("{base_url}/{activity_id}");
-		let res = patch(&client, &url, to_string(&update_activity).unwrap(), token);
+		let res = patch(
+			&app.client,
+			&url,
+			to_string(&update_activity).unwrap(),
+			token,
+		);
 		assert_eq!(res.status(), Status::Ok);
 		let updated_activity = res.into_json::<Activity>().unwrap();
 		assert_eq!(updated_activity.name, new_company_name);
 		assert_ne!(updated_activity, inserted_activity);
 
 		// Get activity
-		let res = get(&client, &url, token);
+		let res = get(&app.client, &url, token);
 		assert_eq!(res.status(), Status::Ok);
 		let res = res.into_json::<Activity>().unwrap();
 		assert_eq!(res, updated_activity);
 
 		// Test inserting as normal user
-		let user_token = get_token_user(&client);
+		let user_token = app.user_token();
 		let res = post(
-			&client,
+			&app.client,
 			"/activity",
 			to_string(&activity).unwrap(),
 			user_token,
@@ -200,22 +186,27 @@ mod test {
 		assert_eq!(res.status(), Status::Forbidden);
 
 		// delete activity
-		let res = delete(&client, &url, token);
+		let res = delete(&app.client, &url, token);
 		assert_eq!(res.status(), Status::Ok);
 	}
 
 	#[tracing_test::traced_test]
 	#[test]
 	fn activity_multiple() {
-		let client = Client::tracked(rocket()).unwrap();
-		let token = get_token_admin(&client);
+		let app = TestApp::new();
+		let token = app.admin_token();
 		let base_url = String::from("/activity");
 
 		let mut activity_list: Vec<Activity> = Vec::new();
 		// insert multiple activitys
 		for _ in 0..10 {
 			let activity = generate_activity();
-			let res = post(&client, "/activity", to_string(&activity).unwrap(), token);
+			let res = post(
+				&app.client,
+				"/activity",
+				to_string(&activity).unwrap(),
+				token,
+			);
 			if res.status() != Status::Ok {
 				dbg!(res.into_string());
 				panic!();
@@ -224,12 +215,12 @@ mod test {
 		}
 
 		let url = format!("{base_url}/{}", activity_list[0].id);
-		let res = get(&client, &url, token);
+		let res = get(&app.client, &url, token);
 		assert_eq!(res.status(), Status::Ok);
 		assert_eq!(res.into_json::<Activity>().unwrap(), activity_list[0]);
 
 		// get first page of users with page_size 5
-		let res = get(&client, "/activity/page/5/0", token);
+		let res = get(&app.client, "/activity/page/5/0", token);
 		assert_eq!(res.status(), Status::Ok);
 		let pagination = res.into_json::<PaginationResult<Activity>>().unwrap();
 		assert_eq!(pagination.page, 0);
@@ -237,7 +228,7 @@ mod test {
 
 		// get last page of users with page_size 5 with number
 		let url = format!("{base_url}/page/5/{}", pagination.num_pages - 1);
-		let res = get(&client, &url, token);
+		let res = get(&app.client, &url, token);
 		assert_eq!(res.status(), Status::Ok);
 		let pagination = res.into_json::<PaginationResult<Activity>>().unwrap();
 		// the last page contains 5 OR LESS items
@@ -253,7 +244,7 @@ mod test {
 		);
 
 		let url = format!("{base_url}/page/5/last");
-		let res = get(&client, &url, token);
+		let res = get(&app.client, &url, token);
 		assert_eq!(res.status(), Status::Ok);
 		let last_page = res.into_json::<PaginationResult<Activity>>().unwrap();
 		assert_eq!(last_page, pagination);
@@ -261,8 +252,74 @@ mod test {
 		// delete all activitys
 		for activity in activity_list {
 			let url = format!("{base_url}/{}", activity.id);
-			let res = delete(&client, &url, token);
+			let res = delete(&app.client, &url, token);
 			assert_eq!(res.status(), Status::Ok);
 		}
 	}
+
+	#[tracing_test::traced_test]
+	#[test]
+	fn activity_merge() {
+		let app = TestApp::new();
+		let token = app.admin_token();
+
+		let project = app.fixture().project().create();
+
+		let source = post(
+			&app.client,
+			"/activity",
+			to_string(&generate_activity()).unwrap(),
+			token,
+		)
+		.into_json::<Activity>()
+		.unwrap();
+		let target = post(
+			&app.client,
+			"/activity",
+			to_string(&generate_activity()).unwrap(),
+			token,
+		)
+		.into_json::<Activity>()
+		.unwrap();
+
+		let both = app
+			.fixture()
+			.tracking()
+			.project(&project)
+			.activities(&[source.id, target.id])
+			.create();
+		let only_source = app
+			.fixture()
+			.tracking()
+			.project(&project)
+			.activities(&[source.id])
+			.create();
+
+		let self_url = format!("/activity/{}/merge_into/{}", source.id, source.id);
+		let res = post(&app.client, &self_url, String::new(), token);
+		assert_eq!(res.status(), Status::BadRequest);
+		let merge_url = format!("/activity/{}/merge_into/{}", source.id, target.id);
+		let res = post(&app.client, &merge_url, String::new(), app.user_token());
+		assert_eq!(res.status(), Status::Forbidden);
+		let res = post(&app.client, &merge_url, String::new(), token);
+		assert_eq!(res.status(), Status::Ok);
+		assert_eq!(res.into_json::<Activity>().unwrap().id, target.id);
+
+		// the link to the source collapses into the existing link to the target
+		for tracking in [both, only_source] {
+			let url = format!("/tracking/{}", tracking.id);
+			let merged = get(&app.client, &url, token)
+				.into_json::<Tracking>()
+				.unwrap();
+			assert_eq!(merged.activities, [target.id]);
+		}
+		// the source is soft deleted
+		let source_url = format!("/activity/{}", source.id);
+		let res = get(&app.client, &source_url, token);
+		assert_eq!(res.status(), Status::NotFound);
+
+		// merging a deleted activity fails
+		let res = post(&app.client, &merge_url, String::new(), token);
+		assert_eq!(res.status(), Status::NotFound);
+	}
 }
@@ -0,0 +1,159 @@
+use rocket::{
+	get,
+	serde::json::Json,
+	FromForm,
+	Route,
+};
+use rocket_db_pools::Connection;
+use rocket_okapi::{
+	okapi::openapi3::OpenApi,
+	openapi,
+	openapi_get_routes_spec,
+	settings::OpenApiSettings,
+};
+use schemars::JsonSchema;
+
+use crate::{
+	db::{
+		audit::{
+			Audit,
+			AuditFilter,
+		},
+		PaginationResult,
+	},
+	Error,
+	Result,
+	User,
+	DB,
+};
+
+/// Query parameters of `GET /audit`, all of them are optional
+#[derive(Debug, FromForm, JsonSchema)]
+struct AuditQuery {
+	/// Name of the table, e.g. `tracking`
+	entity: Option<String>,
+	/// Primary key of the row in `entity`
+	id: Option<i32>,
+	/// Id of the user that made the change
+	user: Option<i32>,
+	page: Option<i64>,
+	page_size: Option<i64>,
+}
+
+#[openapi(tag = "Audit")]
+#[get("/?<query..>")]
+async fn get(
+	user: User,
+	mut db: Connection<DB>,
+	query: AuditQuery,
+) -> Result<Json<PaginationResult<Audit>>> {
+	if user.sys_role != "admin" {
+		return Err(Error::ForbiddenAccess);
+	}
+	let filter = AuditFilter {
+		entity: query.entity,
+		entity_id: query.id,
+		user_id: query.user,
+	};
+	Ok(Json(
+		Audit::filter(
+			&mut db,
+			&filter,
+			query.page.unwrap_or(0),
+			query.page_size.unwrap_or(50),
+		)
+		.await?,
+	))
+}
+
+pub fn routes(settings: &OpenApiSettings) -> (Vec<Route>, OpenApi) {
+	openapi_get_routes_spec![settings: get]
+}
+
+#[cfg(test)]
+mod test {
+	use pretty_assertions::assert_eq;
+	use rocket::{
+		http::Status,
+		serde::json::to_string,
+	};
+
+	use crate::{
+		db::{
+			audit::{
+				Action,
+				Audit,
+			},
+			client::{
+				Client,
+				UpdateClient,
+			},
+			PaginationResult,
+		},
+		test::{
+			generate_client,
+			methods::{
+				delete,
+				get,
+				patch,
+				post,
+			},
+			TestApp,
+		},
+	};
+
+	#[tracing_test::traced_test]
+	#[test]
+	fn audit_client() {
+		let app = TestApp::new();
+		let token = app.admin_token();
+		let user_token = app.user_token();
+
+		let res = post(
+			&app.client,
+			"/client",
+			to_string(&generate_client()).unwrap(),
+			token,
+		);
+		assert_eq!(res.status(), Status::Ok);
+		let inserted_client = res.into_json::<Client>().unwrap();
+		let url = format!("/client/{}", inserted_client.id);
+
+		let new_name = format!("{} GmbH", inserted_client.name);
+		let update_client = UpdateClient {
+			name: Some(new_name.clone()),
+			..Default::default()
+		};
+		let res = patch(&app.client, &url, to_string(&update_client).unwrap(), token);
+		assert_eq!(res.status(), Status::Ok);
+		let res = delete(&app.client, &url, token);
+		assert_eq!(res.status(), Status::Ok);
+
+		let audit_url = format!("/audit?entity=client&id={}", inserted_client.id);
+		let res = get(&app.client, &audit_url, user_token);
+		assert_eq!(res.status(), Status::Forbidden);
+
+		let res = get(&app.client, &audit_url, token);
+		assert_eq!(res.status(), Status::Ok);
+		let audit = res.into_json::<PaginationResult<Audit>>().unwrap();
+		assert_eq!(audit.total_items, 3);
+		let actions: Vec<Action> = audit.items.iter().map(|entry| entry.action).collect();
+		// newest entries come first
+		assert_eq!(actions, [Action::Delete, Action::Update, Action::Create]);
+
+		let update = &audit.items[1];
+		assert_eq!(update.entity, "client");
+		assert_eq!(update.entity_id, inserted_client.id);
+		assert_eq!(
+			update.old_value.as_ref().unwrap()["name"],
+			inserted_client.name.as_str()
+		);
+		assert_eq!(
+			update.new_value.as_ref().unwrap()["name"],
+			new_name.as_str()
+		);
+		// deleting is a soft delete, so only `deleted_at` changes
+		assert!(audit.items[0].new_value.as_ref().unwrap()["deleted_at"].is_string());
+		assert!(audit.items[2].old_value.is_none());
+	}
+}
@@ -0,0 +1,249 @@
+use rocket::{
+	get,
+	serde::json::Json,
+	Route,
+};
+use rocket_db_pools::Connection;
+use rocket_okapi::{
+	okapi::openapi3::OpenApi,
+	openapi,
+	openapi_get_routes_spec,
+	settings::OpenApiSettings,
+};
+
+use crate::{
+	db::billing::BillingReport,
+	routes::{
+		ensure_lead,
+		parse_period,
+	},
+	Result,
+	User,
+	DB,
+};
+
+/// Billable hours of a project, for admins and leads of the project
+#[openapi(tag = "Billing")]
+#[get("/project/<project_id>?<from>&<to>")]
+async fn get_project(
+	user: User,
+	mut db: Connection<DB>,
+	project_id: i32,
+	from: &str,
+	to: &str,
+) -> Result<Json<BillingReport>> {
+	ensure_lead(&mut db, &user, project_id).await?;
+	let (from, to) = parse_period(from, to)?;
+	Ok(Json(
+		BillingReport::project(&mut db, project_id, from, to).await?,
+	))
+}
+
+pub fn routes(settings: &OpenApiSettings) -> (Vec<Route>, OpenApi) {
+	openapi_get_routes_spec![settings: get_project]
+}
+
+#[cfg(test)]
+mod test {
+	use bigdecimal::BigDecimal;
+	use pretty_assertions::assert_eq;
+	use rocket::{
+		http::Status,
+		serde::json::to_string,
+	};
+
+	use crate::{
+		db::{
+			activity::Activity,
+			billing::BillingReport,
+			client::Client,
+			project::Project,
+			project_budget::{
+				BudgetConsumption,
+				CreateProjectBudget,
+				Decimal,
+				UNIT_HOURS,
+			},
+			project_member::{
+				CreateProjectMember,
+				ROLE_MEMBER,
+			},
+			tracking::CreateTracking,
+			user::User,
+		},
+		error::ErrorJson,
+		test::{
+			generate_activity,
+			generate_client,
+			generate_project,
+			generate_user,
+			methods::{
+				get,
+				post,
+				put,
+			},
+			TestApp,
+		},
+	};
+
+	#[tracing_test::traced_test]
+	#[test]
+	fn billing_excludes_non_billable() {
+		let app = TestApp::new();
+		let token = app.admin_token();
+
+		let client_db = post(
+			&app.client,
+			"/client",
+			to_string(&generate_client()).unwrap(),
+			token,
+		)
+		.into_json::<Client>()
+		.unwrap();
+		let user = post(
+			&app.client,
+			"/user",
+			to_string(&generate_user()).unwrap(),
+			token,
+		)
+		.into_json::<User>()
+		.unwrap();
+		let mut project = generate_project();
+		project.client_id = client_db.id;
+		let project = post(&app.client, "/project", to_string(&project).unwrap(), token)
+			.into_json::<Project>()
+			.unwrap();
+		let member = CreateProjectMember {
+			project_id: project.id,
+			user_id: user.id,
+			role: ROLE_MEMBER.to_string(),
+		};
+		let member_url = format!("/project/{}/member", project.id);
+		post(&app.client, &member_url, to_string(&member).unwrap(), token);
+
+		let billable = post(
+			&app.client,
+			"/activity",
+			to_string(&generate_activity()).unwrap(),
+			token,
+		)
+		.into_json::<Activity>()
+		.unwrap();
+		let mut pause = generate_activity();
+		pause.billable = false;
+		let pause = post(&app.client, "/activity", to_string(&pause).unwrap(), token)
+			.into_json::<Activity>()
+			.unwrap();
+		assert!(!pause.billable);
+		let other = post(
+			&app.client,
+			"/activity",
+			to_string(&generate_activity()).unwrap(),
+			token,
+		)
+		.into_json::<Activity>()
+		.unwrap();
+
+		let activity_url = format!("/project/{}/activity", project.id);
+		let whitelist = vec![billable.id, pause.id];
+		let res = put(
+			&app.client,
+			&activity_url,
+			to_string(&whitelist).unwrap(),
+			app.user_token(),
+		);
+		assert_eq!(res.status(), Status::Forbidden);
+		let res = put(
+			&app.client,
+			&activity_url,
+			to_string(&whitelist).unwrap(),
+			token,
+		);
+		assert_eq!(res.status(), Status::Ok);
+
+		let mut tracking = CreateTracking {
+			client_id: client_db.id,
+			user_id: user.id,
+			project_id: project.id,
+			date: chrono::NaiveDate::from_ymd_opt(2024, 2, 19).unwrap(),
+			begin: chrono::NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+			end: chrono::NaiveTime::from_hms_opt(11, 0, 0).unwrap(),
+			pause: None,
+			performed: 2.0,
+			billed: 2.0,
+			description: None,
+			activities: vec![other.id],
+			tags: vec![],
+			custom_fields: Default::default(),
+		};
+		let res = post(
+			&app.client,
+			"/tracking",
+			to_string(&tracking).unwrap(),
+			token,
+		);
+		assert_eq!(res.status(), Status::BadRequest);
+		let error = res.into_json::<ErrorJson>().unwrap();
+		assert_eq!(error.error, "Activity is not allowed on this project");
+		assert_eq!(error.error_code, "tracking.activity_not_allowed");
+		assert_eq!(error.details[0].field, "activities");
+
+		tracking.activities = vec![billable.id];
+		let res = post(
+			&app.client,
+			"/tracking",
+			to_string(&tracking).unwrap(),
+			token,
+		);
+		assert_eq!(res.status(), Status::Ok);
+		tracking.activities = vec![pause.id];
+		tracking.billed = 0.5;
+		let res = post(
+			&app.client,
+			"/tracking",
+			to_string(&tracking).unwrap(),
+			token,
+		);
+		assert_eq!(res.status(), Status::Ok);
+		// a billable activity makes the whole entry billable
+		tracking.activities = vec![billable.id, pause.id];
+		tracking.billed = 1.0;
+		let res = post(
+			&app.client,
+			"/tracking",
+			to_string(&tracking).unwrap(),
+			token,
+		);
+		assert_eq!(res.status(), Status::Ok);
+
+		let billing_url = format!(
+			"/billing/project/{}?from=2024-02-01&to=2024-02-29",
+			project.id
+		);
+		let res = get(&app.client, &billing_url, token);
+		assert_eq!(res.status(), Status::Ok);
+		let report = res.into_json::<BillingReport>().unwrap();
+		assert_eq!(report.entries, 2);
+		assert_eq!(report.billed, 3.0);
+		assert_eq!(report.excluded_entries, 1);
+		assert_eq!(report.excluded_billed, 0.5);
+
+		// budgets count the same entries as billing
+		let budget = CreateProjectBudget {
+			project_id: project.id,
+			activity_id: Some(pause.id),
+			unit: UNIT_HOURS.to_string(),
+			amount: Decimal(BigDecimal::from(10)),
+			hourly_rate: None,
+			thresholds: Default::default(),
+			hard_stop: false,
+		};
+		let budget_url = format!("/project/{}/budget", project.id);
+		let res = post(&app.client, &budget_url, to_string(&budget).unwrap(), token);
+		assert_eq!(res.status(), Status::Ok);
+		let consumption_url = format!("{budget_url}/consumption");
+		let res = get(&app.client, &consumption_url, token);
+		let consumption = res.into_json::<Vec<BudgetConsumption>>().unwrap();
+		assert_eq!(consumption[0].billed, 1.0);
+	}
+}
@@ -1,96 +1,177 @@
 use rocket::{
 	delete,
-	fairing::AdHoc,
 	get,
 	patch,
 	post,
-	routes,
 	serde::json::Json,
+	Route,
 };
 use rocket_db_pools::Connection;
+use rocket_okapi::{
+	okapi::openapi3::OpenApi,
+	openapi,
+	openapi_get_routes_spec,
+	settings::OpenApiSettings,
+};
 
 use crate::{
 	db::{
 		client::{
+			check_billing,
 			Client,
 			CreateClient,
 			UpdateClient,
 		},
+		custom_field::{
+			CustomField,
+			WithCustomFields,
+			ENTITY_CLIENT,
+		},
+		ListQuery,
 		PaginationResult,
 	},
+	routes::ensure_admin,
 	Error,
 	Result,
 	User,
 	DB,
 };
 
+#[openapi(tag = "Client")]
 #[post("/", data = "<create_client>")]
 async fn create(
 	user: User,
 	mut db: Connection<DB>,
-	create_client: Json<CreateClient>,
-) -> Result<Json<Client>> {
-	if user.sys_role != "admin" {
-		return Err(Error::ForbiddenAccess);
-	}
-	let client = Client::create(&mut db, &create_client).await;
-	if let Ok(client) = client {
-		Ok(Json(client))
-	} else {
-		Err(Error::BadRequest("Client already exists".to_string()))
-	}
+	create_client: Json<WithCustomFields<CreateClient>>,
+) -> Result<Json<WithCustomFields<Client>>> {
+	ensure_admin(&user)?;
+	CustomField::validate(&mut db, ENTITY_CLIENT, &create_client.custom_fields, true).await?;
+	check_billing(&create_client.currency, create_client.payment_terms)?;
+	let client = Client::create(&mut db, user.id, &create_client.item).await?;
+	let custom_fields = CustomField::set_values(
+		&mut db,
+		user.id,
+		ENTITY_CLIENT,
+		client.id,
+		&create_client.custom_fields,
+	)
+	.await?;
+	Ok(Json(WithCustomFields {
+		item: client,
+		custom_fields,
+	}))
 }
 
+#[openapi(tag = "Client")]
 #[patch("/<id>", data = "<update_client>")]
 async fn update(
-	_user: User,
+	user: User,
 	mut db: Connection<DB>,
-	update_client: Json<UpdateClient>,
+	update_client: Json<WithCustomFields<UpdateClient>>,
 	id: i32,
-) -> Result<Json<Client>> {
-	Ok(Json(Client::update(&mut db, id, &update_client).await?))
+) -> Result<Json<WithCustomFields<Client>>> {
+	ensure_admin(&user)?;
+	CustomField::validate(&mut db, ENTITY_CLIENT, &update_client.custom_fields, false).await?;
+	if update_client.currency.is_some() || update_client.payment_terms.is_some() {
+		let client = Client::read(&mut db, id).await?;
+		check_billing(
+			update_client
+				.currency
+				.as_deref()
+				.unwrap_or(&client.currency),
+			update_client.payment_terms.unwrap_or(client.payment_terms),
+		)?;
+	}
+	let client = Client::update(&mut db, user.id, id, &update_client.item).await?;
+	let custom_fields = CustomField::set_values(
+		&mut db,
+		user.id,
+		ENTITY_CLIENT,
+		id,
+		&update_client.custom_fields,
+	)
+	.await?;
+	Ok(Json(WithCustomFields {
+		item: client,
+		custom_fields,
+	}))
 }
 
+#[openapi(tag = "Client")]
 #[get("/<id>")]
-async fn get(_user: User, mut db: Connection<DB>, id: i32) -> Result<Json<Client>> {
-	Ok(Json(Client::read(&mut db, id).await?))
+async fn get(
+	_user: User,
+	mut db: Connection<DB>,
+	id: i32,
+) -> Result<Json<WithCustomFields<Client>>> {
+	let client = Client::read(&mut db, id).await?;
+	Ok(Json(
+		CustomField::inline(&mut db, ENTITY_CLIENT, id, client).await?,
+	))
 }
 
-#[get("/page/<page_size>/<page>")]
+#[openapi(tag = "Client")]
+#[get("/page/<page_size>/<page>?<include_archived>&<list..>")]
 async fn get_page(
 	_user: User,
 	mut db: Connection<DB>,
 	page_size: i64,
 	page: i64,
-) -> Result<Json<PaginationResult<Client>>> {
-	Ok(Json(Client::paginate(&mut db, page, page_size).await?))
+	include_archived: Option<bool>,
+	list: ListQuery,
+) -> Result<Json<PaginationResult<WithCustomFields<Client>>>> {
+	let page = Client::paginate(
+		&mut db,
+		page,
+		page_size,
+		include_archived.unwrap_or_default(),
+		&list,
+	)
+	.await?;
+	Ok(Json(
+		CustomField::inline_page(&mut db, ENTITY_CLIENT, page, |client| client.id).await?,
+	))
 }
 
-#[get("/page/<page_size>/last", rank = 2)]
+#[openapi(tag = "Client")]
+#[get("/page/<page_size>/last?<include_archived>&<list..>", rank = 2)]
 async fn get_last_page(
 	_user: User,
 	mut db: Connection<DB>,
 	page_size: i64,
-) -> Result<Json<PaginationResult<Client>>> {
-	let last_page = Client::last_page(&mut db, page_size).await?;
-	Ok(Json(Client::paginate(&mut db, last_page, page_size).await?))
+	include_archived: Option<bool>,
+	list: ListQuery,
+) -> Result<Json<PaginationResult<WithCustomFields<Client>>>> {
+	let include_archived = include_archived.unwrap_or_default();
+	let last_page = Client::last_page(&mut db, page_size, include_archived, &list).await?;
+	let page = Client::paginate(&mut db, last_page, page_size, include_archived, &list).await?;
+	Ok(Json(
+		CustomField::inline_page(&mut db, ENTITY_CLIENT, page, |client| client.id).await?,
+	))
 }
 
-#[delete("/<id>")]
-async fn delete(user: User, mut db: Connection<DB>, id: i32) -> Result<Json<usize>> {
-	if user.sys_role != "admin" {
-		return Err(Error::ForbiddenAccess);
+/// Moves every reference over to `target` and soft deletes this client
+#[openapi(tag = "Client")]
+#[post("/<id>/merge_into/<target>")]
+async fn merge_into(
+	user: User,
+	mut db: Connection<DB>,
+	id: i32,
+	target: i32,
+) -> Result<Json<Client>> {
+	ensure_admin(&user)?;
+	if id == target {
+		return Err(Error::bad_request("merge.into_itself").with_entity("client"));
 	}
-	Ok(Json(Client::delete(&mut db, id).await?))
+	Ok(Json(
+		Client::merge_into(&mut db, user.id, id, target).await?,
+	))
 }
 
-pub fn mount() -> AdHoc {
-	AdHoc::on_ignite("Client", |rocket| async {
-		rocket.mount(
-			"/client",
-			routes![create, get, update, delete, get_page, get_last_page,],
-		)
-	})
+crud_routes!(Client, "Client", delete);
+
+pub fn routes(settings: &OpenApiSettings) -> (Vec<Route>, OpenApi) {
+	openapi_get_routes_spec![settings: create, get, update, delete, get_page, get_last_page, restore, purge, merge_into]
 }
 
 #[cfg(test)]
@@ -101,80 +182,70 @@ mod test {
 	};
 	use rocket::{
 		http::Status,
-		local::blocking::Client as RocketClient,
 		serde::json::to_string,
 	};
 
 	use crate::{
 		db::{
+			audit::{
+				Action,
+				Audit,
+			},
 			client::{
 				Client,
 				UpdateClient,
 			},
+			client_contact::ClientContact,
+			project::Project,
+			tracking::Tracking,
 			PaginationResult,
 		},
 		error::ErrorJson,
-		rocket,
 		test::{
 			generate_client,
+			generate_client_contact,
 			methods::{
 				delete,
 				get,
 				patch,
 				post,
 			},
-			token::{
-				get_token_admin,
-				get_token_user,
-			},
+			TestApp,
 		},
 	};
 
 	#[tracing_test::traced_test]
 	#[test]
 	fn client_single() {
-		let rocket_client = RocketClient::tracked(rocket()).unwrap();
+		let app = TestApp::new();
 		let client = generate_client();
 		let base_url = String::from("/client");
 
 		// Test unauthorized
-		let res = rocket_client
+		let res = app
+			.client
 			.post("/client")
 			.body(to_string(&client).unwrap())
 			.dispatch();
 		assert_eq!(res.status(), Status::Unauthorized);
 
 		// Test with empty token
-		let res = post(&rocket_client, &base_url, to_string(&client).unwrap(), "");
+		let res = post(&app.client, &base_url, to_string(&client).unwrap(), "");
 		assert_eq!(res.status(), Status::Unauthorized);
 
-		let token = get_token_admin(&rocket_client);
+		let token = app.admin_token();
 		// Create new client
-		let res = post(
-			&rocket_client,
-			&base_url,
-			to_string(&client).unwrap(),
-			token,
-		);
+		let res = post(&app.client, &base_url, to_string(&client).unwrap(), token);
 		let inserted_client = res.into_json::<Client>().unwrap();
 		assert_eq!(inserted_client, client);
 		let client_id = inserted_client.id;
 
 		// Check duplicate client insert
-		let res = post(
-			&rocket_client,
-			&base_url,
-			to_string(&client).unwrap(),
-			token,
-		);
-		assert_eq!(res.status(), Status::BadRequest);
-		assert_eq!(
-			res.into_string(),
-			Some(
-				to_string(&ErrorJson::new(400, "Client already exists",))
-					.expect("Could not serialize ErrorJson")
-			)
-		);
+		let res = post(&app.client, &base_url, to_string(&client).unwrap(), token);
+		assert_eq!(res.status(), Status::Conflict);
+		let error = res.into_json::<ErrorJson>().unwrap();
+		assert_eq!(error.error_code, "client.name_taken");
+		assert_eq!(error.details[0].field, "name");
 
 		// Update client
 		let mut new_company_name = client.name.clone();
@@ -185,55 +256,54 @@ mod test {
 			..Default::default()
 		};
 		let url = format!("{base_url}/{client_id}");
-		let res = patch(
-			&rocket_client,
-			&url,
-			to_string(&update_client).unwrap(),
-			token,
-		);
+		let res = patch(&app.client, &url, to_string(&update_client).unwrap(), token);
 		assert_eq!(res.status(), Status::Ok);
 		let updated_client = res.into_json::<Client>().unwrap();
 		assert_eq!(updated_client.name, new_company_name);
 		assert_ne!(updated_client, inserted_client);
 
 		// Get client
-		let res = get(&rocket_client, &url, token);
+		let res = get(&app.client, &url, token);
 		assert_eq!(res.status(), Status::Ok);
 		let res = res.into_json::<Client>().unwrap();
 		assert_eq!(res, updated_client);
 
 		// Test inserting as normal user
-		let user_token = get_token_user(&rocket_client);
+		let user_token = app.user_token();
 		let res = post(
-			&rocket_client,
+			&app.client,
 			&base_url,
 			to_string(&client).unwrap(),
 			user_token,
 		);
 		assert_eq!(res.status(), Status::Forbidden);
 
+		// Test updating as normal user
+		let res = patch(
+			&app.client,
+			&url,
+			to_string(&update_client).unwrap(),
+			user_token,
+		);
+		assert_eq!(res.status(), Status::Forbidden);
+
 		// delete client
-		let res = delete(&rocket_client, &url, token);
+		let res = delete(&app.client, &url, token);
 		assert_eq!(res.status(), Status::Ok);
 	}
 
 	#[tracing_test::traced_test]
 	#[test]
 	fn client_multiple() {
-		let rocket_client = RocketClient::tracked(rocket()).unwrap();
-		let token = get_token_admin(&rocket_client);
+		let app = TestApp::new();
+		let token = app.admin_token();
 		let base_url = String::from("/client");
 
 		let mut client_list: Vec<Client> = Vec::new();
 		// insert multiple clients
 		for _ in 0..10 {
 			let client = generate_client();
-			let res = post(
-				&rocket_client,
-				&base_url,
-				to_string(&client).unwrap(),
-				token,
-			);
+			let res = post(&app.client, &base_url, to_string(&client).unwrap(), token);
 			if res.status() != Status::Ok {
 				dbg!(res.into_string());
 				panic!();
@@ -242,13 +312,13 @@ mod test {
 		}
 
 		let url = format!("{base_url}/{}", client_list[0].id);
-		let res = get(&rocket_client, &url, token);
+		let res = get(&app.client, &url, token);
 		assert_eq!(res.status(), Status::Ok);
 		assert_eq!(res.into_json::<Client>().unwrap(), client_list[0]);
 
 		// get first page of users with page_size 5
 		let url = format!("{base_url}/page/5/0");
-		let res = get(&rocket_client, &url, token);
+		let res = get(&app.client, &url, token);
 		assert_eq!(res.status(), Status::Ok);
 		let pagination = res.into_json::<PaginationResult<Client>>().unwrap();
 		assert_eq!(pagination.page, 0);
@@ -256,7 +326,7 @@ mod test {
 
 		// get last page of users with page_size 5 with number
 		let url = format!("{base_url}/page/5/{}", pagination.num_pages - 1);
-		let res = get(&rocket_client, &url, token);
+		let res = get(&app.client, &url, token);
 		assert_eq!(res.status(), Status::Ok);
 		let pagination = res.into_json::<PaginationResult<Client>>().unwrap();
 		// the last page contains 5 OR LESS items
@@ -272,7 +342,7 @@ mod test {
 		);
 
 		let url = format!("{base_url}/page/5/last");
-		let res = get(&rocket_client, &url, token);
+		let res = get(&app.client, &url, token);
 		assert_eq!(res.status(), Status::Ok);
 		let last_page = res.into_json::<PaginationResult<Client>>().unwrap();
 		assert_eq!(last_page, pagination);
@@ -280,8 +350,170 @@ mod test {
 		// delete all clients
 		for client in client_list {
 			let url = format!("{base_url}/{}", client.id);
-			let res = delete(&rocket_client, &url, token);
+			let res = delete(&app.client, &url, token);
 			assert_eq!(res.status(), Status::Ok);
 		}
 	}
+
+	#[tracing_test::traced_test]
+	#[test]
+	fn client_list_query() {
+		let app = TestApp::new();
+		let token = app.admin_token();
+		let base_url = String::from("/client");
+
+		let client_list = [
+			app.fixture().client().name("list-a").create(),
+			app.fixture().client().name("list-b").create(),
+			app.fixture().client().name("list-c").archived().create(),
+		];
+
+		let url = format!(
+			"{base_url}/page/10/0?include_archived=true&sort=-name&q=list&filter[archived]=false"
+		);
+		let res = get(&app.client, &url, token);
+		assert_eq!(res.status(), Status::Ok);
+		let page = res.into_json::<PaginationResult<Client>>().unwrap();
+		assert_eq!(page.total_items, 2);
+		assert_eq!(
+			page
+				.items
+				.iter()
+				.map(|client| client.id)
+				.collect::<Vec<_>>(),
+			[client_list[1].id, client_list[0].id]
+		);
+
+		let url = format!("{base_url}/page/10/0?sort=password");
+		let res = get(&app.client, &url, token);
+		assert_eq!(res.status(), Status::BadRequest);
+		let url = format!("{base_url}/page/10/0?filter[archived]=maybe");
+		let res = get(&app.client, &url, token);
+		assert_eq!(res.status(), Status::BadRequest);
+	}
+
+	#[tracing_test::traced_test]
+	#[test]
+	fn client_soft_delete() {
+		let app = TestApp::new();
+		let token = app.admin_token();
+		let base_url = String::from("/client");
+
+		let res = post(
+			&app.client,
+			&base_url,
+			to_string(&generate_client()).unwrap(),
+			token,
+		);
+		let inserted_client = res.into_json::<Client>().unwrap();
+		assert!(inserted_client.deleted_at.is_none());
+		let url = format!("{base_url}/{}", inserted_client.id);
+
+		let project = app.fixture().project().client(&inserted_client).create();
+
+		// soft delete hides the client
+		let res = delete(&app.client, &url, token);
+		assert_eq!(res.status(), Status::Ok);
+		let res = get(&app.client, &url, token);
+		assert_eq!(res.status(), Status::NotFound);
+
+		// from the lists as well
+		let last_page_url = format!("{base_url}/page/5/last");
+		let res = get(&app.client, &last_page_url, token);
+		let last_page = res.into_json::<PaginationResult<Client>>().unwrap();
+		assert!(last_page
+			.items
+			.iter()
+			.all(|client| client.id != inserted_client.id));
+
+		// it can't be changed until it is restored
+		let update_client = UpdateClient {
+			name: Some(format!("{} GmbH", inserted_client.name)),
+			..Default::default()
+		};
+		let res = patch(&app.client, &url, to_string(&update_client).unwrap(), token);
+		assert_eq!(res.status(), Status::NotFound);
+
+		let restore_url = format!("{url}/restore");
+		let user_token = app.user_token();
+		let res = post(&app.client, &restore_url, String::new(), user_token);
+		assert_eq!(res.status(), Status::Forbidden);
+		let res = post(&app.client, &restore_url, String::new(), token);
+		assert_eq!(res.status(), Status::Ok);
+		assert_eq!(res.into_json::<Client>().unwrap().deleted_at, None);
+		let res = get(&app.client, &url, token);
+		assert_eq!(res.status(), Status::Ok);
+
+		// the project blocks the purge
+		let purge_url = format!("{url}/purge");
+		let res = delete(&app.client, &purge_url, token);
+		assert_eq!(res.status(), Status::Conflict);
+		let error = res.into_json::<ErrorJson>().unwrap();
+		assert_eq!(error.error, "Client is still referenced by: 1 project");
+		assert_eq!(error.error_code, "purge.referenced");
+
+		let project_purge_url = format!("/project/{}/purge", project.id);
+		let res = delete(&app.client, &project_purge_url, token);
+		assert_eq!(res.status(), Status::Ok);
+		let res = delete(&app.client, &purge_url, token);
+		assert_eq!(res.status(), Status::Ok);
+		let res = get(&app.client, &url, token);
+		assert_ne!(res.status(), Status::Ok);
+	}
+
+	#[tracing_test::traced_test]
+	#[test]
+	fn client_merge() {
+		let app = TestApp::new();
+		let token = app.admin_token();
+		let source = app.fixture().client().create();
+		let target = app.fixture().client().create();
+		let project = app.fixture().project().client(&source).create();
+		let tracking = app.fixture().tracking().project(&project).create();
+		let contacts_url = format!("/client/{}/contacts", source.id);
+		let contact = post(
+			&app.client,
+			&contacts_url,
+			to_string(&generate_client_contact()).unwrap(),
+			token,
+		)
+		.into_json::<ClientContact>()
+		.unwrap();
+
+		let merge_url = format!("/client/{}/merge_into/{}", source.id, target.id);
+		let res = post(&app.client, &merge_url, String::new(), app.user_token());
+		assert_eq!(res.status(), Status::Forbidden);
+		let res = post(&app.client, &merge_url, String::new(), token);
+		assert_eq!(res.status(), Status::Ok);
+		assert_eq!(res.into_json::<Client>().unwrap().id, target.id);
+
+		// projects, entries and contacts belong to the target now
+		let project_url = format!("/project/{}", project.id);
+		let res = get(&app.client, &project_url, token);
+		assert_eq!(res.into_json::<Project>().unwrap().client_id, target.id);
+		let tracking_url = format!("/tracking/{}", tracking.id);
+		let res = get(&app.client, &tracking_url, token);
+		assert_eq!(res.into_json::<Tracking>().unwrap().client_id, target.id);
+		let contacts_url = format!("/client/{}/contacts", target.id);
+		let res = get(&app.client, &contacts_url, token);
+		let contacts = res.into_json::<Vec<ClientContact>>().unwrap();
+		assert_eq!(contacts.len(), 1);
+		assert_eq!(contacts[0].id, contact.id);
+
+		// the source is soft deleted
+		let source_url = format!("/client/{}", source.id);
+		let res = get(&app.client, &source_url, token);
+		assert_eq!(res.status(), Status::NotFound);
+
+		let audit_url = format!("/audit?entity=client&id={}", source.id);
+		let res = get(&app.client, &audit_url, token);
+		let audit = res.into_json::<PaginationResult<Audit>>().unwrap();
+		let actions: Vec<Action> = audit.items.iter().map(|entry| entry.action).collect();
+		assert_eq!(actions, [Action::Delete, Action::Merge]);
+		assert_eq!(audit.items[1].new_value.as_ref().unwrap()["id"], target.id);
+
+		// merging a deleted client fails
+		let res = post(&app.client, &merge_url, String::new(), token);
+		assert_eq!(res.status(), Status::NotFound);
+	}
 }
@@ -0,0 +1,276 @@
+use diesel::OptionalExtension;
+use rocket::{
+	delete,
+	get,
+	patch,
+	post,
+	serde::json::Json,
+	Route,
+};
+use rocket_db_pools::Connection;
+use rocket_okapi::{
+	okapi::openapi3::OpenApi,
+	openapi,
+	openapi_get_routes_spec,
+	settings::OpenApiSettings,
+};
+
+use crate::{
+	db::{
+		client::Client,
+		client_contact::{
+			ClientContact,
+			CreateClientContact,
+			UpdateClientContact,
+		},
+	},
+	Error,
+	Result,
+	User,
+	DB,
+};
+
+/// Only a rough sanity check, the address is never used to send mails
+fn check_email(email: Option<&str>) -> Result<()> {
+	match email {
+		Some(email) if !email.contains('@') => Err(Error::bad_request("client_contact.invalid_email")),
+		_ => Ok(()),
+	}
+}
+
+#[openapi(tag = "Client Contact")]
+#[get("/<client_id>/contacts")]
+async fn get_all(
+	_user: User,
+	mut db: Connection<DB>,
+	client_id: i32,
+) -> Result<Json<Vec<ClientContact>>> {
+	Client::read(&mut db, client_id)
+		.await
+		.optional()?
+		.ok_or(Error::NotFound)?;
+	Ok(Json(ClientContact::from_client(&mut db, client_id).await?))
+}
+
+#[openapi(tag = "Client Contact")]
+#[post("/<client_id>/contacts", data = "<create_contact>")]
+async fn create(
+	user: User,
+	mut db: Connection<DB>,
+	client_id: i32,
+	create_contact: Json<CreateClientContact>,
+) -> Result<Json<ClientContact>> {
+	if user.sys_role != "admin" {
+		return Err(Error::ForbiddenAccess);
+	}
+	check_email(create_contact.email.as_deref())?;
+	Client::read(&mut db, client_id)
+		.await
+		.optional()?
+		.ok_or(Error::NotFound)?;
+	let mut create_contact = create_contact.into_inner();
+	create_contact.client_id = client_id;
+	Ok(Json(
+		ClientContact::create(&mut db, user.id, &create_contact).await?,
+	))
+}
+
+#[openapi(tag = "Client Contact")]
+#[get("/<client_id>/contacts/<id>")]
+async fn get(
+	_user: User,
+	mut db: Connection<DB>,
+	client_id: i32,
+	id: i32,
+) -> Result<Json<ClientContact>> {
+	Ok(Json(
+		ClientContact::of_client(&mut db, client_id, id)
+			.await
+			.optional()?
+			.ok_or(Error::NotFound)?,
+	))
+}
+
+#[openapi(tag = "Client Contact")]
+#[patch("/<client_id>/contacts/<id>", data = "<update_contact>")]
+async fn update(
+	user: User,
+	mut db: Connection<DB>,
+	client_id: i32,
+	id: i32,
+	update_contact: Json<UpdateClientContact>,
+) -> Result<Json<ClientContact>> {
+	if user.sys_role != "admin" {
+		return Err(Error::ForbiddenAccess);
+	}
+	check_email(update_contact.email.clone().flatten().as_deref())?;
+	let contact = ClientContact::of_client(&mut db, client_id, id)
+		.await
+		.optional()?
+		.ok_or(Error::NotFound)?;
+	Ok(Json(
+		ClientContact::update(&mut db, user.id, contact.id, &update_contact).await?,
+	))
+}
+
+#[openapi(tag = "Client Contact")]
+#[delete("/<client_id>/contacts/<id>")]
+async fn delete(
+	user: User,
+	mut db: Connection<DB>,
+	client_id: i32,
+	id: i32,
+) -> Result<Json<usize>> {
+	if user.sys_role != "admin" {
+		return Err(Error::ForbiddenAccess);
+	}
+	let Some(contact) = ClientContact::of_client(&mut db, client_id, id)
+		.await
+		.optional()?
+	else {
+		return Ok(Json(0));
+	};
+	Ok(Json(
+		ClientContact::delete(&mut db, user.id, contact.id).await?,
+	))
+}
+
+pub fn routes(settings: &OpenApiSettings) -> (Vec<Route>, OpenApi) {
+	openapi_get_routes_spec![settings: get_all, create, get, update, delete]
+}
+
+#[cfg(test)]
+mod test {
+	use pretty_assertions::assert_eq;
+	use rocket::{
+		http::Status,
+		serde::json::to_string,
+	};
+
+	use crate::{
+		db::{
+			client::{
+				Client,
+				UpdateClient,
+			},
+			client_contact::{
+				ClientContact,
+				UpdateClientContact,
+			},
+		},
+		test::{
+			generate_client,
+			generate_client_contact,
+			methods::{
+				delete,
+				get,
+				patch,
+				post,
+			},
+			TestApp,
+		},
+	};
+
+	#[tracing_test::traced_test]
+	#[test]
+	fn client_contacts() {
+		let app = TestApp::new();
+		let token = app.admin_token();
+		let user_token = app.user_token();
+
+		let mut create_client = generate_client();
+		create_client.currency = "euro".to_string();
+		let res = post(
+			&app.client,
+			"/client",
+			to_string(&create_client).unwrap(),
+			token,
+		);
+		assert_eq!(res.status(), Status::BadRequest);
+		create_client.currency = "CHF".to_string();
+		let client = post(
+			&app.client,
+			"/client",
+			to_string(&create_client).unwrap(),
+			token,
+		)
+		.into_json::<Client>()
+		.unwrap();
+		assert_eq!(client.currency, "CHF");
+
+		let client_url = format!("/client/{}", client.id);
+		let update_client = UpdateClient {
+			payment_terms: Some(-1),
+			..Default::default()
+		};
+		let res = patch(
+			&app.client,
+			&client_url,
+			to_string(&update_client).unwrap(),
+			token,
+		);
+		assert_eq!(res.status(), Status::BadRequest);
+
+		let contacts_url = format!("/client/{}/contacts", client.id);
+		let contact = generate_client_contact();
+		let res = post(
+			&app.client,
+			&contacts_url,
+			to_string(&contact).unwrap(),
+			user_token,
+		);
+		assert_eq!(res.status(), Status::Forbidden);
+		let res = post(
+			&app.client,
+			&contacts_url,
+			to_string(&contact).unwrap(),
+			token,
+		);
+		assert_eq!(res.status(), Status::Ok);
+		let inserted = res.into_json::<ClientContact>().unwrap();
+		assert_eq!(inserted.client_id, client.id);
+		assert_eq!(inserted.name, contact.name);
+
+		let res = get(&app.client, &contacts_url, user_token);
+		assert_eq!(res.status(), Status::Ok);
+		let contacts = res.into_json::<Vec<ClientContact>>().unwrap();
+		assert_eq!(contacts.len(), 1);
+		assert_eq!(contacts[0], inserted);
+
+		let contact_url = format!("{contacts_url}/{}", inserted.id);
+		let update_contact = UpdateClientContact {
+			email: Some(Some("no mail".to_string())),
+			..Default::default()
+		};
+		let res = patch(
+			&app.client,
+			&contact_url,
+			to_string(&update_contact).unwrap(),
+			token,
+		);
+		assert_eq!(res.status(), Status::BadRequest);
+		let update_contact = UpdateClientContact {
+			position: Some(Some("Accounting".to_string())),
+			..Default::default()
+		};
+		let res = patch(
+			&app.client,
+			&contact_url,
+			to_string(&update_contact).unwrap(),
+			token,
+		);
+		assert_eq!(res.status(), Status::Ok);
+		let updated = res.into_json::<ClientContact>().unwrap();
+		assert_eq!(updated.position.as_deref(), Some("Accounting"));
+
+		// contacts are only reachable through their own client
+		let foreign_url = format!("/client/{}/contacts/{}", client.id + 1, inserted.id);
+		let res = get(&app.client, &foreign_url, token);
+		assert_eq!(res.status(), Status::NotFound);
+
+		let res = delete(&app.client, &contact_url, token);
+		assert_eq!(res.into_json::<usize>().unwrap(), 1);
+		let res = get(&app.client, &contact_url, token);
+		assert_eq!(res.status(), Status::NotFound);
+	}
+}
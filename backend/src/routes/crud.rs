@@ -0,0 +1,97 @@
+/// Generates the handlers entity routes share, on top of the functions of [`crud!`]
+///
+/// `read` adds `get`, `get_page` and `get_last_page` for entities paginated without
+/// further parameters, `delete` adds the admin only `delete`, `restore` and `purge` of soft
+/// deleted entities, a purge fails as long as `references` of the entity isn't empty.
+/// The handlers are tagged with `name` in the OpenAPI document and still need to be listed in
+/// `openapi_get_routes_spec!`, the route attributes `get`, `post` and `delete` of rocket have to be
+/// in scope.
+///
+/// ```ignore
+/// crud_routes!(Activity, "Activity", read, delete);
+/// ```
+macro_rules! crud_routes {
+	($struct:ident, $name:literal, $($group:ident),+ $(,)?) => {
+		$(crud_routes!(@$group $struct, $name);)+
+	};
+	(@read $struct:ident, $name:literal) => {
+		#[rocket_okapi::openapi(tag = $name)]
+		#[get("/<id>")]
+		async fn get(
+			_user: $crate::User,
+			mut db: rocket_db_pools::Connection<$crate::DB>,
+			id: i32,
+		) -> $crate::Result<rocket::serde::json::Json<$struct>> {
+			Ok(rocket::serde::json::Json($struct::read(&mut db, id).await?))
+		}
+
+		#[rocket_okapi::openapi(tag = $name)]
+		#[get("/page/<page_size>/<page>?<list..>")]
+		async fn get_page(
+			_user: $crate::User,
+			mut db: rocket_db_pools::Connection<$crate::DB>,
+			page_size: i64,
+			page: i64,
+			list: $crate::db::ListQuery,
+		) -> $crate::Result<rocket::serde::json::Json<$crate::db::PaginationResult<$struct>>> {
+			Ok(rocket::serde::json::Json(
+				$struct::paginate(&mut db, page, page_size, &list).await?,
+			))
+		}
+
+		#[rocket_okapi::openapi(tag = $name)]
+		#[get("/page/<page_size>/last?<list..>", rank = 2)]
+		async fn get_last_page(
+			_user: $crate::User,
+			mut db: rocket_db_pools::Connection<$crate::DB>,
+			page_size: i64,
+			list: $crate::db::ListQuery,
+		) -> $crate::Result<rocket::serde::json::Json<$crate::db::PaginationResult<$struct>>> {
+			let last_page = $struct::last_page(&mut db, page_size, &list).await?;
+			Ok(rocket::serde::json::Json(
+				$struct::paginate(&mut db, last_page, page_size, &list).await?,
+			))
+		}
+	};
+	(@delete $struct:ident, $name:literal) => {
+		#[rocket_okapi::openapi(tag = $name)]
+		#[delete("/<id>")]
+		async fn delete(
+			user: $crate::User,
+			mut db: rocket_db_pools::Connection<$crate::DB>,
+			id: i32,
+		) -> $crate::Result<rocket::serde::json::Json<usize>> {
+			$crate::routes::ensure_admin(&user)?;
+			Ok(rocket::serde::json::Json(
+				$struct::delete(&mut db, user.id, id).await?,
+			))
+		}
+
+		#[rocket_okapi::openapi(tag = $name)]
+		#[post("/<id>/restore")]
+		async fn restore(
+			user: $crate::User,
+			mut db: rocket_db_pools::Connection<$crate::DB>,
+			id: i32,
+		) -> $crate::Result<rocket::serde::json::Json<$struct>> {
+			$crate::routes::ensure_admin(&user)?;
+			Ok(rocket::serde::json::Json(
+				$struct::restore(&mut db, user.id, id).await?,
+			))
+		}
+
+		#[rocket_okapi::openapi(tag = $name)]
+		#[delete("/<id>/purge")]
+		async fn purge(
+			user: $crate::User,
+			mut db: rocket_db_pools::Connection<$crate::DB>,
+			id: i32,
+		) -> $crate::Result<rocket::serde::json::Json<usize>> {
+			$crate::routes::ensure_admin(&user)?;
+			$crate::routes::ensure_unreferenced(&$name.to_lowercase(), $struct::references(&mut db, id).await?)?;
+			Ok(rocket::serde::json::Json(
+				$struct::purge(&mut db, user.id, id).await?,
+			))
+		}
+	};
+}
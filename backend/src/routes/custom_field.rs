@@ -0,0 +1,397 @@
+use std::collections::HashMap;
+
+use rocket::{
+	delete,
+	get,
+	patch,
+	post,
+	serde::json::Json,
+	Route,
+};
+use rocket_db_pools::Connection;
+use rocket_okapi::{
+	okapi::openapi3::OpenApi,
+	openapi,
+	openapi_get_routes_spec,
+	settings::OpenApiSettings,
+};
+
+use crate::{
+	db::custom_field::{
+		check_definition,
+		CreateCustomField,
+		CustomField,
+		CustomFieldReport,
+		UpdateCustomField,
+	},
+	routes::{
+		ensure_admin,
+		parse_period,
+	},
+	Result,
+	User,
+	DB,
+};
+
+#[openapi(tag = "Custom Field")]
+#[post("/", data = "<create_field>")]
+async fn create(
+	user: User,
+	mut db: Connection<DB>,
+	create_field: Json<CreateCustomField>,
+) -> Result<Json<CustomField>> {
+	ensure_admin(&user)?;
+	check_definition(
+		&create_field.entity,
+		&create_field.field_type,
+		&create_field.options,
+	)?;
+	let field = CustomField::create(&mut db, user.id, &create_field).await?;
+	Ok(Json(field))
+}
+
+/// All fields of `client`, `project` or `tracking`
+#[openapi(tag = "Custom Field")]
+#[get("/?<entity>")]
+async fn get_all(
+	_user: User,
+	mut db: Connection<DB>,
+	entity: &str,
+) -> Result<Json<Vec<CustomField>>> {
+	Ok(Json(CustomField::all(&mut db, entity).await?))
+}
+
+#[openapi(tag = "Custom Field")]
+#[get("/<id>")]
+async fn get(_user: User, mut db: Connection<DB>, id: i32) -> Result<Json<CustomField>> {
+	Ok(Json(CustomField::read(&mut db, id).await?))
+}
+
+#[openapi(tag = "Custom Field")]
+#[patch("/<id>", data = "<update_field>")]
+async fn update(
+	user: User,
+	mut db: Connection<DB>,
+	id: i32,
+	update_field: Json<UpdateCustomField>,
+) -> Result<Json<CustomField>> {
+	ensure_admin(&user)?;
+	let field = CustomField::read(&mut db, id).await?;
+	check_definition(
+		&field.entity,
+		&field.field_type,
+		update_field.options.as_ref().unwrap_or(&field.options),
+	)?;
+	Ok(Json(
+		CustomField::update(&mut db, user.id, id, &update_field).await?,
+	))
+}
+
+/// Deletes the field together with every value stored for it
+#[openapi(tag = "Custom Field")]
+#[delete("/<id>")]
+async fn delete(user: User, mut db: Connection<DB>, id: i32) -> Result<Json<usize>> {
+	ensure_admin(&user)?;
+	Ok(Json(CustomField::delete(&mut db, user.id, id).await?))
+}
+
+/// Hours grouped by the value of the field `group_by`, dates are formatted as `YYYY-MM-DD`
+///
+/// Entries can be narrowed down with `filter.<field id>=<value>`, e.g. `filter.3=4711`
+#[openapi(tag = "Custom Field")]
+#[get("/report?<from>&<to>&<group_by>&<filter>")]
+async fn get_report(
+	user: User,
+	mut db: Connection<DB>,
+	from: &str,
+	to: &str,
+	group_by: i32,
+	filter: HashMap<i32, String>,
+) -> Result<Json<Vec<CustomFieldReport>>> {
+	ensure_admin(&user)?;
+	let (from, to) = parse_period(from, to)?;
+	Ok(Json(
+		CustomField::report(&mut db, group_by, &filter, from, to).await?,
+	))
+}
+
+pub fn routes(settings: &OpenApiSettings) -> (Vec<Route>, OpenApi) {
+	openapi_get_routes_spec![settings: create, get_all, get, update, delete, get_report]
+}
+
+#[cfg(test)]
+mod test {
+	use pretty_assertions::assert_eq;
+	use rocket::{
+		http::Status,
+		serde::json::{
+			json,
+			to_string,
+		},
+	};
+
+	use crate::{
+		db::{
+			client::Client,
+			custom_field::{
+				CreateCustomField,
+				CustomField,
+				CustomFieldReport,
+				WithCustomFields,
+				ENTITY_PROJECT,
+				ENTITY_TRACKING,
+				TYPE_NUMBER,
+				TYPE_SELECT,
+			},
+			project::Project,
+			project_member::{
+				CreateProjectMember,
+				ROLE_MEMBER,
+			},
+			tracking::{
+				CreateTracking,
+				Tracking,
+			},
+			user::User,
+			PaginationResult,
+		},
+		test::{
+			generate_client,
+			generate_project,
+			generate_user,
+			methods::{
+				delete,
+				get,
+				post,
+			},
+			TestApp,
+		},
+	};
+
+	#[tracing_test::traced_test]
+	#[test]
+	fn custom_field_report() {
+		let app = TestApp::new();
+		let token = app.admin_token();
+
+		let client_db = post(
+			&app.client,
+			"/client",
+			to_string(&generate_client()).unwrap(),
+			token,
+		)
+		.into_json::<Client>()
+		.unwrap();
+
+		let cost_center = CreateCustomField {
+			entity: ENTITY_PROJECT.to_string(),
+			name: format!("Cost center {}", client_db.id),
+			field_type: TYPE_SELECT.to_string(),
+			options: to_string(&["Sales", "Support"]).unwrap(),
+			required: false,
+		};
+		let res = post(
+			&app.client,
+			"/custom_field",
+			to_string(&cost_center).unwrap(),
+			app.user_token(),
+		);
+		assert_eq!(res.status(), Status::Forbidden);
+		let res = post(
+			&app.client,
+			"/custom_field",
+			to_string(&cost_center).unwrap(),
+			token,
+		);
+		assert_eq!(res.status(), Status::Ok);
+		let cost_center = res.into_json::<CustomField>().unwrap();
+		assert_eq!(cost_center.option_list(), ["Sales", "Support"]);
+
+		let ticket = CreateCustomField {
+			entity: ENTITY_TRACKING.to_string(),
+			name: format!("Ticket {}", client_db.id),
+			field_type: TYPE_NUMBER.to_string(),
+			options: "[]".to_string(),
+			required: false,
+		};
+		let ticket = post(
+			&app.client,
+			"/custom_field",
+			to_string(&ticket).unwrap(),
+			token,
+		)
+		.into_json::<CustomField>()
+		.unwrap();
+
+		let mut project = WithCustomFields {
+			item: generate_project(),
+			custom_fields: [(cost_center.name.clone(), json!("Marketing"))].into(),
+		};
+		project.item.client_id = client_db.id;
+		let res = post(&app.client, "/project", to_string(&project).unwrap(), token);
+		assert_eq!(res.status(), Status::BadRequest);
+		project.custom_fields = [(cost_center.name.clone(), json!("Support"))].into();
+		let res = post(&app.client, "/project", to_string(&project).unwrap(), token);
+		assert_eq!(res.status(), Status::Ok);
+		let project = res.into_json::<WithCustomFields<Project>>().unwrap();
+		assert_eq!(project.custom_fields[&cost_center.name], "Support");
+
+		let user = post(
+			&app.client,
+			"/user",
+			to_string(&generate_user()).unwrap(),
+			token,
+		)
+		.into_json::<User>()
+		.unwrap();
+		let project_member = CreateProjectMember {
+			project_id: project.id,
+			user_id: user.id,
+			role: ROLE_MEMBER.to_string(),
+		};
+		let project_member_url = format!("/project/{}/member", project.id);
+		post(
+			&app.client,
+			&project_member_url,
+			to_string(&project_member).unwrap(),
+			token,
+		);
+		let mut tracking = CreateTracking {
+			client_id: client_db.id,
+			user_id: user.id,
+			project_id: project.id,
+			date: chrono::NaiveDate::from_ymd_opt(2024, 3, 11).unwrap(),
+			begin: chrono::NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+			end: chrono::NaiveTime::from_hms_opt(11, 0, 0).unwrap(),
+			pause: None,
+			performed: 2.0,
+			billed: 1.5,
+			description: None,
+			activities: vec![],
+			tags: vec![],
+			custom_fields: [("Unknown".to_string(), json!(1))].into(),
+		};
+		let res = post(
+			&app.client,
+			"/tracking",
+			to_string(&tracking).unwrap(),
+			token,
+		);
+		assert_eq!(res.status(), Status::BadRequest);
+		tracking.custom_fields = [(ticket.name.clone(), json!("4711"))].into();
+		let res = post(
+			&app.client,
+			"/tracking",
+			to_string(&tracking).unwrap(),
+			token,
+		);
+		assert_eq!(res.status(), Status::BadRequest);
+		tracking.custom_fields = [(ticket.name.clone(), json!(4711))].into();
+		let res = post(
+			&app.client,
+			"/tracking",
+			to_string(&tracking).unwrap(),
+			token,
+		);
+		assert_eq!(res.status(), Status::Ok);
+		let inserted = res.into_json::<Tracking>().unwrap();
+		assert_eq!(inserted, tracking);
+		// stored like `4711`, so both land in the same group
+		let mut decimal = tracking.clone();
+		decimal.custom_fields = [(ticket.name.clone(), json!(4711.0))].into();
+		let res = post(
+			&app.client,
+			"/tracking",
+			to_string(&decimal).unwrap(),
+			token,
+		);
+		assert_eq!(res.status(), Status::Ok);
+		let decimal = res.into_json::<Tracking>().unwrap();
+		assert_eq!(decimal.custom_fields[&ticket.name], json!(4711));
+		// an entry without a ticket and one outside of the period
+		let mut other = tracking.clone();
+		other.custom_fields = Default::default();
+		other.billed = 2.0;
+		let res = post(&app.client, "/tracking", to_string(&other).unwrap(), token);
+		assert_eq!(res.status(), Status::Ok);
+		other.date = chrono::NaiveDate::from_ymd_opt(2024, 4, 1).unwrap();
+		let res = post(&app.client, "/tracking", to_string(&other).unwrap(), token);
+		assert_eq!(res.status(), Status::Ok);
+
+		let report_url = format!(
+			"/custom_field/report?from=2024-03-01&to=2024-03-31&group_by={}&filter.{}=Support",
+			ticket.id, cost_center.id
+		);
+		let res = get(&app.client, &report_url, token);
+		assert_eq!(res.status(), Status::Ok);
+		assert_eq!(
+			res.into_json::<Vec<CustomFieldReport>>().unwrap(),
+			[
+				CustomFieldReport {
+					value: None,
+					entries: 1,
+					performed: 2.0,
+					billed: 2.0,
+				},
+				CustomFieldReport {
+					value: Some(json!(4711)),
+					entries: 2,
+					performed: 4.0,
+					billed: 3.0,
+				}
+			]
+		);
+
+		// numbers match by value
+		let report_url = format!(
+			"/custom_field/report?from=2024-03-01&to=2024-03-31&group_by={}&filter.{}=4711.0",
+			cost_center.id, ticket.id
+		);
+		let res = get(&app.client, &report_url, token);
+		assert_eq!(res.status(), Status::Ok);
+		assert_eq!(
+			res.into_json::<Vec<CustomFieldReport>>().unwrap(),
+			[CustomFieldReport {
+				value: Some(json!("Support")),
+				entries: 2,
+				performed: 4.0,
+				billed: 3.0,
+			}]
+		);
+
+		// lists are filtered by custom fields as well
+		let project_url = format!(
+			"/project/page/10/0?filter[custom_field_{}]=Support",
+			cost_center.id
+		);
+		let projects = get(&app.client, &project_url, token)
+			.into_json::<PaginationResult<WithCustomFields<Project>>>()
+			.unwrap();
+		assert_eq!(projects.items, [project]);
+		let tracking_url = format!(
+			"/tracking/page/10/0?filter[custom_field_{}]=4711.0",
+			ticket.id
+		);
+		let trackings = get(&app.client, &tracking_url, token)
+			.into_json::<PaginationResult<Tracking>>()
+			.unwrap();
+		let ids = trackings.items.iter().map(|tracking| tracking.id);
+		assert_eq!(ids.collect::<Vec<_>>(), [inserted.id, decimal.id]);
+		// a field of another entity matches nothing
+		let project_url = format!("/project/page/10/0?filter[custom_field_{}]=4711", ticket.id);
+		let projects = get(&app.client, &project_url, token)
+			.into_json::<PaginationResult<WithCustomFields<Project>>>()
+			.unwrap();
+		assert_eq!(projects.total_items, 0);
+
+		// deleting the field removes it from every entry
+		let field_url = format!("/custom_field/{}", ticket.id);
+		let res = delete(&app.client, &field_url, token);
+		assert_eq!(res.into_json::<usize>().unwrap(), 1);
+		let tracking_url = format!("/tracking/{}", inserted.id);
+		let tracking = get(&app.client, &tracking_url, token)
+			.into_json::<Tracking>()
+			.unwrap();
+		assert!(tracking.custom_fields.is_empty());
+	}
+}
@@ -0,0 +1,136 @@
+use rocket::{
+	get,
+	http::Status,
+	serde::json::Json,
+	Route,
+};
+use rocket_db_pools::Connection;
+use rocket_okapi::{
+	okapi::openapi3::OpenApi,
+	openapi,
+	openapi_get_routes_spec,
+	settings::OpenApiSettings,
+};
+use schemars::JsonSchema;
+use serde::{
+	Deserialize,
+	Serialize,
+};
+
+use crate::{
+	db::pending_migrations,
+	DB,
+};
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[typeshare::typeshare]
+pub struct Health {
+	pub status: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[typeshare::typeshare]
+pub struct Readiness {
+	/// Whether requests can be served, `database` is reachable and nothing is pending
+	pub ready: bool,
+	pub database: bool,
+	pub pending_migrations: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[typeshare::typeshare]
+pub struct Version {
+	/// Version of the backend crate
+	pub version: String,
+	/// Short hash of the commit the backend was built from, `unknown` without git
+	pub commit: String,
+	/// Migrations that are not applied yet, missing if the database can't be reached
+	pub pending_migrations: Option<Vec<String>>,
+}
+
+/// Pending migrations, `None` if the database can't be queried
+async fn pending(db: Option<Connection<DB>>) -> Option<Vec<String>> {
+	pending_migrations(&mut db?).await.ok()
+}
+
+/// The process is alive, nothing else is checked
+#[openapi(tag = "Health")]
+#[get("/health")]
+fn health() -> Json<Health> {
+	Json(Health {
+		status: "ok".to_string(),
+	})
+}
+
+/// The database is reachable and migrated, `503 Service Unavailable` otherwise
+#[openapi(tag = "Health")]
+#[get("/ready")]
+async fn ready(db: Option<Connection<DB>>) -> (Status, Json<Readiness>) {
+	let pending = pending(db).await;
+	let readiness = Readiness {
+		ready: pending.as_ref().is_some_and(Vec::is_empty),
+		database: pending.is_some(),
+		pending_migrations: pending.unwrap_or_default(),
+	};
+	let status = if readiness.ready {
+		Status::Ok
+	} else {
+		Status::ServiceUnavailable
+	};
+	(status, Json(readiness))
+}
+
+/// Version and commit of the running backend
+#[openapi(tag = "Health")]
+#[get("/version")]
+async fn version(db: Option<Connection<DB>>) -> Json<Version> {
+	Json(Version {
+		version: env!("CARGO_PKG_VERSION").to_string(),
+		commit: env!("GIT_COMMIT").to_string(),
+		pending_migrations: pending(db).await,
+	})
+}
+
+pub fn routes(settings: &OpenApiSettings) -> (Vec<Route>, OpenApi) {
+	openapi_get_routes_spec![settings: health, ready, version]
+}
+
+#[cfg(test)]
+mod test {
+	use pretty_assertions::assert_eq;
+	use rocket::http::Status;
+
+	use super::{
+		Health,
+		Readiness,
+		Version,
+	};
+	use crate::test::TestApp;
+
+	#[tracing_test::traced_test]
+	#[test]
+	fn probes() {
+		let app = TestApp::new();
+
+		let res = app.client.get("/health").dispatch();
+		assert_eq!(res.status(), Status::Ok);
+		assert_eq!(res.into_json::<Health>().unwrap().status, "ok");
+
+		let res = app.client.get("/ready").dispatch();
+		assert_eq!(res.status(), Status::Ok);
+		assert_eq!(
+			res.into_json::<Readiness>().unwrap(),
+			Readiness {
+				ready: true,
+				database: true,
+				pending_migrations: vec![],
+			}
+		);
+
+		let res = app.client.get("/version").dispatch();
+		assert_eq!(res.status(), Status::Ok);
+		let version = res.into_json::<Version>().unwrap();
+		assert_eq!(version.version, env!("CARGO_PKG_VERSION"));
+		assert_eq!(version.pending_migrations, Some(vec![]));
+	}
+}
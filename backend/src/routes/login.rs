@@ -1,20 +1,33 @@
+use std::net::IpAddr;
+
 use rocket::{
-	fairing::AdHoc,
 	post,
 	request::Request,
 	response::{
 		content,
 		Responder as ResponderImpl,
 	},
-	routes,
 	serde::json::{
 		to_string,
 		Json,
 	},
 	Responder,
+	Route,
 	State,
 };
 use rocket_db_pools::Connection;
+use rocket_okapi::{
+	gen::OpenApiGenerator,
+	okapi::openapi3::{
+		OpenApi,
+		Responses,
+	},
+	openapi,
+	openapi_get_routes_spec,
+	response::OpenApiResponderInner,
+	settings::OpenApiSettings,
+};
+use schemars::JsonSchema;
 use serde::{
 	Deserialize,
 	Serialize,
@@ -22,7 +35,11 @@ use serde::{
 use tracing::trace;
 
 use crate::{
-	auth::Tokenizer,
+	auth::{
+		LoginThrottle,
+		Tokenizer,
+	},
+	metrics::Metrics,
 	Error,
 	User,
 	DB,
@@ -34,7 +51,8 @@ pub struct LoginResponder {
 	inner: Token,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[typeshare::typeshare]
 pub struct Token {
 	pub token: String,
 }
@@ -45,6 +63,12 @@ impl<'r> ResponderImpl<'r, 'static> for Token {
 	}
 }
 
+impl OpenApiResponderInner for LoginResponder {
+	fn responses(gen: &mut OpenApiGenerator) -> rocket_okapi::Result<Responses> {
+		Json::<Token>::responses(gen)
+	}
+}
+
 impl LoginResponder {
 	pub fn new(inner: String) -> Self {
 		trace!("LoginResponder::new(**REDACTED TOKEN**)");
@@ -54,7 +78,7 @@ impl LoginResponder {
 	}
 }
 
-#[derive(Deserialize, Serialize)]
+#[derive(Deserialize, Serialize, JsonSchema)]
 #[typeshare::typeshare]
 pub struct Login<'r> {
 	email: &'r str,
@@ -69,29 +93,41 @@ impl<'a> Login<'a> {
 	}
 }
 
+/// Fails with `429 Too Many Requests` while the account is locked after too many wrong
+/// passwords or the client made too many attempts, see [`LoginThrottle`]
+#[openapi(tag = "Login")]
 #[post("/", data = "<login>")]
-async fn post_login<'r>(
+async fn post_login(
 	tokenizer: &State<Tokenizer>,
+	throttle: &State<LoginThrottle>,
+	metrics: &State<Metrics>,
+	ip: Option<IpAddr>,
 	mut db: Connection<DB>,
 	login: Json<Login<'_>>,
 ) -> Result<LoginResponder, Error> {
-	Ok(LoginResponder::new(tokenizer.generate(
-		User::check_credentials(&mut db, login.email, login.password).await?,
-	)?))
+	throttle.attempt(login.email, ip)?;
+	let user = User::check_credentials(&mut db, login.email, login.password).await;
+	metrics.login(user.is_ok());
+	match &user {
+		Ok(_) => throttle.success(login.email),
+		Err(Error::WrongCredentials) => throttle.failure(login.email),
+		Err(_) => {}
+	}
+	Ok(LoginResponder::new(tokenizer.generate(user?)?))
 }
 
-pub fn mount() -> AdHoc {
-	AdHoc::on_ignite("Mount Login Routes", |rocket| async {
-		rocket.mount("/login", routes![post_login])
-	})
+pub fn routes(settings: &OpenApiSettings) -> (Vec<Route>, OpenApi) {
+	openapi_get_routes_spec![settings: post_login]
 }
 
 #[cfg(test)]
 mod test {
 	use pretty_assertions::assert_eq;
 	use rocket::{
-		http::Status,
-		local::blocking::Client,
+		http::{
+			Header,
+			Status,
+		},
 		serde::json::to_string,
 	};
 
@@ -99,48 +135,102 @@ mod test {
 	use crate::{
 		auth::Tokenizer,
 		error::ErrorJson,
-		rocket,
 		test::{
-			db::{
-				cleanup_admin_user,
-				create_admin,
-			},
 			methods::post,
+			TestApp,
 		},
 	};
 
 	#[tracing_test::traced_test]
 	#[test]
 	fn login() {
-		let client = Client::tracked(rocket()).unwrap();
-		let [admin_email, admin_password] = create_admin(&client, None).unwrap();
+		let app = TestApp::new();
+		let admin = app.fixture().user().admin().create();
 		let login = super::Login {
-			email: admin_email.as_str(),
-			password: admin_password.as_str(),
+			email: admin.email.as_str(),
+			password: admin.password.as_str(),
 		};
 		let url = String::from("/login");
 		let res = post(
-			&client,
+			&app.client,
 			&url,
-			format!("email={}&password=Admin_01!", login.email),
+			format!("email={}&password={}", login.email, login.password),
 			"",
 		);
 		assert_eq!(res.status(), Status::BadRequest);
-		assert_eq!(
-			res.into_string(),
-			Some(to_string(&ErrorJson::new(400, "Bad Request")).expect("Could not serialize ErrorJson"))
-		);
+		let error = res.into_json::<ErrorJson>().unwrap();
+		assert_eq!(error.error, "Bad Request");
+		assert_eq!(error.error_code, "bad_request");
 
 		let res = post(
-			&client,
+			&app.client,
 			&url,
 			to_string(&login).expect("Could not serialize Login"),
 			"",
 		);
 		assert_eq!(res.status(), Status::Ok);
-		let tokenizer = client.rocket().state::<Tokenizer>().unwrap();
+		let tokenizer = app.client.rocket().state::<Tokenizer>().unwrap();
 		let token = res.into_json::<Token>().unwrap().token;
 		assert!(tokenizer.verify(&token).is_ok());
-		cleanup_admin_user(&client, admin_email);
+	}
+
+	#[tracing_test::traced_test]
+	#[test]
+	fn login_localized() {
+		let app = TestApp::new();
+		let admin = app.fixture().user().admin().create();
+		let login = super::Login {
+			email: admin.email.as_str(),
+			password: "wrong password",
+		};
+		let res = app
+			.client
+			.post("/login")
+			.header(Header::new("Accept-Language", "de-DE, en;q=0.5"))
+			.body(to_string(&login).expect("Could not serialize Login"))
+			.dispatch();
+		assert_eq!(res.status(), Status::Unauthorized);
+		let error = res.into_json::<ErrorJson>().unwrap();
+		assert_eq!(error.error, "Falsche Zugangsdaten");
+		assert_eq!(error.error_code, "login.wrong_credentials");
+	}
+
+	#[tracing_test::traced_test]
+	#[test]
+	fn login_lockout() {
+		let app = TestApp::configured(|figment| figment.merge(("login.max_failures", 2)));
+		let user = app.fixture().user().create();
+		let login = |email: &str, password: &str| {
+			app
+				.client
+				.post("/login")
+				.body(to_string(&super::Login::new(email, password)).unwrap())
+				.dispatch()
+		};
+
+		// unknown emails look like wrong passwords
+		let res = login("nobody@example.com", "guess");
+		assert_eq!(res.status(), Status::Unauthorized);
+		assert_eq!(
+			res.into_json::<ErrorJson>().unwrap().error_code,
+			"login.wrong_credentials"
+		);
+
+		assert_eq!(login(&user.email, "guess").status(), Status::Unauthorized);
+		assert_eq!(login(&user.email, "guess").status(), Status::Unauthorized);
+		let res = login(&user.email, &user.password);
+		assert_eq!(res.status(), Status::TooManyRequests);
+		assert_eq!(
+			res.into_json::<ErrorJson>().unwrap().error_code,
+			"login.locked"
+		);
+
+		let url = format!("/user/{}/unlock", user.id);
+		let res = post(&app.client, &url, String::new(), app.user_token());
+		assert_eq!(res.status(), Status::Forbidden);
+		let res = post(&app.client, &url, String::new(), app.admin_token());
+		assert_eq!(res.into_json::<bool>(), Some(true));
+		assert_eq!(login(&user.email, &user.password).status(), Status::Ok);
+		assert!(!logs_contain("guess"));
 	}
 }
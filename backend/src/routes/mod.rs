@@ -1,6 +1,270 @@
+use chrono::NaiveDate;
+use rocket::{
+	fairing::AdHoc,
+	Route,
+};
+use rocket_db_pools::Connection;
+use rocket_okapi::{
+	get_openapi_route,
+	okapi::{
+		merge::marge_spec_list,
+		openapi3::OpenApi,
+	},
+	settings::OpenApiSettings,
+};
+
+use crate::{
+	db::project_member::ProjectMember,
+	request_id,
+	Error,
+	Result,
+	User,
+	DB,
+};
+
+#[macro_use]
+mod crud;
+
 pub mod activity;
+pub mod audit;
+pub mod billing;
 pub mod client;
+pub mod client_contact;
+pub mod custom_field;
+pub mod health;
 pub mod login;
 pub mod project;
+pub mod project_activity;
+pub mod project_budget;
+pub mod project_member;
+pub mod tag;
+pub mod team;
 pub mod tracking;
 pub mod user;
+
+/// Routes of every module with the path they are mounted at and their OpenAPI documentation
+fn routes_and_docs(settings: &OpenApiSettings) -> Vec<(&'static str, (Vec<Route>, OpenApi))> {
+	vec![
+		("/", health::routes(settings)),
+		("/login", login::routes(settings)),
+		("/activity", activity::routes(settings)),
+		("/audit", audit::routes(settings)),
+		("/billing", billing::routes(settings)),
+		("/user", user::routes(settings)),
+		("/client", client::routes(settings)),
+		("/client", client_contact::routes(settings)),
+		("/custom_field", custom_field::routes(settings)),
+		("/project", project::routes(settings)),
+		("/project", project_activity::routes(settings)),
+		("/project", project_budget::routes(settings)),
+		("/project", project_member::routes(settings)),
+		("/tag", tag::routes(settings)),
+		("/team", team::routes(settings)),
+		("/tracking", tracking::routes(settings)),
+	]
+}
+
+/// Merges the documentation of the modules into a single document
+///
+/// Handlers of different modules share names like `get`, their operation ids are prefixed
+/// with the tag of the handler to keep them unique
+fn merge_docs(docs: &[(&str, OpenApi)]) -> OpenApi {
+	let mut openapi = marge_spec_list(docs).expect("Could not merge OpenAPI documents");
+	for item in openapi.paths.values_mut() {
+		let operations = [
+			&mut item.get,
+			&mut item.put,
+			&mut item.post,
+			&mut item.delete,
+			&mut item.patch,
+		];
+		for operation in operations.into_iter().flatten() {
+			if let (Some(id), Some(tag)) = (&operation.operation_id, operation.tags.first()) {
+				let prefix = tag.to_lowercase().replace(' ', "_");
+				operation.operation_id = Some(format!("{prefix}_{id}"));
+			}
+		}
+	}
+	openapi
+}
+
+/// Mounts the routes of every module and their OpenAPI document at `/openapi.json`
+///
+/// With the `openapi-ui` feature the document can be browsed at `/swagger-ui` and `/rapidoc`
+pub fn mount() -> AdHoc {
+	AdHoc::on_ignite("Routes", |mut rocket| async {
+		let settings = OpenApiSettings::default();
+		let mut docs = Vec::new();
+		for (path, (routes, openapi)) in routes_and_docs(&settings) {
+			rocket = rocket.mount(path, request_id::instrument(routes));
+			docs.push((path, openapi));
+		}
+		rocket = rocket.mount("/", vec![get_openapi_route(merge_docs(&docs), &settings)]);
+		#[cfg(feature = "openapi-ui")]
+		{
+			use rocket_okapi::{
+				rapidoc::{
+					make_rapidoc,
+					GeneralConfig,
+					RapiDocConfig,
+				},
+				settings::UrlObject,
+				swagger_ui::{
+					make_swagger_ui,
+					SwaggerUIConfig,
+				},
+			};
+
+			rocket = rocket
+				.mount(
+					"/swagger-ui",
+					make_swagger_ui(&SwaggerUIConfig {
+						url: "../openapi.json".to_string(),
+						..Default::default()
+					}),
+				)
+				.mount(
+					"/rapidoc",
+					make_rapidoc(&RapiDocConfig {
+						general: GeneralConfig {
+							spec_urls: vec![UrlObject::new("Zeiterfassung", "../openapi.json")],
+							..Default::default()
+						},
+						..Default::default()
+					}),
+				);
+		}
+		rocket
+	})
+}
+
+/// Fails with `purge.referenced` listing the tables that still reference a row,
+/// so a purge can explain what is blocking it
+pub(crate) fn ensure_unreferenced(entity: &str, references: Vec<(&str, i64)>) -> Result<()> {
+	if references.is_empty() {
+		return Ok(());
+	}
+	let references = references
+		.into_iter()
+		.map(|(table, count)| (count, table.to_string()))
+		.collect();
+	Err(
+		Error::conflict("purge.referenced")
+			.with_entity(entity)
+			.with_entities(references),
+	)
+}
+
+/// Fails with [`Error::ForbiddenAccess`] unless the user is an admin
+pub(crate) fn ensure_admin(user: &User) -> Result<()> {
+	if user.sys_role == "admin" {
+		Ok(())
+	} else {
+		Err(Error::ForbiddenAccess)
+	}
+}
+
+/// Fails with [`Error::ForbiddenAccess`] unless the user is an admin or leads the project
+pub(crate) async fn ensure_lead(
+	db: &mut Connection<DB>,
+	user: &User,
+	project_id: i32,
+) -> Result<()> {
+	if user.sys_role == "admin" || ProjectMember::is_lead(db, project_id, user.id).await? {
+		Ok(())
+	} else {
+		Err(Error::ForbiddenAccess)
+	}
+}
+
+/// Parses the `from` and `to` query parameters of period based endpoints, formatted as `YYYY-MM-DD`
+pub(crate) fn parse_period(from: &str, to: &str) -> Result<(NaiveDate, NaiveDate)> {
+	let parse = |date: &str| {
+		NaiveDate::parse_from_str(date, "%Y-%m-%d")
+			.map_err(|_| Error::bad_request("invalid_date").with_arg(date))
+	};
+	Ok((parse(from)?, parse(to)?))
+}
+
+#[cfg(test)]
+mod test {
+	use rocket::{
+		http::{
+			Header,
+			Status,
+		},
+		serde::json::{
+			serde_json,
+			Value,
+		},
+	};
+	use rocket_okapi::{
+		okapi::openapi3::OpenApi,
+		settings::OpenApiSettings,
+	};
+
+	use super::{
+		merge_docs,
+		routes_and_docs,
+	};
+	use crate::{
+		error::ErrorJson,
+		test::TestApp,
+	};
+
+	/// OpenAPI document of all routes, as it is served at `/openapi.json`
+	fn openapi() -> OpenApi {
+		let docs = routes_and_docs(&OpenApiSettings::default())
+			.into_iter()
+			.map(|(path, (_, openapi))| (path, openapi))
+			.collect::<Vec<_>>();
+		merge_docs(&docs)
+	}
+
+	/// Fails as soon as the committed `openapi.json` no longer matches the routes,
+	/// `just openapi` writes the current document
+	#[test]
+	fn openapi_up_to_date() {
+		let path = concat!(env!("CARGO_MANIFEST_DIR"), "/openapi.json");
+		let generated = serde_json::to_string_pretty(&openapi()).unwrap() + "\n";
+		if std::env::var_os("UPDATE_OPENAPI").is_some() {
+			std::fs::write(path, &generated).expect("Could not write openapi.json");
+			return;
+		}
+		let committed = std::fs::read_to_string(path).unwrap_or_default();
+		assert!(
+			committed == generated,
+			"openapi.json is outdated, run `just openapi` and commit the result"
+		);
+	}
+
+	#[tracing_test::traced_test]
+	#[test]
+	fn openapi_served() {
+		let app = TestApp::new();
+		let res = app.client.get("/openapi.json").dispatch();
+		assert_eq!(res.status(), Status::Ok);
+		assert_eq!(
+			res.into_json::<Value>().unwrap(),
+			serde_json::to_value(openapi()).unwrap()
+		);
+	}
+
+	#[tracing_test::traced_test]
+	#[test]
+	fn catcher_localized() {
+		let app = TestApp::new();
+		let res = app
+			.client
+			.get("/does-not-exist")
+			.header(Header::new("Accept-Language", "fr, de;q=0.8, en;q=0.5"))
+			.dispatch();
+		assert_eq!(res.status(), Status::NotFound);
+		let error = res.into_json::<ErrorJson>().unwrap();
+		assert_eq!(error.error, "Nicht gefunden");
+		assert_eq!(error.error_code, "not_found");
+
+		let res = app.client.get("/does-not-exist").dispatch();
+		assert_eq!(res.into_json::<ErrorJson>().unwrap().error, "Not Found");
+	}
+}
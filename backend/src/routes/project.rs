@@ -1,102 +1,181 @@
 use rocket::{
 	delete,
-	fairing::AdHoc,
 	get,
 	patch,
 	post,
-	routes,
 	serde::json::Json,
+	Route,
 };
 use rocket_db_pools::Connection;
+use rocket_okapi::{
+	okapi::openapi3::OpenApi,
+	openapi,
+	openapi_get_routes_spec,
+	settings::OpenApiSettings,
+};
 
 use crate::{
 	db::{
+		custom_field::{
+			CustomField,
+			WithCustomFields,
+			ENTITY_PROJECT,
+		},
 		project::{
 			CreateProject,
 			Project,
 			UpdateProject,
 		},
+		ListQuery,
 		PaginationResult,
 	},
+	routes::ensure_admin,
 	Error,
 	Result,
 	User,
 	DB,
 };
 
+#[openapi(tag = "Project")]
 #[post("/", data = "<create_project>")]
 async fn create(
 	user: User,
 	mut db: Connection<DB>,
-	create_project: Json<CreateProject>,
-) -> Result<Json<Project>> {
-	if user.sys_role != "admin" {
-		return Err(Error::ForbiddenAccess);
-	}
-	let project = Project::create(&mut db, &create_project).await;
-	if let Ok(project) = project {
-		Ok(Json(project))
-	} else {
-		Err(Error::BadRequest("Project already exists".to_string()))
-	}
+	create_project: Json<WithCustomFields<CreateProject>>,
+) -> Result<Json<WithCustomFields<Project>>> {
+	ensure_admin(&user)?;
+	CustomField::validate(&mut db, ENTITY_PROJECT, &create_project.custom_fields, true).await?;
+	let project = Project::create(&mut db, user.id, &create_project.item).await?;
+	let custom_fields = CustomField::set_values(
+		&mut db,
+		user.id,
+		ENTITY_PROJECT,
+		project.id,
+		&create_project.custom_fields,
+	)
+	.await?;
+	Ok(Json(WithCustomFields {
+		item: project,
+		custom_fields,
+	}))
 }
 
+#[openapi(tag = "Project")]
 #[patch("/<id>", data = "<update_project>")]
 async fn update(
-	_user: User,
+	user: User,
 	mut db: Connection<DB>,
-	update_project: Json<UpdateProject>,
+	update_project: Json<WithCustomFields<UpdateProject>>,
 	id: i32,
-) -> Result<Json<Project>> {
-	Ok(Json(Project::update(&mut db, id, &update_project).await?))
+) -> Result<Json<WithCustomFields<Project>>> {
+	ensure_admin(&user)?;
+	CustomField::validate(
+		&mut db,
+		ENTITY_PROJECT,
+		&update_project.custom_fields,
+		false,
+	)
+	.await?;
+	let project = Project::update(&mut db, user.id, id, &update_project.item).await?;
+	let custom_fields = CustomField::set_values(
+		&mut db,
+		user.id,
+		ENTITY_PROJECT,
+		id,
+		&update_project.custom_fields,
+	)
+	.await?;
+	Ok(Json(WithCustomFields {
+		item: project,
+		custom_fields,
+	}))
 }
 
+#[openapi(tag = "Project")]
 #[get("/<id>")]
-async fn get(_user: User, mut db: Connection<DB>, id: i32) -> Result<Json<Project>> {
-	Ok(Json(Project::read(&mut db, id).await?))
+async fn get(
+	_user: User,
+	mut db: Connection<DB>,
+	id: i32,
+) -> Result<Json<WithCustomFields<Project>>> {
+	let project = Project::read(&mut db, id).await?;
+	Ok(Json(
+		CustomField::inline(&mut db, ENTITY_PROJECT, id, project).await?,
+	))
 }
 
-#[get("/page/<page_size>/<page>")]
+#[openapi(tag = "Project")]
+#[get("/page/<page_size>/<page>?<include_archived>&<list..>")]
 async fn get_page(
 	_user: User,
 	mut db: Connection<DB>,
 	page_size: i64,
 	page: i64,
-) -> Result<Json<PaginationResult<Project>>> {
-	Ok(Json(Project::paginate(&mut db, page, page_size).await?))
+	include_archived: Option<bool>,
+	list: ListQuery,
+) -> Result<Json<PaginationResult<WithCustomFields<Project>>>> {
+	let page = Project::paginate(
+		&mut db,
+		page,
+		page_size,
+		include_archived.unwrap_or_default(),
+		&list,
+	)
+	.await?;
+	Ok(Json(
+		CustomField::inline_page(&mut db, ENTITY_PROJECT, page, |project| project.id).await?,
+	))
 }
 
-#[get("/page/<page_size>/last", rank = 2)]
+#[openapi(tag = "Project")]
+#[get("/page/<page_size>/last?<include_archived>&<list..>", rank = 2)]
 async fn get_last_page(
 	_user: User,
 	mut db: Connection<DB>,
 	page_size: i64,
-) -> Result<Json<PaginationResult<Project>>> {
-	let last_page = Project::last_page(&mut db, page_size).await?;
+	include_archived: Option<bool>,
+	list: ListQuery,
+) -> Result<Json<PaginationResult<WithCustomFields<Project>>>> {
+	let include_archived = include_archived.unwrap_or_default();
+	let last_page = Project::last_page(&mut db, page_size, include_archived, &list).await?;
+	let page = Project::paginate(&mut db, last_page, page_size, include_archived, &list).await?;
 	Ok(Json(
-		Project::paginate(&mut db, last_page, page_size).await?,
+		CustomField::inline_page(&mut db, ENTITY_PROJECT, page, |project| project.id).await?,
 	))
 }
 
-#[delete("/<id>")]
-async fn delete(user: User, mut db: Connection<DB>, id: i32) -> Result<Json<usize>> {
-	if user.sys_role != "admin" {
-		return Err(Error::ForbiddenAccess);
+/// Moves every reference over to `target` and soft deletes this project
+#[openapi(tag = "Project")]
+#[post("/<id>/merge_into/<target>")]
+async fn merge_into(
+	user: User,
+	mut db: Connection<DB>,
+	id: i32,
+	target: i32,
+) -> Result<Json<Project>> {
+	ensure_admin(&user)?;
+	if id == target {
+		return Err(Error::bad_request("merge.into_itself").with_entity("project"));
+	}
+	let source = Project::read(&mut db, id).await?;
+	let target_project = Project::read(&mut db, target).await?;
+	if source.client_id != target_project.client_id {
+		return Err(Error::bad_request("merge.different_clients"));
 	}
-	Ok(Json(Project::delete(&mut db, id).await?))
+	Ok(Json(
+		Project::merge_into(&mut db, user.id, id, target).await?,
+	))
 }
 
-pub fn mount() -> AdHoc {
-	AdHoc::on_ignite("Project", |rocket| async {
-		rocket.mount(
-			"/project",
-			routes![create, get, update, delete, get_page, get_last_page,],
-		)
-	})
+crud_routes!(Project, "Project", delete);
+
+pub fn routes(settings: &OpenApiSettings) -> (Vec<Route>, OpenApi) {
+	openapi_get_routes_spec![settings: create, get, update, delete, get_page, get_last_page, restore, purge, merge_into]
 }
 
 #[cfg(test)]
 mod test {
+	use bigdecimal::BigDecimal;
 	use fake::{
 		faker::lorem::en::Word,
 		Fake,
@@ -107,20 +186,30 @@ mod test {
 	};
 	use rocket::{
 		http::Status,
-		local::blocking::Client as LocalClient,
 		serde::json::to_string,
 	};
 
 	use crate::{
 		db::{
+			audit::{
+				Action,
+				Audit,
+			},
 			client::Client,
 			project::{
 				Project,
 				UpdateProject,
 			},
+			project_budget::{
+				CreateProjectBudget,
+				Decimal,
+				ProjectBudget,
+				UNIT_HOURS,
+			},
+			project_member::ProjectMember,
+			tracking::Tracking,
 			PaginationResult,
 		},
-		rocket,
 		test::{
 			generate_client,
 			generate_project,
@@ -130,23 +219,20 @@ mod test {
 				patch,
 				post,
 			},
-			token::{
-				get_token_admin,
-				get_token_user,
-			},
+			TestApp,
 		},
 	};
 
 	#[tracing_test::traced_test]
 	#[test]
 	fn project_single() {
-		let client = LocalClient::tracked(rocket()).unwrap();
+		let app = TestApp::new();
 		let mut project = generate_project();
-		let token = get_token_admin(&client);
+		let token = app.admin_token();
 		// Create new client for project
 		let project_client = generate_client();
 		let res = post(
-			&client,
+			&app.client,
 			"/client",
 			to_string(&project_client).unwrap(),
 			token,
@@ -156,11 +242,11 @@ mod test {
 		let base_url = String::from("/project");
 
 		// Test unauthorized
-		let res = post(&client, &base_url, to_string(&project).unwrap(), "");
+		let res = post(&app.client, &base_url, to_string(&project).unwrap(), "");
 		assert_eq!(res.status(), Status::Unauthorized);
 
 		// Create new project
-		let res = post(&client, &base_url, to_string(&project).unwrap(), token);
+		let res = post(&app.client, &base_url, to_string(&project).unwrap(), token);
 		let inserted_project = res.into_json::<Project>().unwrap();
 		assert_eq!(inserted_project, project);
 		let project_id = inserted_project.id;
@@ -172,39 +258,58 @@ mod test {
 			..Default::default()
 		};
 		let url = format!("{base_url}/{}", project_id);
-		let res = patch(&client, &url, to_string(&update_project).unwrap(), token);
+		let res = patch(
+			&app.client,
+			&url,
+			to_string(&update_project).unwrap(),
+			token,
+		);
 		assert_eq!(res.status(), Status::Ok);
 		let updated_project = res.into_json::<Project>().unwrap();
 		assert_eq!(updated_project.name, new_project_name);
 		assert_ne!(updated_project, inserted_project);
 
 		// Get project
-		let res = get(&client, &url, token);
+		let res = get(&app.client, &url, token);
 		assert_eq!(res.status(), Status::Ok);
 		let res = res.into_json::<Project>().unwrap();
 		assert_eq!(res, updated_project);
 
 		// Test inserting as normal user
-		let user_token = get_token_user(&client);
-		let res = post(&client, &base_url, to_string(&project).unwrap(), user_token);
+		let user_token = app.user_token();
+		let res = post(
+			&app.client,
+			&base_url,
+			to_string(&project).unwrap(),
+			user_token,
+		);
+		assert_eq!(res.status(), Status::Forbidden);
+
+		// Test updating as normal user
+		let res = patch(
+			&app.client,
+			&url,
+			to_string(&update_project).unwrap(),
+			user_token,
+		);
 		assert_eq!(res.status(), Status::Forbidden);
 
 		// delete project
-		let res = delete(&client, &url, token);
+		let res = delete(&app.client, &url, token);
 		assert_eq!(res.status(), Status::Ok);
 	}
 
 	#[tracing_test::traced_test]
 	#[test]
 	fn project_multiple() {
-		let client = LocalClient::tracked(rocket()).unwrap();
-		let token = get_token_admin(&client);
+		let app = TestApp::new();
+		let token = app.admin_token();
 		let base_url = String::from("/project");
 
 		// Create new client for project
 		let project_client = generate_client();
 		let res = post(
-			&client,
+			&app.client,
 			"/client",
 			to_string(&project_client).unwrap(),
 			token,
@@ -216,7 +321,7 @@ mod test {
 		for _ in 0..10 {
 			let mut project = generate_project();
 			project.client_id = project_client.id;
-			let res = post(&client, &base_url, to_string(&project).unwrap(), token);
+			let res = post(&app.client, &base_url, to_string(&project).unwrap(), token);
 			if res.status() != Status::Ok {
 				dbg!(res.into_string());
 				panic!();
@@ -225,13 +330,13 @@ mod test {
 		}
 
 		let url = format!("{base_url}/{}", project_list[0].id);
-		let res = get(&client, &url, token);
+		let res = get(&app.client, &url, token);
 		assert_eq!(res.status(), Status::Ok);
 		assert_eq!(res.into_json::<Project>().unwrap(), project_list[0]);
 
 		// get first page of users with page_size 5
 		let url = format!("{base_url}/page/5/0");
-		let res = get(&client, &url, token);
+		let res = get(&app.client, &url, token);
 		assert_eq!(res.status(), Status::Ok);
 		let pagination = res.into_json::<PaginationResult<Project>>().unwrap();
 		assert_eq!(pagination.page, 0);
@@ -239,7 +344,7 @@ mod test {
 
 		// get last page of users with page_size 5 with number
 		let url = format!("{base_url}/page/5/{}", pagination.num_pages - 1);
-		let res = get(&client, &url, token);
+		let res = get(&app.client, &url, token);
 		assert_eq!(res.status(), Status::Ok);
 		let pagination = res.into_json::<PaginationResult<Project>>().unwrap();
 		// the last page contains 5 OR LESS items
@@ -255,7 +360,7 @@ mod test {
 		);
 
 		let url = format!("{base_url}/page/5/last");
-		let res = get(&client, &url, token);
+		let res = get(&app.client, &url, token);
 		assert_eq!(res.status(), Status::Ok);
 		let last_page = res.into_json::<PaginationResult<Project>>().unwrap();
 		assert_eq!(last_page, pagination);
@@ -263,8 +368,176 @@ mod test {
 		// delete all projects
 		for project in project_list {
 			let url = format!("{base_url}/{}", project.id);
-			let res = delete(&client, &url, token);
+			let res = delete(&app.client, &url, token);
 			assert_eq!(res.status(), Status::Ok);
 		}
 	}
+
+	#[tracing_test::traced_test]
+	#[test]
+	fn project_archived() {
+		let app = TestApp::new();
+		let token = app.admin_token();
+		let base_url = String::from("/project");
+
+		let project_client = post(
+			&app.client,
+			"/client",
+			to_string(&generate_client()).unwrap(),
+			token,
+		)
+		.into_json::<Client>()
+		.unwrap();
+		let mut project = generate_project();
+		project.client_id = project_client.id;
+		let inserted_project = post(&app.client, &base_url, to_string(&project).unwrap(), token)
+			.into_json::<Project>()
+			.unwrap();
+		assert!(!inserted_project.archived);
+
+		let update_project = UpdateProject {
+			archived: Some(true),
+			..Default::default()
+		};
+		let url = format!("{base_url}/{}", inserted_project.id);
+		let res = patch(
+			&app.client,
+			&url,
+			to_string(&update_project).unwrap(),
+			token,
+		);
+		assert_eq!(res.status(), Status::Ok);
+		assert!(res.into_json::<Project>().unwrap().archived);
+
+		// archived projects are hidden unless asked for
+		let url = format!("{base_url}/page/5/last");
+		let res = get(&app.client, &url, token);
+		let last_page = res.into_json::<PaginationResult<Project>>().unwrap();
+		assert!(last_page
+			.items
+			.iter()
+			.all(|project| project.id != inserted_project.id));
+
+		let url = format!("{base_url}/page/5/last?include_archived=true");
+		let res = get(&app.client, &url, token);
+		let last_page = res.into_json::<PaginationResult<Project>>().unwrap();
+		assert_eq!(last_page.items.last().unwrap().id, inserted_project.id);
+	}
+
+	#[tracing_test::traced_test]
+	#[test]
+	fn project_clear_dates() {
+		let app = TestApp::new();
+		let token = app.admin_token();
+		let project = app.fixture().project().create();
+		let url = format!("/project/{}", project.id);
+
+		let end_date = chrono::NaiveDate::from_ymd_opt(2024, 12, 31).unwrap();
+		let update_project = UpdateProject {
+			end_date: Some(Some(end_date)),
+			..Default::default()
+		};
+		let res = patch(
+			&app.client,
+			&url,
+			to_string(&update_project).unwrap(),
+			token,
+		);
+		assert_eq!(res.status(), Status::Ok);
+		assert_eq!(res.into_json::<Project>().unwrap().end_date, Some(end_date));
+
+		let res = patch(
+			&app.client,
+			&url,
+			r#"{"end_date": null}"#.to_string(),
+			token,
+		);
+		assert_eq!(res.status(), Status::Ok);
+		let res = get(&app.client, &url, token);
+		assert_eq!(res.into_json::<Project>().unwrap().end_date, None);
+	}
+
+	#[tracing_test::traced_test]
+	#[test]
+	fn project_merge() {
+		let app = TestApp::new();
+		let token = app.admin_token();
+		let client = app.fixture().client().create();
+		let shared = app.fixture().user().create().user;
+		let only_source = app.fixture().user().create().user;
+		let source = app
+			.fixture()
+			.project()
+			.client(&client)
+			.member(&shared)
+			.member(&only_source)
+			.create();
+		let target = app
+			.fixture()
+			.project()
+			.client(&client)
+			.member(&shared)
+			.create();
+		let tracking = app.fixture().tracking().project(&source).create();
+		let budget = CreateProjectBudget {
+			project_id: source.id,
+			activity_id: None,
+			unit: UNIT_HOURS.to_string(),
+			amount: Decimal(BigDecimal::from(10)),
+			hourly_rate: None,
+			thresholds: Default::default(),
+			hard_stop: false,
+		};
+		let budget_url = format!("/project/{}/budget", source.id);
+		let budget = post(&app.client, &budget_url, to_string(&budget).unwrap(), token)
+			.into_json::<ProjectBudget>()
+			.unwrap();
+
+		let foreign = app.fixture().project().create();
+		let foreign_url = format!("/project/{}/merge_into/{}", source.id, foreign.id);
+		let res = post(&app.client, &foreign_url, String::new(), token);
+		assert_eq!(res.status(), Status::BadRequest);
+		let merge_url = format!("/project/{}/merge_into/{}", source.id, target.id);
+		let res = post(&app.client, &merge_url, String::new(), app.user_token());
+		assert_eq!(res.status(), Status::Forbidden);
+		let res = post(&app.client, &merge_url, String::new(), token);
+		assert_eq!(res.status(), Status::Ok);
+		assert_eq!(res.into_json::<Project>().unwrap().id, target.id);
+
+		// entries and budgets belong to the target now
+		let tracking_url = format!("/tracking/{}", tracking.id);
+		let res = get(&app.client, &tracking_url, token);
+		assert_eq!(res.into_json::<Tracking>().unwrap().project_id, target.id);
+		let budget_url = format!("/project/{}/budget", target.id);
+		let res = get(&app.client, &budget_url, token);
+		let budgets = res.into_json::<Vec<ProjectBudget>>().unwrap();
+		assert_eq!(budgets.len(), 1);
+		assert_eq!(budgets[0].id, budget.id);
+
+		// members of both projects are only added once
+		let member_url = format!("/project/{}/member", target.id);
+		let res = get(&app.client, &member_url, token);
+		let mut members = res
+			.into_json::<Vec<ProjectMember>>()
+			.unwrap()
+			.into_iter()
+			.map(|member| member.user_id)
+			.collect::<Vec<_>>();
+		members.sort();
+		let mut expected = vec![shared.id, only_source.id, tracking.user_id];
+		expected.sort();
+		assert_eq!(members, expected);
+
+		// the source is soft deleted
+		let source_url = format!("/project/{}", source.id);
+		let res = get(&app.client, &source_url, token);
+		assert_eq!(res.status(), Status::NotFound);
+
+		let audit_url = format!("/audit?entity=project&id={}", source.id);
+		let res = get(&app.client, &audit_url, token);
+		let audit = res.into_json::<PaginationResult<Audit>>().unwrap();
+		let actions: Vec<Action> = audit.items.iter().map(|entry| entry.action).collect();
+		assert_eq!(actions, [Action::Delete, Action::Merge]);
+		assert_eq!(audit.items[1].new_value.as_ref().unwrap()["id"], target.id);
+	}
 }
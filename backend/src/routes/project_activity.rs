@@ -0,0 +1,48 @@
+use rocket::{
+	get,
+	put,
+	serde::json::Json,
+	Route,
+};
+use rocket_db_pools::Connection;
+use rocket_okapi::{
+	okapi::openapi3::OpenApi,
+	openapi,
+	openapi_get_routes_spec,
+	settings::OpenApiSettings,
+};
+
+use crate::{
+	db::project_activity::ProjectActivity,
+	Error,
+	Result,
+	User,
+	DB,
+};
+
+/// Activities that may be booked on the project, empty if every activity is allowed
+#[openapi(tag = "Project Activity")]
+#[get("/<project_id>/activity")]
+async fn get(_user: User, mut db: Connection<DB>, project_id: i32) -> Result<Json<Vec<i32>>> {
+	Ok(Json(ProjectActivity::allowed(&mut db, project_id).await?))
+}
+
+/// Replaces the whitelist of the project, an empty list allows every activity
+#[openapi(tag = "Project Activity")]
+#[put("/<project_id>/activity", data = "<activity_ids>")]
+async fn set(
+	user: User,
+	mut db: Connection<DB>,
+	activity_ids: Json<Vec<i32>>,
+	project_id: i32,
+) -> Result<Json<Vec<i32>>> {
+	if user.sys_role != "admin" {
+		return Err(Error::ForbiddenAccess);
+	}
+	let allowed = ProjectActivity::set(&mut db, user.id, project_id, &activity_ids).await?;
+	Ok(Json(allowed))
+}
+
+pub fn routes(settings: &OpenApiSettings) -> (Vec<Route>, OpenApi) {
+	openapi_get_routes_spec![settings: get, set]
+}
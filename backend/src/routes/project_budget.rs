@@ -0,0 +1,343 @@
+use rocket::{
+	delete,
+	get,
+	patch,
+	post,
+	serde::json::Json,
+	Route,
+};
+use rocket_db_pools::Connection;
+use rocket_okapi::{
+	okapi::openapi3::OpenApi,
+	openapi,
+	openapi_get_routes_spec,
+	settings::OpenApiSettings,
+};
+
+use crate::{
+	db::project_budget::{
+		check_budget,
+		BudgetConsumption,
+		CreateProjectBudget,
+		ProjectBudget,
+		UpdateProjectBudget,
+	},
+	Error,
+	Result,
+	User,
+	DB,
+};
+
+#[openapi(tag = "Project Budget")]
+#[post("/<project_id>/budget", data = "<create_budget>")]
+async fn create(
+	user: User,
+	mut db: Connection<DB>,
+	mut create_budget: Json<CreateProjectBudget>,
+	project_id: i32,
+) -> Result<Json<ProjectBudget>> {
+	if user.sys_role != "admin" {
+		return Err(Error::ForbiddenAccess);
+	}
+	check_budget(
+		&create_budget.unit,
+		&create_budget.amount,
+		create_budget.hourly_rate.as_ref(),
+		&create_budget.thresholds,
+	)?;
+	create_budget.project_id = project_id;
+	let budget = ProjectBudget::create(&mut db, user.id, &create_budget).await?;
+	Ok(Json(budget))
+}
+
+#[openapi(tag = "Project Budget")]
+#[get("/<project_id>/budget")]
+async fn get_all(
+	_user: User,
+	mut db: Connection<DB>,
+	project_id: i32,
+) -> Result<Json<Vec<ProjectBudget>>> {
+	Ok(Json(
+		ProjectBudget::from_project(&mut db, project_id).await?,
+	))
+}
+
+#[openapi(tag = "Project Budget")]
+#[get("/<project_id>/budget/consumption")]
+async fn consumption(
+	_user: User,
+	mut db: Connection<DB>,
+	project_id: i32,
+) -> Result<Json<Vec<BudgetConsumption>>> {
+	Ok(Json(
+		ProjectBudget::project_consumption(&mut db, project_id, None).await?,
+	))
+}
+
+#[openapi(tag = "Project Budget")]
+#[get("/budget/alerts")]
+async fn alerts(user: User, mut db: Connection<DB>) -> Result<Json<Vec<BudgetConsumption>>> {
+	if user.sys_role != "admin" {
+		return Err(Error::ForbiddenAccess);
+	}
+	Ok(Json(ProjectBudget::alerts(&mut db).await?))
+}
+
+#[openapi(tag = "Project Budget")]
+#[patch("/<project_id>/budget/<id>", data = "<update_budget>")]
+async fn update(
+	user: User,
+	mut db: Connection<DB>,
+	update_budget: Json<UpdateProjectBudget>,
+	project_id: i32,
+	id: i32,
+) -> Result<Json<ProjectBudget>> {
+	if user.sys_role != "admin" {
+		return Err(Error::ForbiddenAccess);
+	}
+	let budget = ProjectBudget::read(&mut db, id).await?;
+	if budget.project_id != project_id {
+		return Err(Error::NotFound);
+	}
+	check_budget(
+		update_budget.unit.as_deref().unwrap_or(&budget.unit),
+		update_budget.amount.as_ref().unwrap_or(&budget.amount),
+		update_budget
+			.hourly_rate
+			.as_ref()
+			.unwrap_or(&budget.hourly_rate)
+			.as_ref(),
+		update_budget
+			.thresholds
+			.as_ref()
+			.unwrap_or(&budget.thresholds),
+	)?;
+	Ok(Json(
+		ProjectBudget::update(&mut db, user.id, id, &update_budget).await?,
+	))
+}
+
+#[openapi(tag = "Project Budget")]
+#[delete("/<project_id>/budget/<id>")]
+async fn delete(
+	user: User,
+	mut db: Connection<DB>,
+	project_id: i32,
+	id: i32,
+) -> Result<Json<usize>> {
+	if user.sys_role != "admin" {
+		return Err(Error::ForbiddenAccess);
+	}
+	let budget = ProjectBudget::read(&mut db, id).await?;
+	if budget.project_id != project_id {
+		return Err(Error::NotFound);
+	}
+	Ok(Json(ProjectBudget::delete(&mut db, user.id, id).await?))
+}
+
+pub fn routes(settings: &OpenApiSettings) -> (Vec<Route>, OpenApi) {
+	openapi_get_routes_spec![settings: create, get_all, consumption, alerts, update, delete]
+}
+
+#[cfg(test)]
+mod test {
+	use bigdecimal::BigDecimal;
+	use pretty_assertions::assert_eq;
+	use rocket::{
+		http::Status,
+		serde::json::to_string,
+	};
+
+	use crate::{
+		db::{
+			project_budget::{
+				BudgetConsumption,
+				CreateProjectBudget,
+				Decimal,
+				ProjectBudget,
+				Thresholds,
+				UpdateProjectBudget,
+				UNIT_HOURS,
+				UNIT_MONEY,
+			},
+			tracking::{
+				CreateTracking,
+				Tracking,
+				UpdateTracking,
+			},
+		},
+		error::ErrorJson,
+		test::{
+			methods::{
+				get,
+				patch,
+				post,
+			},
+			TestApp,
+		},
+	};
+
+	#[tracing_test::traced_test]
+	#[test]
+	fn budget_hard_stop() {
+		let app = TestApp::new();
+		let token = app.admin_token();
+		let user_token = app.user_token();
+
+		let user = app.fixture().user().create().user;
+		let project = app.fixture().project().member(&user).create();
+		let budget_url = format!("/project/{}/budget", project.id);
+
+		let mut budget = CreateProjectBudget {
+			project_id: project.id,
+			activity_id: None,
+			unit: "days".to_string(),
+			amount: Decimal(BigDecimal::from(1)),
+			hourly_rate: None,
+			thresholds: Thresholds(vec![80.0, 100.0]),
+			hard_stop: true,
+		};
+		let res = post(
+			&app.client,
+			&budget_url,
+			to_string(&budget).unwrap(),
+			user_token,
+		);
+		assert_eq!(res.status(), Status::Forbidden);
+		let res = post(&app.client, &budget_url, to_string(&budget).unwrap(), token);
+		assert_eq!(res.status(), Status::BadRequest);
+
+		budget.unit = UNIT_HOURS.to_string();
+		let res = post(&app.client, &budget_url, to_string(&budget).unwrap(), token);
+		assert_eq!(res.status(), Status::Ok);
+		let inserted_budget = res.into_json::<ProjectBudget>().unwrap();
+		assert_eq!(inserted_budget.project_id, project.id);
+
+		let tracking = CreateTracking {
+			client_id: project.client_id,
+			user_id: user.id,
+			project_id: project.id,
+			date: chrono::NaiveDate::from_ymd_opt(2024, 1, 29).unwrap(),
+			begin: chrono::NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+			end: chrono::NaiveTime::from_hms_opt(10, 0, 0).unwrap(),
+			pause: None,
+			performed: 1.0,
+			billed: 1.0,
+			description: None,
+			activities: vec![],
+			tags: vec![],
+			custom_fields: Default::default(),
+		};
+		let res = post(
+			&app.client,
+			"/tracking",
+			to_string(&tracking).unwrap(),
+			token,
+		);
+		assert_eq!(res.status(), Status::Ok);
+		let booked = res.into_json::<Tracking>().unwrap();
+
+		let consumption_url = format!("{budget_url}/consumption");
+		let res = get(&app.client, &consumption_url, token);
+		assert_eq!(res.status(), Status::Ok);
+		let consumption = res.into_json::<Vec<BudgetConsumption>>().unwrap();
+		assert_eq!(consumption.len(), 1);
+		assert_eq!(consumption[0].billed, 1.0);
+		assert_eq!(consumption[0].reached_thresholds, [80.0, 100.0]);
+		assert!(consumption[0].exhausted);
+
+		let res = get(&app.client, "/project/budget/alerts", token);
+		assert_eq!(res.status(), Status::Ok);
+		let alerts = res.into_json::<Vec<BudgetConsumption>>().unwrap();
+		assert!(alerts
+			.iter()
+			.any(|alert| alert.budget.id == inserted_budget.id));
+
+		let res = post(
+			&app.client,
+			"/tracking",
+			to_string(&tracking).unwrap(),
+			token,
+		);
+		assert_eq!(res.status(), Status::BadRequest);
+		let error = res.into_json::<ErrorJson>().unwrap();
+		assert_eq!(error.error, "Budget of the project is exhausted");
+		assert_eq!(error.error_code, "tracking.budget_exhausted");
+
+		// the entry that used up the budget doesn't count against itself
+		let tracking_url = format!("/tracking/{}", booked.id);
+		let update_tracking = UpdateTracking {
+			billed: Some(0.5),
+			..Default::default()
+		};
+		let res = patch(
+			&app.client,
+			&tracking_url,
+			to_string(&update_tracking).unwrap(),
+			token,
+		);
+		assert_eq!(res.status(), Status::Ok);
+		assert_eq!(res.into_json::<Tracking>().unwrap().billed, 0.5);
+	}
+
+	#[tracing_test::traced_test]
+	#[test]
+	fn budget_money() {
+		let app = TestApp::new();
+		let token = app.admin_token();
+		let project = app.fixture().project().create();
+		let tracking = app.fixture().tracking().project(&project).create();
+		let budget_url = format!("/project/{}/budget", project.id);
+
+		let budget = CreateProjectBudget {
+			project_id: project.id,
+			activity_id: Some(i32::MAX),
+			unit: UNIT_MONEY.to_string(),
+			amount: "1000.10".parse().unwrap(),
+			hourly_rate: Some("0.10".parse().unwrap()),
+			thresholds: Thresholds(vec![50.0]),
+			hard_stop: false,
+		};
+		let res = post(&app.client, &budget_url, to_string(&budget).unwrap(), token);
+		assert_eq!(res.status(), Status::BadRequest);
+		let mut budget = budget;
+		budget.activity_id = Some(tracking.activities[0]);
+		// a rate of 0 would never use up the budget
+		budget.hourly_rate = Some("0".parse().unwrap());
+		let res = post(&app.client, &budget_url, to_string(&budget).unwrap(), token);
+		assert_eq!(res.status(), Status::BadRequest);
+		let error = res.into_json::<ErrorJson>().unwrap();
+		assert_eq!(error.error_code, "budget.invalid_hourly_rate");
+		budget.hourly_rate = Some("0.10".parse().unwrap());
+		let res = post(&app.client, &budget_url, to_string(&budget).unwrap(), token);
+		assert_eq!(res.status(), Status::Ok);
+		let body = res.into_string().unwrap();
+		// money is sent as exact decimal strings
+		assert!(body.contains(r#""amount":"1000.10""#), "{body}");
+		assert!(body.contains(r#""thresholds":[50.0]"#), "{body}");
+		let inserted_budget = rocket::serde::json::from_str::<ProjectBudget>(&body).unwrap();
+		assert_eq!(inserted_budget.amount, budget.amount);
+
+		// the budget covers the whole project again
+		let url = format!("{budget_url}/{}", inserted_budget.id);
+		let update_budget = UpdateProjectBudget {
+			activity_id: Some(None),
+			..Default::default()
+		};
+		let res = patch(&app.client, &url, to_string(&update_budget).unwrap(), token);
+		assert_eq!(res.status(), Status::Ok);
+		assert_eq!(res.into_json::<ProjectBudget>().unwrap().activity_id, None);
+
+		let consumption_url = format!("{budget_url}/consumption");
+		let consumption = get(&app.client, &consumption_url, token)
+			.into_json::<Vec<BudgetConsumption>>()
+			.unwrap();
+		let billed = BigDecimal::try_from(f64::from(tracking.billed))
+			.unwrap()
+			.round(2);
+		assert_eq!(
+			consumption[0].consumed,
+			(billed * budget.hourly_rate.unwrap().0).round(2)
+		);
+	}
+}
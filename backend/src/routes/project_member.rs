@@ -0,0 +1,340 @@
+use diesel::OptionalExtension;
+use rocket::{
+	delete,
+	get,
+	patch,
+	post,
+	serde::json::Json,
+	Route,
+};
+use rocket_db_pools::Connection;
+use rocket_okapi::{
+	okapi::openapi3::OpenApi,
+	openapi,
+	openapi_get_routes_spec,
+	settings::OpenApiSettings,
+};
+
+use crate::{
+	db::{
+		project::Project,
+		project_member::{
+			is_valid_role,
+			CreateProjectMember,
+			ProjectMember,
+			UpdateProjectMember,
+			ROLE_LEAD,
+			ROLE_MEMBER,
+		},
+		tracking::Tracking,
+		PaginationResult,
+	},
+	routes::ensure_lead,
+	Error,
+	Result,
+	User,
+	DB,
+};
+
+fn check_role(role: &str) -> Result<()> {
+	if is_valid_role(role) {
+		Ok(())
+	} else {
+		Err(
+			Error::bad_request("project_member.invalid_role")
+				.with_arg(ROLE_MEMBER)
+				.with_arg(ROLE_LEAD),
+		)
+	}
+}
+
+#[openapi(tag = "Project Member")]
+#[post("/<project_id>/member", data = "<create_member>")]
+async fn create(
+	user: User,
+	mut db: Connection<DB>,
+	mut create_member: Json<CreateProjectMember>,
+	project_id: i32,
+) -> Result<Json<ProjectMember>> {
+	if user.sys_role != "admin" {
+		return Err(Error::ForbiddenAccess);
+	}
+	check_role(&create_member.role)?;
+	create_member.project_id = project_id;
+	let member = ProjectMember::create(&mut db, user.id, &create_member).await?;
+	Ok(Json(member))
+}
+
+#[openapi(tag = "Project Member")]
+#[get("/<project_id>/member")]
+async fn get_all(
+	_user: User,
+	mut db: Connection<DB>,
+	project_id: i32,
+) -> Result<Json<Vec<ProjectMember>>> {
+	Ok(Json(
+		ProjectMember::from_project(&mut db, project_id).await?,
+	))
+}
+
+#[openapi(tag = "Project Member")]
+#[patch("/<project_id>/member/<user_id>", data = "<update_member>")]
+async fn update(
+	user: User,
+	mut db: Connection<DB>,
+	update_member: Json<UpdateProjectMember>,
+	project_id: i32,
+	user_id: i32,
+) -> Result<Json<ProjectMember>> {
+	if user.sys_role != "admin" {
+		return Err(Error::ForbiddenAccess);
+	}
+	if let Some(role) = &update_member.role {
+		check_role(role)?;
+	}
+	let member = ProjectMember::find(&mut db, project_id, user_id).await?;
+	Ok(Json(
+		ProjectMember::update(&mut db, user.id, member.id, &update_member).await?,
+	))
+}
+
+#[openapi(tag = "Project Member")]
+#[delete("/<project_id>/member/<user_id>")]
+async fn delete(
+	user: User,
+	mut db: Connection<DB>,
+	project_id: i32,
+	user_id: i32,
+) -> Result<Json<usize>> {
+	if user.sys_role != "admin" {
+		return Err(Error::ForbiddenAccess);
+	}
+	let Some(member) = ProjectMember::find(&mut db, project_id, user_id)
+		.await
+		.optional()?
+	else {
+		return Ok(Json(0));
+	};
+	Ok(Json(
+		ProjectMember::delete(&mut db, user.id, member.id).await?,
+	))
+}
+
+/// Projects the user may book on, admins can ask for any user
+#[openapi(tag = "Project Member")]
+#[get("/bookable?<user_id>")]
+async fn bookable(
+	user: User,
+	mut db: Connection<DB>,
+	user_id: Option<i32>,
+) -> Result<Json<Vec<Project>>> {
+	let user_id = user_id.unwrap_or(user.id);
+	if user_id != user.id && user.sys_role != "admin" {
+		return Err(Error::ForbiddenAccess);
+	}
+	Ok(Json(
+		ProjectMember::bookable_projects(&mut db, user_id).await?,
+	))
+}
+
+/// All entries booked on the project, for admins and leads of the project
+#[openapi(tag = "Project Member")]
+#[get("/<project_id>/tracking/page/<page_size>/<page>")]
+async fn get_tracking_page(
+	user: User,
+	mut db: Connection<DB>,
+	project_id: i32,
+	page_size: i64,
+	page: i64,
+) -> Result<Json<PaginationResult<Tracking>>> {
+	ensure_lead(&mut db, &user, project_id).await?;
+	Ok(Json(
+		Tracking::paginate_project(&mut db, project_id, page, page_size).await?,
+	))
+}
+
+pub fn routes(settings: &OpenApiSettings) -> (Vec<Route>, OpenApi) {
+	openapi_get_routes_spec![settings: create, get_all, update, delete, bookable, get_tracking_page]
+}
+
+#[cfg(test)]
+mod test {
+	use pretty_assertions::assert_eq;
+	use rocket::{
+		http::Status,
+		serde::json::to_string,
+	};
+
+	use crate::{
+		db::{
+			project::Project,
+			project_member::{
+				CreateProjectMember,
+				ProjectMember,
+				ROLE_LEAD,
+				ROLE_MEMBER,
+			},
+			tracking::{
+				CreateTracking,
+				Tracking,
+			},
+			PaginationResult,
+		},
+		error::ErrorJson,
+		test::{
+			methods::{
+				get,
+				post,
+			},
+			TestApp,
+		},
+	};
+
+	#[tracing_test::traced_test]
+	#[test]
+	fn project_lead() {
+		let app = TestApp::new();
+		let token = app.admin_token();
+
+		let project = app.fixture().project().create();
+		let (member, member_token) = app.user_with_token();
+		let (lead, lead_token) = app.user_with_token();
+
+		let tracking = CreateTracking {
+			client_id: project.client_id,
+			user_id: member.id,
+			project_id: project.id,
+			date: chrono::NaiveDate::from_ymd_opt(2024, 2, 5).unwrap(),
+			begin: chrono::NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+			end: chrono::NaiveTime::from_hms_opt(10, 0, 0).unwrap(),
+			pause: None,
+			performed: 1.0,
+			billed: 1.0,
+			description: None,
+			activities: vec![],
+			tags: vec![],
+			custom_fields: Default::default(),
+		};
+		let res = post(
+			&app.client,
+			"/tracking",
+			to_string(&tracking).unwrap(),
+			token,
+		);
+		assert_eq!(res.status(), Status::BadRequest);
+		let error = res.into_json::<ErrorJson>().unwrap();
+		assert_eq!(error.error, "User is not a member of the project");
+		assert_eq!(error.error_code, "tracking.not_member");
+		assert_eq!(error.details[0].field, "user_id");
+
+		let member_url = format!("/project/{}/member", project.id);
+		let mut create_member = CreateProjectMember {
+			project_id: project.id,
+			user_id: member.id,
+			role: "owner".to_string(),
+		};
+		let res = post(
+			&app.client,
+			&member_url,
+			to_string(&create_member).unwrap(),
+			app.user_token(),
+		);
+		assert_eq!(res.status(), Status::Forbidden);
+		let res = post(
+			&app.client,
+			&member_url,
+			to_string(&create_member).unwrap(),
+			token,
+		);
+		assert_eq!(res.status(), Status::BadRequest);
+		create_member.role = ROLE_MEMBER.to_string();
+		let res = post(
+			&app.client,
+			&member_url,
+			to_string(&create_member).unwrap(),
+			token,
+		);
+		assert_eq!(res.status(), Status::Ok);
+		let res = post(
+			&app.client,
+			&member_url,
+			to_string(&create_member).unwrap(),
+			token,
+		);
+		assert_eq!(res.status(), Status::Conflict);
+		let error = res.into_json::<ErrorJson>().unwrap();
+		assert_eq!(error.error_code, "project_member.project_id_taken");
+		let missing_user = CreateProjectMember {
+			user_id: i32::MAX,
+			..create_member.clone()
+		};
+		let res = post(
+			&app.client,
+			&member_url,
+			to_string(&missing_user).unwrap(),
+			token,
+		);
+		assert_eq!(res.status(), Status::BadRequest);
+		let error = res.into_json::<ErrorJson>().unwrap();
+		#[cfg(not(feature = "sqlite"))]
+		{
+			assert_eq!(error.error_code, "fk.user_missing");
+			assert_eq!(error.details[0].field, "user_id");
+		}
+		// SQLite doesn't name the foreign key
+		#[cfg(feature = "sqlite")]
+		assert_eq!(error.error_code, "fk.violation");
+		let create_lead = CreateProjectMember {
+			project_id: project.id,
+			user_id: lead.id,
+			role: ROLE_LEAD.to_string(),
+		};
+		let res = post(
+			&app.client,
+			&member_url,
+			to_string(&create_lead).unwrap(),
+			token,
+		);
+		assert_eq!(res.status(), Status::Ok);
+		let res = get(&app.client, &member_url, &member_token);
+		assert_eq!(res.status(), Status::Ok);
+		assert_eq!(res.into_json::<Vec<ProjectMember>>().unwrap().len(), 2);
+
+		let res = post(
+			&app.client,
+			"/tracking",
+			to_string(&tracking).unwrap(),
+			token,
+		);
+		assert_eq!(res.status(), Status::Ok);
+		let inserted_tracking = res.into_json::<Tracking>().unwrap();
+		assert_eq!(inserted_tracking.approved_by, None);
+
+		let res = get(&app.client, "/project/bookable", &member_token);
+		assert_eq!(res.status(), Status::Ok);
+		let bookable = res.into_json::<Vec<Project>>().unwrap();
+		assert_eq!(bookable.len(), 1);
+		assert_eq!(bookable[0].id, project.id);
+		let other_bookable_url = format!("/project/bookable?user_id={}", lead.id);
+		let res = get(&app.client, &other_bookable_url, &member_token);
+		assert_eq!(res.status(), Status::Forbidden);
+
+		let tracking_url = format!("/project/{}/tracking/page/10/0", project.id);
+		let res = get(&app.client, &tracking_url, &member_token);
+		assert_eq!(res.status(), Status::Forbidden);
+		let res = get(&app.client, &tracking_url, &lead_token);
+		assert_eq!(res.status(), Status::Ok);
+		let page = res.into_json::<PaginationResult<Tracking>>().unwrap();
+		assert_eq!(page.total_items, 1);
+		assert_eq!(page.items[0].id, inserted_tracking.id);
+
+		let approve_url = format!("/tracking/{}/approve", inserted_tracking.id);
+		let res = post(&app.client, &approve_url, String::new(), &member_token);
+		assert_eq!(res.status(), Status::Forbidden);
+		let res = post(&app.client, &approve_url, String::new(), &lead_token);
+		assert_eq!(res.status(), Status::Ok);
+		let approved = res.into_json::<Tracking>().unwrap();
+		assert_eq!(approved.approved_by, Some(lead.id));
+		assert!(approved.approved_at.is_some());
+	}
+}
@@ -0,0 +1,193 @@
+use rocket::{
+	get,
+	serde::json::Json,
+	Route,
+};
+use rocket_db_pools::Connection;
+use rocket_okapi::{
+	okapi::openapi3::OpenApi,
+	openapi,
+	openapi_get_routes_spec,
+	settings::OpenApiSettings,
+};
+
+use crate::{
+	db::tag::{
+		Tag,
+		TagReport,
+	},
+	routes::parse_period,
+	Error,
+	Result,
+	User,
+	DB,
+};
+
+/// Names of the tags starting with `q`, at most `limit` (default 10)
+#[openapi(tag = "Tag")]
+#[get("/?<q>&<limit>")]
+async fn autocomplete(
+	_user: User,
+	mut db: Connection<DB>,
+	q: Option<&str>,
+	limit: Option<i64>,
+) -> Result<Json<Vec<String>>> {
+	Ok(Json(
+		Tag::autocomplete(&mut db, q.unwrap_or_default(), limit.unwrap_or(10)).await?,
+	))
+}
+
+/// Hours per tag, dates are formatted as `YYYY-MM-DD`
+#[openapi(tag = "Tag")]
+#[get("/report?<from>&<to>")]
+async fn get_report(
+	user: User,
+	mut db: Connection<DB>,
+	from: &str,
+	to: &str,
+) -> Result<Json<Vec<TagReport>>> {
+	if user.sys_role != "admin" {
+		return Err(Error::ForbiddenAccess);
+	}
+	let (from, to) = parse_period(from, to)?;
+	Ok(Json(Tag::report(&mut db, from, to).await?))
+}
+
+pub fn routes(settings: &OpenApiSettings) -> (Vec<Route>, OpenApi) {
+	openapi_get_routes_spec![settings: autocomplete, get_report]
+}
+
+#[cfg(test)]
+mod test {
+	use pretty_assertions::assert_eq;
+	use rocket::{
+		http::Status,
+		serde::json::to_string,
+	};
+
+	use crate::{
+		db::{
+			tag::TagReport,
+			tracking::{
+				CreateTracking,
+				Tracking,
+				UpdateTracking,
+			},
+			PaginationResult,
+		},
+		test::{
+			methods::{
+				get,
+				patch,
+				post,
+			},
+			TestApp,
+		},
+	};
+
+	#[tracing_test::traced_test]
+	#[test]
+	fn tracking_tags() {
+		let app = TestApp::new();
+		let token = app.admin_token();
+
+		let user = app.fixture().user().create().user;
+		let project = app.fixture().project().member(&user).create();
+
+		let travel = String::from("travel");
+		let mut tracking = CreateTracking {
+			client_id: project.client_id,
+			user_id: user.id,
+			project_id: project.id,
+			date: chrono::NaiveDate::from_ymd_opt(2024, 3, 18).unwrap(),
+			begin: chrono::NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+			end: chrono::NaiveTime::from_hms_opt(11, 0, 0).unwrap(),
+			pause: None,
+			performed: 2.0,
+			billed: 1.5,
+			description: None,
+			activities: vec![],
+			tags: vec![" ".to_string()],
+			custom_fields: Default::default(),
+		};
+		let res = post(
+			&app.client,
+			"/tracking",
+			to_string(&tracking).unwrap(),
+			token,
+		);
+		assert_eq!(res.status(), Status::BadRequest);
+		tracking.tags = vec![format!(" {travel}"), "remote".to_string(), travel.clone()];
+		let res = post(
+			&app.client,
+			"/tracking",
+			to_string(&tracking).unwrap(),
+			token,
+		);
+		assert_eq!(res.status(), Status::Ok);
+		let tagged = res.into_json::<Tracking>().unwrap();
+		assert_eq!(tagged.tags, ["remote", travel.as_str()]);
+
+		tracking.tags = vec![];
+		let untagged = post(
+			&app.client,
+			"/tracking",
+			to_string(&tracking).unwrap(),
+			token,
+		)
+		.into_json::<Tracking>()
+		.unwrap();
+
+		let autocomplete_url = format!("/tag?q={}", &travel[..travel.len() - 1]);
+		let res = get(&app.client, &autocomplete_url, app.user_token());
+		assert_eq!(res.status(), Status::Ok);
+		assert!(res.into_json::<Vec<String>>().unwrap().contains(&travel));
+
+		let page_url = format!("/tracking/page/10/0?tag=remote&tag={travel}");
+		let page = get(&app.client, &page_url, token)
+			.into_json::<PaginationResult<Tracking>>()
+			.unwrap();
+		assert_eq!(page.total_items, 1);
+		assert_eq!(page.items[0].id, tagged.id);
+
+		let last_page_url = format!("/tracking/page/10/last?tag=remote&tag={travel}");
+		let last_page = get(&app.client, &last_page_url, token)
+			.into_json::<PaginationResult<Tracking>>()
+			.unwrap();
+		assert_eq!(last_page, page);
+
+		let update_tracking = UpdateTracking {
+			tags: Some(vec![travel.clone()]),
+			..Default::default()
+		};
+		let untagged_url = format!("/tracking/{}", untagged.id);
+		let res = patch(
+			&app.client,
+			&untagged_url,
+			to_string(&update_tracking).unwrap(),
+			token,
+		);
+		assert_eq!(res.into_json::<Tracking>().unwrap().tags, [travel.as_str()]);
+
+		let res = get(
+			&app.client,
+			"/tag/report?from=2024-03-18&to=2024-03-18",
+			token,
+		);
+		assert_eq!(res.status(), Status::Ok);
+		let report = res.into_json::<Vec<TagReport>>().unwrap();
+		let travel_report = report
+			.into_iter()
+			.find(|report| report.tag.as_ref() == Some(&travel))
+			.unwrap();
+		assert_eq!(
+			travel_report,
+			TagReport {
+				tag: Some(travel),
+				entries: 2,
+				performed: 4.0,
+				billed: 3.0,
+			}
+		);
+	}
+}
@@ -0,0 +1,317 @@
+use rocket::{
+	delete,
+	get,
+	patch,
+	post,
+	serde::json::Json,
+	Route,
+};
+use rocket_db_pools::Connection;
+use rocket_okapi::{
+	okapi::openapi3::OpenApi,
+	openapi,
+	openapi_get_routes_spec,
+	settings::OpenApiSettings,
+};
+
+use crate::{
+	db::{
+		team::{
+			CreateTeam,
+			Team,
+			TeamMember,
+			TeamReport,
+			UpdateTeam,
+		},
+		tracking::Tracking,
+		PaginationResult,
+	},
+	routes::parse_period,
+	Error,
+	Result,
+	User,
+	DB,
+};
+
+/// Admins, the manager of the team and everybody the manager reports to may see its data
+async fn ensure_team_access(db: &mut Connection<DB>, user: &User, team_id: i32) -> Result<()> {
+	let team = Team::read(db, team_id).await?;
+	if user.sys_role == "admin" {
+		return Ok(());
+	}
+	match team.manager_id {
+		Some(manager_id) if user.in_reporting_chain(db, manager_id).await? => Ok(()),
+		_ => Err(Error::ForbiddenAccess),
+	}
+}
+
+#[openapi(tag = "Team")]
+#[post("/", data = "<create_team>")]
+async fn create(
+	user: User,
+	mut db: Connection<DB>,
+	create_team: Json<CreateTeam>,
+) -> Result<Json<Team>> {
+	if user.sys_role != "admin" {
+		return Err(Error::ForbiddenAccess);
+	}
+	let team = Team::create(&mut db, user.id, &create_team).await?;
+	Ok(Json(team))
+}
+
+#[openapi(tag = "Team")]
+#[get("/<id>")]
+async fn get(_user: User, mut db: Connection<DB>, id: i32) -> Result<Json<Team>> {
+	Ok(Json(Team::read(&mut db, id).await?))
+}
+
+#[openapi(tag = "Team")]
+#[get("/page/<page_size>/<page>")]
+async fn get_page(
+	_user: User,
+	mut db: Connection<DB>,
+	page_size: i64,
+	page: i64,
+) -> Result<Json<PaginationResult<Team>>> {
+	Ok(Json(Team::paginate(&mut db, page, page_size).await?))
+}
+
+#[openapi(tag = "Team")]
+#[patch("/<id>", data = "<update_team>")]
+async fn update(
+	user: User,
+	mut db: Connection<DB>,
+	update_team: Json<UpdateTeam>,
+	id: i32,
+) -> Result<Json<Team>> {
+	if user.sys_role != "admin" {
+		return Err(Error::ForbiddenAccess);
+	}
+	Ok(Json(
+		Team::update(&mut db, user.id, id, &update_team).await?,
+	))
+}
+
+#[openapi(tag = "Team")]
+#[delete("/<id>")]
+async fn delete(user: User, mut db: Connection<DB>, id: i32) -> Result<Json<usize>> {
+	if user.sys_role != "admin" {
+		return Err(Error::ForbiddenAccess);
+	}
+	Ok(Json(Team::delete(&mut db, user.id, id).await?))
+}
+
+#[openapi(tag = "Team")]
+#[get("/<id>/member")]
+async fn get_members(_user: User, mut db: Connection<DB>, id: i32) -> Result<Json<Vec<i32>>> {
+	Ok(Json(Team::members(&mut db, id).await?))
+}
+
+#[openapi(tag = "Team")]
+#[post("/<id>/member/<user_id>")]
+async fn add_member(
+	user: User,
+	mut db: Connection<DB>,
+	id: i32,
+	user_id: i32,
+) -> Result<Json<TeamMember>> {
+	if user.sys_role != "admin" {
+		return Err(Error::ForbiddenAccess);
+	}
+	let member = Team::add_member(&mut db, user.id, id, user_id).await?;
+	Ok(Json(member))
+}
+
+#[openapi(tag = "Team")]
+#[delete("/<id>/member/<user_id>")]
+async fn remove_member(
+	user: User,
+	mut db: Connection<DB>,
+	id: i32,
+	user_id: i32,
+) -> Result<Json<usize>> {
+	if user.sys_role != "admin" {
+		return Err(Error::ForbiddenAccess);
+	}
+	Ok(Json(
+		Team::remove_member(&mut db, user.id, id, user_id).await?,
+	))
+}
+
+/// Timesheet of the team, dates are formatted as `YYYY-MM-DD`
+#[openapi(tag = "Team")]
+#[get("/<id>/tracking?<from>&<to>")]
+async fn get_tracking(
+	user: User,
+	mut db: Connection<DB>,
+	id: i32,
+	from: &str,
+	to: &str,
+) -> Result<Json<Vec<Tracking>>> {
+	ensure_team_access(&mut db, &user, id).await?;
+	let (from, to) = parse_period(from, to)?;
+	Ok(Json(Team::tracking(&mut db, id, from, to).await?))
+}
+
+/// Hours per member of the team, dates are formatted as `YYYY-MM-DD`
+#[openapi(tag = "Team")]
+#[get("/<id>/report?<from>&<to>")]
+async fn get_report(
+	user: User,
+	mut db: Connection<DB>,
+	id: i32,
+	from: &str,
+	to: &str,
+) -> Result<Json<Vec<TeamReport>>> {
+	ensure_team_access(&mut db, &user, id).await?;
+	let (from, to) = parse_period(from, to)?;
+	Ok(Json(Team::report(&mut db, id, from, to).await?))
+}
+
+pub fn routes(settings: &OpenApiSettings) -> (Vec<Route>, OpenApi) {
+	openapi_get_routes_spec![settings: create, get, get_page, update, delete, get_members, add_member, remove_member, get_tracking, get_report]
+}
+
+#[cfg(test)]
+mod test {
+	use pretty_assertions::assert_eq;
+	use rocket::{
+		http::Status,
+		serde::json::to_string,
+	};
+
+	use crate::{
+		db::{
+			project_member::{
+				CreateProjectMember,
+				ROLE_MEMBER,
+			},
+			team::{
+				Team,
+				TeamReport,
+			},
+			tracking::{
+				CreateTracking,
+				Tracking,
+			},
+			user::{
+				UpdateUser,
+				User,
+			},
+		},
+		test::{
+			generate_team,
+			methods::{
+				get,
+				patch,
+				post,
+			},
+			TestApp,
+		},
+	};
+
+	#[tracing_test::traced_test]
+	#[test]
+	fn team_timesheet() {
+		let app = TestApp::new();
+		let token = app.admin_token();
+		let (head, head_token) = app.user_with_token();
+		let (manager, manager_token) = app.user_with_token();
+		let (member, member_token) = app.user_with_token();
+
+		// head <- manager <- member
+		let set_manager = |user: &User, manager: &User| {
+			let update_user = UpdateUser {
+				manager_id: Some(Some(manager.id)),
+				..Default::default()
+			};
+			let url = format!("/user/{}", user.id);
+			let status = patch(&app.client, &url, to_string(&update_user).unwrap(), token).status();
+			status
+		};
+		assert_eq!(set_manager(&manager, &head), Status::Ok);
+		assert_eq!(set_manager(&member, &manager), Status::Ok);
+		assert_eq!(set_manager(&head, &member), Status::BadRequest);
+
+		let mut team = generate_team();
+		team.manager_id = Some(manager.id);
+		let res = post(
+			&app.client,
+			"/team",
+			to_string(&team).unwrap(),
+			&manager_token,
+		);
+		assert_eq!(res.status(), Status::Forbidden);
+		let res = post(&app.client, "/team", to_string(&team).unwrap(), token);
+		assert_eq!(res.status(), Status::Ok);
+		let team = res.into_json::<Team>().unwrap();
+		let member_url = format!("/team/{}/member/{}", team.id, member.id);
+		let res = post(&app.client, &member_url, String::new(), token);
+		assert_eq!(res.status(), Status::Ok);
+
+		let project = app.fixture().project().create();
+		let project_member = CreateProjectMember {
+			project_id: project.id,
+			user_id: member.id,
+			role: ROLE_MEMBER.to_string(),
+		};
+		let project_member_url = format!("/project/{}/member", project.id);
+		post(
+			&app.client,
+			&project_member_url,
+			to_string(&project_member).unwrap(),
+			token,
+		);
+		let tracking = CreateTracking {
+			client_id: project.client_id,
+			user_id: member.id,
+			project_id: project.id,
+			date: chrono::NaiveDate::from_ymd_opt(2024, 2, 12).unwrap(),
+			begin: chrono::NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+			end: chrono::NaiveTime::from_hms_opt(11, 0, 0).unwrap(),
+			pause: None,
+			performed: 2.0,
+			billed: 1.5,
+			description: None,
+			activities: vec![],
+			tags: vec![],
+			custom_fields: Default::default(),
+		};
+		let res = post(
+			&app.client,
+			"/tracking",
+			to_string(&tracking).unwrap(),
+			token,
+		);
+		assert_eq!(res.status(), Status::Ok);
+		let inserted_tracking = res.into_json::<Tracking>().unwrap();
+
+		let tracking_url = format!("/team/{}/tracking?from=2024-02-01&to=2024-02-29", team.id);
+		let res = get(&app.client, &tracking_url, &member_token);
+		assert_eq!(res.status(), Status::Forbidden);
+		let res = get(&app.client, &tracking_url, &manager_token);
+		assert_eq!(res.status(), Status::Ok);
+		let timesheet = res.into_json::<Vec<Tracking>>().unwrap();
+		assert_eq!(timesheet.len(), 1);
+		assert_eq!(timesheet[0].id, inserted_tracking.id);
+
+		// further up the reporting chain sees the team as well
+		let report_url = format!("/team/{}/report?from=2024-02-01&to=2024-02-29", team.id);
+		let res = get(&app.client, &report_url, &head_token);
+		assert_eq!(res.status(), Status::Ok);
+		let report = res.into_json::<Vec<TeamReport>>().unwrap();
+		assert_eq!(
+			report,
+			[TeamReport {
+				user_id: member.id,
+				entries: 1,
+				performed: 2.0,
+				billed: 1.5,
+			}]
+		);
+
+		let invalid_url = format!("/team/{}/report?from=yesterday&to=2024-02-29", team.id);
+		let res = get(&app.client, &invalid_url, token);
+		assert_eq!(res.status(), Status::BadRequest);
+	}
+}
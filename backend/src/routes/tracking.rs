@@ -1,29 +1,47 @@
 use rocket::{
 	delete,
-	fairing::AdHoc,
 	get,
 	patch,
 	post,
-	routes,
 	serde::json::Json,
+	Route,
 };
 use rocket_db_pools::Connection;
+use rocket_okapi::{
+	okapi::openapi3::OpenApi,
+	openapi,
+	openapi_get_routes_spec,
+	settings::OpenApiSettings,
+};
 
 use crate::{
 	db::{
+		cursor::{
+			self,
+			Cursor,
+			CursorPage,
+		},
+		custom_field::{
+			CustomField,
+			ENTITY_TRACKING,
+		},
+		tag::check_tags,
 		tracking::{
 			CreateTracking,
 			Tracking,
 			UpdateTracking,
 		},
+		ListQuery,
 		PaginationResult,
 	},
+	routes::ensure_lead,
 	Error,
 	Result,
 	User,
 	DB,
 };
 
+#[openapi(tag = "Tracking")]
 #[post("/", data = "<create_tracking>")]
 async fn create(
 	user: User,
@@ -33,95 +51,179 @@ async fn create(
 	if user.sys_role != "admin" {
 		return Err(Error::ForbiddenAccess);
 	}
-	let tracking = Tracking::create(&mut db, &create_tracking).await;
-	if let Ok(tracking) = tracking {
-		Ok(Json(tracking))
-	} else {
-		Err(Error::BadRequest("Tracking already exists".to_string()))
-	}
+	Tracking::validate(&mut db, &create_tracking).await?;
+	check_tags(&create_tracking.tags)?;
+	CustomField::validate(
+		&mut db,
+		ENTITY_TRACKING,
+		&create_tracking.custom_fields,
+		true,
+	)
+	.await?;
+	let tracking = Tracking::create(&mut db, user.id, &create_tracking).await?;
+	Ok(Json(tracking))
 }
 
+/// Users may change their own entries, leads of the project and admins every entry of it
+///
+/// Approved entries are locked. Changed fields have to pass the same checks as on a new entry.
+#[openapi(tag = "Tracking")]
 #[patch("/<id>", data = "<update_tracking>")]
 async fn update(
-	_user: User,
+	user: User,
 	mut db: Connection<DB>,
 	update_tracking: Json<UpdateTracking>,
 	id: i32,
 ) -> Result<Json<Tracking>> {
-	Ok(Json(Tracking::update(&mut db, id, &update_tracking).await?))
+	let old = Tracking::read(&mut db, id).await?;
+	let own = old.user_id == user.id
+		&& update_tracking
+			.user_id
+			.is_none_or(|user_id| user_id == user.id);
+	if !own {
+		ensure_lead(&mut db, &user, old.project_id).await?;
+		if let Some(project_id) = update_tracking.project_id {
+			ensure_lead(&mut db, &user, project_id).await?;
+		}
+	}
+	if old.approved_at.is_some() {
+		return Err(Error::conflict("tracking.approved"));
+	}
+	Tracking::validate_update(&mut db, &old, &update_tracking).await?;
+	if let Some(tags) = &update_tracking.tags {
+		check_tags(tags)?;
+	}
+	CustomField::validate(
+		&mut db,
+		ENTITY_TRACKING,
+		&update_tracking.custom_fields,
+		false,
+	)
+	.await?;
+	Ok(Json(
+		Tracking::update(&mut db, user.id, id, &update_tracking).await?,
+	))
 }
 
+#[openapi(tag = "Tracking")]
 #[get("/<id>")]
 async fn get(_user: User, mut db: Connection<DB>, id: i32) -> Result<Json<Tracking>> {
 	Ok(Json(Tracking::read(&mut db, id).await?))
 }
 
-#[get("/page/<page_size>/<page>")]
+/// Entries can be filtered by tags with `?tag=remote&tag=travel`, only entries carrying
+/// every given tag are returned
+#[openapi(tag = "Tracking")]
+#[get("/page/<page_size>/<page>?<tag>&<list..>")]
 async fn get_page(
 	_user: User,
 	mut db: Connection<DB>,
 	page_size: i64,
 	page: i64,
+	tag: Vec<String>,
+	list: ListQuery,
 ) -> Result<Json<PaginationResult<Tracking>>> {
-	Ok(Json(Tracking::paginate(&mut db, page, page_size).await?))
+	Ok(Json(
+		Tracking::paginate(&mut db, &tag, &list, page, page_size).await?,
+	))
 }
 
-#[get("/page/<page_size>/last", rank = 2)]
+/// Entries can be filtered by tags like on the other pages
+#[openapi(tag = "Tracking")]
+#[get("/page/<page_size>/last?<tag>&<list..>", rank = 2)]
 async fn get_last_page(
 	_user: User,
 	mut db: Connection<DB>,
 	page_size: i64,
+	tag: Vec<String>,
+	list: ListQuery,
 ) -> Result<Json<PaginationResult<Tracking>>> {
-	let last_page = Tracking::last_page(&mut db, page_size).await?;
+	let last_page = Tracking::last_page(&mut db, page_size, &tag, &list).await?;
+	Ok(Json(
+		Tracking::paginate(&mut db, &tag, &list, last_page, page_size).await?,
+	))
+}
+
+/// Admins and leads of the project may approve an entry
+#[openapi(tag = "Tracking")]
+#[post("/<id>/approve")]
+async fn approve(user: User, mut db: Connection<DB>, id: i32) -> Result<Json<Tracking>> {
+	let tracking = Tracking::read(&mut db, id).await?;
+	ensure_lead(&mut db, &user, tracking.project_id).await?;
+	Ok(Json(Tracking::approve(&mut db, user.id, id).await?))
+}
+
+/// Keyset pagination, ordered by id and stable while entries are added
+///
+/// Starts at the first entry, the `next` and `prev` cursors of a page are passed back as
+/// `?after=`. `?total=true` adds the number of all matching entries.
+#[openapi(tag = "Tracking")]
+#[get("/cursor?<after>&<limit>&<total>&<tag>&<list..>")]
+async fn get_cursor(
+	_user: User,
+	mut db: Connection<DB>,
+	after: Option<&str>,
+	limit: Option<i64>,
+	total: Option<bool>,
+	tag: Vec<String>,
+	list: ListQuery,
+) -> Result<Json<CursorPage<Tracking>>> {
+	let after = after.map(Cursor::decode).transpose()?;
 	Ok(Json(
-		Tracking::paginate(&mut db, last_page, page_size).await?,
+		Tracking::cursor(
+			&mut db,
+			&tag,
+			&list,
+			after,
+			cursor::limit(limit),
+			total.unwrap_or_default(),
+		)
+		.await?,
 	))
 }
 
+#[openapi(tag = "Tracking")]
 #[delete("/<id>")]
 async fn delete(user: User, mut db: Connection<DB>, id: i32) -> Result<Json<usize>> {
 	if user.sys_role != "admin" {
 		return Err(Error::ForbiddenAccess);
 	}
-	Ok(Json(Tracking::delete(&mut db, id).await?))
+	Ok(Json(Tracking::delete(&mut db, user.id, id).await?))
 }
 
-pub fn mount() -> AdHoc {
-	AdHoc::on_ignite("Tracking", |rocket| async {
-		rocket.mount(
-			"/tracking",
-			routes![create, get, update, delete, get_page, get_last_page,],
-		)
-	})
+pub fn routes(settings: &OpenApiSettings) -> (Vec<Route>, OpenApi) {
+	openapi_get_routes_spec![settings: create, get, update, delete, approve, get_page, get_last_page, get_cursor]
 }
 
 #[cfg(test)]
 mod test {
-	use fake::{
-		Fake,
-		Faker,
-	};
-	use itertools::Itertools;
+
 	use pretty_assertions::{
 		assert_eq,
 		assert_ne,
 	};
-	use rand::{
-		rngs::StdRng,
-		SeedableRng,
-	};
 	use rocket::{
 		http::Status,
-		local::blocking::Client,
 		serde::json::to_string,
 	};
 
 	use crate::{
 		db::{
-			client::Client as ClientDB,
-			project::Project,
+			activity::Activity,
+			audit::{
+				Action,
+				Audit,
+			},
+			cursor::CursorPage,
+			project::{
+				Project,
+				UpdateProject,
+			},
+			project_member::{
+				CreateProjectMember,
+				ROLE_LEAD,
+			},
 			tracking::{
-				CreateTracking,
 				Tracking,
 				UpdateTracking,
 			},
@@ -129,99 +231,56 @@ mod test {
 			PaginationResult,
 		},
 		error::ErrorJson,
-		rocket,
 		test::{
-			generate_client,
-			generate_project,
-			generate_user,
+			generate_activity,
+			generate_tracking,
 			methods::{
 				delete,
 				get,
 				patch,
 				post,
 			},
-			token::{
-				get_token_admin,
-				get_token_user,
-			},
+			token::get_token,
+			TestApp,
 		},
 	};
 
-	pub fn generate_tracking_raw(client_id: i32, user_id: i32, project_id: i32) -> CreateTracking {
-		let mut rng = StdRng::from_entropy();
-		let activities = vec![
-			rand::Rng::gen_range(&mut rng, 1..50),
-			rand::Rng::gen_range(&mut rng, 1..50),
-			rand::Rng::gen_range(&mut rng, 1..50),
-		];
-		let activities = activities.into_iter().unique().collect();
-		CreateTracking {
-			client_id,
-			user_id,
-			project_id,
-			date: Faker.fake(),
-			begin: Faker.fake(),
-			end: Faker.fake(),
-			pause: Faker.fake(),
-			performed: ((Faker.fake::<f32>() * 100.0).round() / 100.0),
-			billed: ((Faker.fake::<f32>() * 100.0).round() / 100.0),
-			description: Faker.fake(),
-			activities,
-		}
-	}
-
-	fn generate_tracking<'a>(client: &'a Client, token: &'a str) -> CreateTracking {
-		let (client_db, user, project) = generate_client_user_project(client, token);
-		generate_tracking_raw(client_db.id, user.id, project.id)
-	}
-
-	fn generate_client_user_project<'a>(
-		client: &'a Client,
-		token: &'a str,
-	) -> (ClientDB, User, Project) {
-		let client_db = generate_client();
-		let client_db = post(client, "/client", to_string(&client_db).unwrap(), token)
-			.into_json::<ClientDB>()
-			.unwrap();
-		let user = generate_user();
-		let user = post(client, "/user", to_string(&user).unwrap(), token)
-			.into_json::<User>()
-			.unwrap();
-		let mut project = generate_project();
-		project.client_id = client_db.id;
-		let project = post(client, "/project", to_string(&project).unwrap(), token)
-			.into_json::<Project>()
-			.unwrap();
-		(client_db, user, project)
+	/// Project of a new client with a new user as member
+	fn project_with_member(app: &TestApp) -> (User, Project) {
+		let user = app.fixture().user().create().user;
+		let project = app.fixture().project().member(&user).create();
+		(user, project)
 	}
 
 	#[tracing_test::traced_test]
 	#[test]
 	fn tracking_single() {
-		let client = Client::tracked(rocket()).unwrap();
-		let token = get_token_admin(&client);
-		let tracking = generate_tracking(&client, token);
+		let app = TestApp::new();
+		let token = app.admin_token();
+		let (user, project) = project_with_member(&app);
+		let tracking = generate_tracking(project.client_id, user.id, project.id);
 		let base_url = String::from("/tracking");
 
 		// Test unauthorized
-		let res = client
+		let res = app
+			.client
 			.post("/tracking")
 			.body(to_string(&tracking).unwrap())
 			.dispatch();
 		assert_eq!(res.status(), Status::Unauthorized);
 
 		// Test empty token
-		let res = post(&client, &base_url, to_string(&tracking).unwrap(), "");
+		let res = post(&app.client, &base_url, to_string(&tracking).unwrap(), "");
 		assert_eq!(res.status(), Status::Unauthorized);
 
 		// Create new tracking
-		let res = post(&client, &base_url, to_string(&tracking).unwrap(), token);
+		let res = post(&app.client, &base_url, to_string(&tracking).unwrap(), token);
 		let inserted_tracking = res.into_json::<Tracking>().unwrap();
 		assert_eq!(inserted_tracking, tracking);
 		let tracking_id = inserted_tracking.id;
 
 		// Check duplicate tracking insert
-		let res = post(&client, &base_url, to_string(&tracking).unwrap(), token);
+		let res = post(&app.client, &base_url, to_string(&tracking).unwrap(), token);
 		// TODO Allow duplicate tracking for now
 		assert_eq!(res.status(), Status::Ok);
 		assert_ne!(
@@ -239,22 +298,27 @@ mod test {
 			..Default::default()
 		};
 		let url = format!("{base_url}/{tracking_id}");
-		let res = patch(&client, &url, to_string(&update_tracking).unwrap(), token);
+		let res = patch(
+			&app.client,
+			&url,
+			to_string(&update_tracking).unwrap(),
+			token,
+		);
 		assert_eq!(res.status(), Status::Ok);
 		let updated_tracking = res.into_json::<Tracking>().unwrap();
 		assert_eq!(updated_tracking.activities, new_activities);
 		assert_ne!(updated_tracking, inserted_tracking);
 
 		// Get tracking
-		let res = get(&client, &url, token);
+		let res = get(&app.client, &url, token);
 		assert_eq!(res.status(), Status::Ok);
 		let res = res.into_json::<Tracking>().unwrap();
 		assert_eq!(res, updated_tracking);
 
 		// Test inserting as normal user
-		let user_token = get_token_user(&client);
+		let user_token = app.user_token();
 		let res = post(
-			&client,
+			&app.client,
 			"/tracking",
 			to_string(&tracking).unwrap(),
 			user_token,
@@ -262,23 +326,35 @@ mod test {
 		assert_eq!(res.status(), Status::Forbidden);
 
 		// delete tracking
-		let res = delete(&client, &url, token);
+		let res = delete(&app.client, &url, token);
 		assert_eq!(res.status(), Status::Ok);
+
+		// entries are gone for good, the audit log has to say so
+		let audit_url = format!("/audit?entity=tracking&id={tracking_id}");
+		let audit = get(&app.client, &audit_url, token)
+			.into_json::<PaginationResult<Audit>>()
+			.unwrap();
+		assert_eq!(audit.items[0].action, Action::Purge);
 	}
 
 	#[tracing_test::traced_test]
 	#[test]
 	fn tracking_multiple() {
-		let client = Client::tracked(rocket()).unwrap();
-		let token = get_token_admin(&client);
+		let app = TestApp::new();
+		let token = app.admin_token();
 		let base_url = String::from("/tracking");
-		let (client_db, user, project) = generate_client_user_project(&client, token);
+		let (user, project) = project_with_member(&app);
 
 		let mut tracking_list: Vec<Tracking> = Vec::new();
 		// insert multiple trackings
 		for _ in 0..10 {
-			let tracking = generate_tracking_raw(client_db.id, user.id, project.id);
-			let res = post(&client, "/tracking", to_string(&tracking).unwrap(), token);
+			let tracking = generate_tracking(project.client_id, user.id, project.id);
+			let res = post(
+				&app.client,
+				"/tracking",
+				to_string(&tracking).unwrap(),
+				token,
+			);
 			if res.status() != Status::Ok {
 				dbg!(res.into_string());
 				panic!();
@@ -287,12 +363,12 @@ mod test {
 		}
 
 		let url = format!("{base_url}/{}", tracking_list[0].id);
-		let res = get(&client, &url, token);
+		let res = get(&app.client, &url, token);
 		assert_eq!(res.status(), Status::Ok);
 		assert_eq!(res.into_json::<Tracking>().unwrap(), tracking_list[0]);
 
 		// get first page of users with page_size 5
-		let res = get(&client, "/tracking/page/5/0", token);
+		let res = get(&app.client, "/tracking/page/5/0", token);
 		assert_eq!(res.status(), Status::Ok);
 		let pagination = res.into_json::<PaginationResult<Tracking>>().unwrap();
 		assert_eq!(pagination.page, 0);
@@ -300,7 +376,7 @@ mod test {
 
 		// get last page of users with page_size 5 with number
 		let url = format!("{base_url}/page/5/{}", pagination.num_pages - 1);
-		let res = get(&client, &url, token);
+		let res = get(&app.client, &url, token);
 		assert_eq!(res.status(), Status::Ok);
 		let pagination = res.into_json::<PaginationResult<Tracking>>().unwrap();
 		// the last page contains 5 OR LESS items
@@ -316,7 +392,7 @@ mod test {
 		);
 
 		let url = format!("{base_url}/page/5/last");
-		let res = get(&client, &url, token);
+		let res = get(&app.client, &url, token);
 		assert_eq!(res.status(), Status::Ok);
 		let last_page = res.into_json::<PaginationResult<Tracking>>().unwrap();
 		assert_eq!(last_page, pagination);
@@ -324,8 +400,439 @@ mod test {
 		// delete all trackings
 		for tracking in tracking_list {
 			let url = format!("{base_url}/{}", tracking.id);
-			let res = delete(&client, &url, token);
+			let res = delete(&app.client, &url, token);
+			assert_eq!(res.status(), Status::Ok);
+		}
+	}
+
+	#[tracing_test::traced_test]
+	#[test]
+	fn tracking_cursor() {
+		let app = TestApp::new();
+		let token = app.admin_token();
+		let (user, project) = project_with_member(&app);
+
+		let mut tracking_list: Vec<Tracking> = Vec::new();
+		for _ in 0..5 {
+			let tracking = generate_tracking(project.client_id, user.id, project.id);
+			let res = post(
+				&app.client,
+				"/tracking",
+				to_string(&tracking).unwrap(),
+				token,
+			);
 			assert_eq!(res.status(), Status::Ok);
+			tracking_list.push(res.into_json::<Tracking>().unwrap());
+		}
+		let ids = |page: &CursorPage<Tracking>| {
+			page
+				.items
+				.iter()
+				.map(|tracking| tracking.id)
+				.collect::<Vec<_>>()
+		};
+		let base_url = format!("/tracking/cursor?limit=2&filter[project_id]={}", project.id);
+
+		let url = format!("{base_url}&total=true");
+		let res = get(&app.client, &url, token);
+		assert_eq!(res.status(), Status::Ok);
+		let first = res.into_json::<CursorPage<Tracking>>().unwrap();
+		assert_eq!(ids(&first), [tracking_list[0].id, tracking_list[1].id]);
+		assert_eq!(first.total_items, Some(5));
+		assert_eq!(first.prev, None);
+
+		let url = format!("{base_url}&after={}", first.next.unwrap());
+		let second = get(&app.client, &url, token)
+			.into_json::<CursorPage<Tracking>>()
+			.unwrap();
+		assert_eq!(ids(&second), [tracking_list[2].id, tracking_list[3].id]);
+		assert_eq!(second.total_items, None);
+
+		let url = format!("{base_url}&after={}", second.next.as_ref().unwrap());
+		let third = get(&app.client, &url, token)
+			.into_json::<CursorPage<Tracking>>()
+			.unwrap();
+		assert_eq!(ids(&third), [tracking_list[4].id]);
+		assert_eq!(third.next, None);
+
+		// walking back ends up at the second page again
+		let url = format!("{base_url}&after={}", third.prev.unwrap());
+		let back = get(&app.client, &url, token)
+			.into_json::<CursorPage<Tracking>>()
+			.unwrap();
+		assert_eq!(ids(&back), ids(&second));
+		assert!(back.prev.is_some());
+
+		let url = format!("{base_url}&after=nonsense");
+		let res = get(&app.client, &url, token);
+		assert_eq!(res.status(), Status::BadRequest);
+		let url = format!("{base_url}&sort=date");
+		let res = get(&app.client, &url, token);
+		assert_eq!(res.status(), Status::BadRequest);
+	}
+
+	#[tracing_test::traced_test]
+	#[test]
+	fn tracking_deleted_references() {
+		let app = TestApp::new();
+		let token = app.admin_token();
+		let (user, project) = project_with_member(&app);
+		let activity = post(
+			&app.client,
+			"/activity",
+			to_string(&generate_activity()).unwrap(),
+			token,
+		)
+		.into_json::<Activity>()
+		.unwrap();
+		let mut tracking = generate_tracking(project.client_id, user.id, project.id);
+		tracking.activities = vec![activity.id];
+		let res = post(
+			&app.client,
+			"/tracking",
+			to_string(&tracking).unwrap(),
+			token,
+		);
+		assert_eq!(res.status(), Status::Ok);
+
+		let activity_url = format!("/activity/{}", activity.id);
+		let res = delete(&app.client, &activity_url, token);
+		assert_eq!(res.status(), Status::Ok);
+		let res = post(
+			&app.client,
+			"/tracking",
+			to_string(&tracking).unwrap(),
+			token,
+		);
+		assert_eq!(res.status(), Status::BadRequest);
+		let error = res.into_json::<ErrorJson>().unwrap();
+		assert_eq!(error.error_code, "tracking.activity_missing");
+		assert_eq!(error.details[0].field, "activities");
+
+		let project_url = format!("/project/{}", project.id);
+		let res = delete(&app.client, &project_url, token);
+		assert_eq!(res.status(), Status::Ok);
+		tracking.activities = vec![];
+		let res = post(
+			&app.client,
+			"/tracking",
+			to_string(&tracking).unwrap(),
+			token,
+		);
+		assert_eq!(res.status(), Status::BadRequest);
+		let error = res.into_json::<ErrorJson>().unwrap();
+		assert_eq!(error.error_code, "tracking.project_missing");
+		assert_eq!(error.details[0].field, "project_id");
+	}
+
+	#[tracing_test::traced_test]
+	#[test]
+	fn tracking_archived_project() {
+		let app = TestApp::new();
+		let token = app.admin_token();
+		let (user, project) = project_with_member(&app);
+		let tracking = generate_tracking(project.client_id, user.id, project.id);
+		let project_url = format!("/project/{}", project.id);
+
+		let update_project = UpdateProject {
+			archived: Some(true),
+			..Default::default()
+		};
+		let res = patch(
+			&app.client,
+			&project_url,
+			to_string(&update_project).unwrap(),
+			token,
+		);
+		assert_eq!(res.status(), Status::Ok);
+		let res = post(
+			&app.client,
+			"/tracking",
+			to_string(&tracking).unwrap(),
+			token,
+		);
+		assert_eq!(res.status(), Status::BadRequest);
+		let error = res.into_json::<ErrorJson>().unwrap();
+		assert_eq!(error.error, "Project is archived");
+		assert_eq!(error.error_code, "tracking.project_archived");
+		assert_eq!(error.details[0].field, "project_id");
+
+		// the project ended the day before the tracking
+		let update_project = UpdateProject {
+			archived: Some(false),
+			end_date: Some(tracking.date.pred_opt()),
+			..Default::default()
+		};
+		let res = patch(
+			&app.client,
+			&project_url,
+			to_string(&update_project).unwrap(),
+			token,
+		);
+		assert_eq!(res.status(), Status::Ok);
+		let res = post(
+			&app.client,
+			"/tracking",
+			to_string(&tracking).unwrap(),
+			token,
+		);
+		assert_eq!(res.status(), Status::BadRequest);
+		let error = res.into_json::<ErrorJson>().unwrap();
+		assert_eq!(error.error, "Date is after the end of the project");
+		assert_eq!(error.error_code, "tracking.after_project_end");
+		assert_eq!(error.details[0].field, "date");
+
+		let update_project = UpdateProject {
+			end_date: Some(Some(tracking.date)),
+			..Default::default()
+		};
+		let res = patch(
+			&app.client,
+			&project_url,
+			to_string(&update_project).unwrap(),
+			token,
+		);
+		assert_eq!(res.status(), Status::Ok);
+		let res = post(
+			&app.client,
+			"/tracking",
+			to_string(&tracking).unwrap(),
+			token,
+		);
+		assert_eq!(res.status(), Status::Ok);
+	}
+
+	#[tracing_test::traced_test]
+	#[test]
+	fn tracking_update_validated() {
+		let app = TestApp::new();
+		let token = app.admin_token();
+		let project = app.fixture().project().create();
+		let tracking = app.fixture().tracking().project(&project).create();
+		let archived = app.fixture().project().create();
+		let foreign = app.fixture().project().create();
+		let url = format!("/tracking/{}", tracking.id);
+
+		let update_project = UpdateProject {
+			archived: Some(true),
+			..Default::default()
+		};
+		let project_url = format!("/project/{}", archived.id);
+		let res = patch(
+			&app.client,
+			&project_url,
+			to_string(&update_project).unwrap(),
+			token,
+		);
+		assert_eq!(res.status(), Status::Ok);
+
+		// moving the entry onto an archived project is rejected like a new entry
+		let update_tracking = UpdateTracking {
+			client_id: Some(archived.client_id),
+			project_id: Some(archived.id),
+			..Default::default()
+		};
+		let res = patch(
+			&app.client,
+			&url,
+			to_string(&update_tracking).unwrap(),
+			token,
+		);
+		assert_eq!(res.status(), Status::BadRequest);
+		let error = res.into_json::<ErrorJson>().unwrap();
+		assert_eq!(error.error_code, "tracking.project_archived");
+
+		let update_tracking = UpdateTracking {
+			client_id: Some(foreign.client_id),
+			project_id: Some(foreign.id),
+			..Default::default()
+		};
+		let res = patch(
+			&app.client,
+			&url,
+			to_string(&update_tracking).unwrap(),
+			token,
+		);
+		assert_eq!(res.status(), Status::BadRequest);
+		let error = res.into_json::<ErrorJson>().unwrap();
+		assert_eq!(error.error_code, "tracking.not_member");
+
+		let res = get(&app.client, &url, token);
+		assert_eq!(res.into_json::<Tracking>().unwrap().project_id, project.id);
+
+		// fields that don't change are not checked again, a late correction still works
+		let project_url = format!("/project/{}", project.id);
+		let res = patch(
+			&app.client,
+			&project_url,
+			to_string(&update_project).unwrap(),
+			token,
+		);
+		assert_eq!(res.status(), Status::Ok);
+		let update_tracking = UpdateTracking {
+			description: Some("Corrected".to_string()),
+			..Default::default()
+		};
+		let res = patch(
+			&app.client,
+			&url,
+			to_string(&update_tracking).unwrap(),
+			token,
+		);
+		assert_eq!(res.status(), Status::Ok);
+		assert_eq!(
+			res.into_json::<Tracking>().unwrap().description.as_deref(),
+			Some("Corrected")
+		);
+	}
+
+	#[tracing_test::traced_test]
+	#[test]
+	fn tracking_create_atomic() {
+		let app = TestApp::new();
+		let token = app.admin_token();
+		let (user, project) = project_with_member(&app);
+		let mut tracking = generate_tracking(project.client_id, user.id, project.id);
+		tracking.activities = vec![1, i32::MAX];
+
+		let res = post(
+			&app.client,
+			"/tracking",
+			to_string(&tracking).unwrap(),
+			token,
+		);
+		assert_eq!(res.status(), Status::BadRequest);
+
+		// the entry itself was rolled back with its activities
+		let url = format!(
+			"/tracking/cursor?filter[project_id]={}&total=true",
+			project.id
+		);
+		let page = get(&app.client, &url, token)
+			.into_json::<CursorPage<Tracking>>()
+			.unwrap();
+		assert_eq!(page.total_items, Some(0));
+	}
+
+	#[tracing_test::traced_test]
+	#[test]
+	fn tracking_clear_pause() {
+		let app = TestApp::new();
+		let token = app.admin_token();
+		let tracking = app.fixture().tracking().create();
+		let url = format!("/tracking/{}", tracking.id);
+
+		let pause = chrono::NaiveTime::from_hms_opt(0, 30, 0).unwrap();
+		let update_tracking = UpdateTracking {
+			pause: Some(Some(pause)),
+			..Default::default()
+		};
+		let res = patch(
+			&app.client,
+			&url,
+			to_string(&update_tracking).unwrap(),
+			token,
+		);
+		assert_eq!(res.status(), Status::Ok);
+		assert_eq!(res.into_json::<Tracking>().unwrap().pause, Some(pause));
+
+		// `pause?: string | null` in types.ts, null clears the pause
+		let update_tracking = UpdateTracking {
+			pause: Some(None),
+			..Default::default()
+		};
+		let body = to_string(&update_tracking).unwrap();
+		assert!(body.contains(r#""pause":null"#), "{body}");
+		let res = patch(&app.client, &url, body, token);
+		assert_eq!(res.status(), Status::Ok);
+		let res = get(&app.client, &url, token);
+		assert_eq!(res.into_json::<Tracking>().unwrap().pause, None);
+	}
+
+	#[tracing_test::traced_test]
+	#[test]
+	fn tracking_update_permissions() {
+		let app = TestApp::new();
+		let token = app.admin_token();
+		let owner = app.fixture().user().create();
+		let lead = app.fixture().user().create();
+		let project = app.fixture().project().member(&owner).create();
+		let project_member = CreateProjectMember {
+			project_id: project.id,
+			user_id: lead.id,
+			role: ROLE_LEAD.to_string(),
+		};
+		let member_url = format!("/project/{}/member", project.id);
+		let res = post(
+			&app.client,
+			&member_url,
+			to_string(&project_member).unwrap(),
+			token,
+		);
+		assert_eq!(res.status(), Status::Ok);
+		let tracking = generate_tracking(project.client_id, owner.id, project.id);
+		let tracking = post(
+			&app.client,
+			"/tracking",
+			to_string(&tracking).unwrap(),
+			token,
+		)
+		.into_json::<Tracking>()
+		.unwrap();
+		let url = format!("/tracking/{}", tracking.id);
+		let owner_token = get_token(&app.client, &owner.email, &owner.password);
+		let lead_token = get_token(&app.client, &lead.email, &lead.password);
+		let update_tracking = |description: &str| {
+			to_string(&UpdateTracking {
+				description: Some(description.to_string()),
+				..Default::default()
+			})
+			.unwrap()
+		};
+
+		// somebody else's entry
+		let res = patch(
+			&app.client,
+			&url,
+			update_tracking("other"),
+			app.user_token(),
+		);
+		assert_eq!(res.status(), Status::Forbidden);
+		// the owner can't hand the entry to somebody else
+		let hand_over = UpdateTracking {
+			user_id: Some(lead.id),
+			..Default::default()
+		};
+		let res = patch(
+			&app.client,
+			&url,
+			to_string(&hand_over).unwrap(),
+			&owner_token,
+		);
+		assert_eq!(res.status(), Status::Forbidden);
+
+		let res = patch(&app.client, &url, update_tracking("owner"), &owner_token);
+		assert_eq!(res.status(), Status::Ok);
+		let res = patch(&app.client, &url, update_tracking("lead"), &lead_token);
+		assert_eq!(res.status(), Status::Ok);
+		assert_eq!(
+			res.into_json::<Tracking>().unwrap().description.as_deref(),
+			Some("lead")
+		);
+
+		// approved entries are locked, for admins as well
+		let approve_url = format!("{url}/approve");
+		let res = post(&app.client, &approve_url, String::new(), &lead_token);
+		assert_eq!(res.status(), Status::Ok);
+		for token in [owner_token.as_str(), &lead_token, token] {
+			let res = patch(&app.client, &url, update_tracking("approved"), token);
+			assert_eq!(res.status(), Status::Conflict);
+			let error = res.into_json::<ErrorJson>().unwrap();
+			assert_eq!(error.error_code, "tracking.approved");
 		}
+		let res = get(&app.client, &url, token);
+		let approved = res.into_json::<Tracking>().unwrap();
+		assert_eq!(approved.description.as_deref(), Some("lead"));
+		assert_eq!(approved.approved_by, Some(lead.id));
 	}
 }
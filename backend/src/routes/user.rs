@@ -1,28 +1,36 @@
 use rocket::{
 	delete,
-	fairing::AdHoc,
 	get,
 	patch,
 	post,
-	routes,
 	serde::json::Json,
+	Route,
+	State,
 };
 use rocket_db_pools::Connection;
+use rocket_okapi::{
+	okapi::openapi3::OpenApi,
+	openapi,
+	openapi_get_routes_spec,
+	settings::OpenApiSettings,
+};
 
 use crate::{
-	auth::Tokenizer,
-	db::{
-		user::{
-			CreateUser,
-			UpdateUser,
-			User,
-		},
-		PaginationResult,
+	auth::{
+		LoginThrottle,
+		Tokenizer,
+	},
+	db::user::{
+		CreateUser,
+		UpdateUser,
+		User,
 	},
+	routes::ensure_admin,
 	Error,
 	DB,
 };
 
+#[openapi(tag = "User")]
 #[post("/", data = "<create_user>")]
 async fn create(
 	user: User,
@@ -33,14 +41,11 @@ async fn create(
 		return Err(Error::ForbiddenAccess);
 	}
 	create_user.password = Tokenizer::hash_password(create_user.password.as_bytes())?;
-	let user = User::create(&mut db, &create_user).await;
-	if let Ok(user) = user {
-		Ok(Json(user))
-	} else {
-		Err(Error::BadRequest("User already exists".to_string()))
-	}
+	let user = User::create(&mut db, user.id, &create_user).await?;
+	Ok(Json(user))
 }
 
+#[openapi(tag = "User")]
 #[patch("/<id>", data = "<update_user>")]
 async fn update(
 	user: User,
@@ -51,63 +56,40 @@ async fn update(
 	if user.id != id && user.sys_role != "admin" {
 		return Err(Error::ForbiddenAccess);
 	}
+	// users edit their own profile, but only admins change roles and managers
+	if update_user.sys_role.is_some() || update_user.manager_id.is_some() {
+		ensure_admin(&user)?;
+	}
+	if let Some(Some(manager_id)) = update_user.manager_id {
+		if manager_id == id || User::reports_to(&mut db, manager_id, id).await? {
+			return Err(Error::bad_request("user.manager_cycle"));
+		}
+	}
 	if let Some(password) = &update_user.password {
 		update_user.password = Some(Tokenizer::hash_password(password.as_bytes())?);
 	}
-	let user = User::update(&mut db, id, &update_user).await?;
+	let user = User::update(&mut db, user.id, id, &update_user).await?;
 	Ok(Json(user))
 }
 
-#[get("/<id>")]
-async fn get(_user: User, mut db: Connection<DB>, id: i32) -> Result<Json<User>, Error> {
-	Ok(Json(User::read(&mut db, id).await?))
-}
-
-#[get("/page/<page_size>/<page>")]
-async fn get_page(
-	_user: User,
-	mut db: Connection<DB>,
-	page_size: i64,
-	page: i64,
-) -> Result<Json<PaginationResult<User>>, Error> {
-	Ok(Json(User::paginate(&mut db, page, page_size).await?))
-}
-
-#[get("/page/<page_size>/last", rank = 2)]
-async fn get_last_page(
-	_user: User,
+/// Lifts the login lock of the user after too many wrong passwords, returns whether it was locked
+#[openapi(tag = "User")]
+#[post("/<id>/unlock")]
+async fn unlock(
+	user: User,
+	throttle: &State<LoginThrottle>,
 	mut db: Connection<DB>,
-	page_size: i64,
-) -> Result<Json<PaginationResult<User>>, Error> {
-	let last_page = User::last_page(&mut db, page_size).await?;
-	Ok(Json(User::paginate(&mut db, last_page, page_size).await?))
-}
-
-#[delete("/<id>")]
-async fn delete(user: User, mut db: Connection<DB>, id: i32) -> Result<(), Error> {
-	if user.sys_role != "admin" {
-		return Err(Error::ForbiddenAccess);
-	}
-	User::delete(&mut db, id).await?;
-	Ok(())
+	id: i32,
+) -> Result<Json<bool>, Error> {
+	ensure_admin(&user)?;
+	let locked = User::read(&mut db, id).await?;
+	Ok(Json(throttle.unlock(&locked.email)))
 }
 
-// #[delete("/", data = "<user_id>")]
-// async fn soft_delete(user: User, mut db: Connection<DB>, user_id: Json<i32>) -> Result<(), Error> {
-// 	if user.sys_role != "admin" {
-// 		return Err(Error::ForbiddenAccess);
-// 	}
-// 	User::soft_delete(&mut db, *user_id).await?;
-// 	Ok(())
-// }
+crud_routes!(User, "User", read, delete);
 
-pub fn mount() -> AdHoc {
-	AdHoc::on_ignite("User Routes", |rocket| async {
-		rocket.mount(
-			"/user",
-			routes![create, get, get_page, get_last_page, update, delete],
-		)
-	})
+pub fn routes(settings: &OpenApiSettings) -> (Vec<Route>, OpenApi) {
+	openapi_get_routes_spec![settings: create, get, get_page, get_last_page, update, delete, restore, purge, unlock]
 }
 
 #[cfg(test)]
@@ -122,7 +104,6 @@ mod test {
 	};
 	use rocket::{
 		http::Status,
-		local::blocking::Client,
 		serde::json::to_string,
 	};
 
@@ -135,7 +116,6 @@ mod test {
 			PaginationResult,
 		},
 		error::ErrorJson,
-		rocket,
 		test::{
 			generate_user,
 			methods::{
@@ -144,41 +124,34 @@ mod test {
 				patch,
 				post,
 			},
-			token::{
-				get_token_admin,
-				get_token_user,
-			},
+			TestApp,
 		},
 	};
 
 	#[tracing_test::traced_test]
 	#[test]
 	fn user_single() {
-		let client = Client::tracked(rocket()).unwrap();
+		let app = TestApp::new();
 		let mut user = generate_user();
 		let base_url = String::from("/user");
 
 		// Test unauthorized
-		let res = post(&client, &base_url, to_string(&user).unwrap(), "");
+		let res = post(&app.client, &base_url, to_string(&user).unwrap(), "");
 		assert_eq!(res.status(), Status::Unauthorized);
 
-		let token = get_token_admin(&client);
+		let token = app.admin_token();
 		// Create new user
-		let res = post(&client, &base_url, to_string(&user).unwrap(), token);
+		let res = post(&app.client, &base_url, to_string(&user).unwrap(), token);
 		let inserted_user = res.into_json::<User>().unwrap();
 		assert_eq!(inserted_user, user);
 		let user_id = inserted_user.id;
 
 		// Check duplicate user insert
-		let res = post(&client, &base_url, to_string(&user).unwrap(), token);
-		assert_eq!(res.status(), Status::BadRequest);
-		assert_eq!(
-			res.into_string(),
-			Some(
-				to_string(&ErrorJson::new(400, "User already exists"))
-					.expect("Could not serialize ErrorJson")
-			)
-		);
+		let res = post(&app.client, &base_url, to_string(&user).unwrap(), token);
+		assert_eq!(res.status(), Status::Conflict);
+		let error = res.into_json::<ErrorJson>().unwrap();
+		assert_eq!(error.error_code, "user.username_taken");
+		assert_eq!(error.details[0].field, "username");
 		let new_username = Username().fake::<String>();
 		user.username = new_username.clone();
 		let update_user = UpdateUser {
@@ -186,56 +159,132 @@ mod test {
 			..Default::default()
 		};
 		let url = format!("{base_url}/{user_id}");
-		let res = patch(&client, &url, to_string(&update_user).unwrap(), token);
+		let res = patch(&app.client, &url, to_string(&update_user).unwrap(), token);
 		assert_eq!(res.status(), Status::Ok);
 		let updated_user = res.into_json::<User>().unwrap();
 		assert_eq!(updated_user.username, new_username);
 		assert_eq!(updated_user.firstname, user.firstname);
-		// They should differ because the password is hashed and updated_at is updated and username is changed
+		// They should differ because updated_at is updated and username is changed
 		assert_ne!(updated_user, inserted_user);
 
-		let res = get(&client, &url, token);
+		let res = get(&app.client, &url, token);
 		assert_eq!(res.status(), Status::Ok);
-		let res = res.into_json::<User>().unwrap();
+		let body = res.into_string().unwrap();
+		// the password hash is never sent
+		assert!(
+			!body.contains("hash") && !body.contains("$argon2"),
+			"{body}"
+		);
+		let res = rocket::serde::json::from_str::<User>(&body).unwrap();
 		assert_eq!(res, user);
 		assert_eq!(res, updated_user);
 
 		// Test inserting as normal user -> should fail
-		let user_token = get_token_user(&client);
+		let user_token = app.user_token();
 		let user = generate_user();
-		let res = post(&client, &base_url, to_string(&user).unwrap(), user_token);
+		let res = post(
+			&app.client,
+			&base_url,
+			to_string(&user).unwrap(),
+			user_token,
+		);
 		assert_eq!(res.status(), Status::Forbidden);
 
 		// test deleting user
-		let res = delete(&client, &url, token);
+		let res = delete(&app.client, &url, token);
+		assert_eq!(res.status(), Status::Ok);
+	}
+
+	#[tracing_test::traced_test]
+	#[test]
+	fn user_self_update() {
+		let app = TestApp::new();
+		let (user, user_token) = app.user_with_token();
+		let url = format!("/user/{}", user.id);
+
+		let update_user = UpdateUser {
+			firstname: Some(format!("{} Jr.", user.firstname)),
+			..Default::default()
+		};
+		let res = patch(
+			&app.client,
+			&url,
+			to_string(&update_user).unwrap(),
+			&user_token,
+		);
+		assert_eq!(res.status(), Status::Ok);
+
+		// no promoting yourself
+		let update_user = UpdateUser {
+			sys_role: Some("admin".to_string()),
+			..Default::default()
+		};
+		let res = patch(
+			&app.client,
+			&url,
+			to_string(&update_user).unwrap(),
+			&user_token,
+		);
+		assert_eq!(res.status(), Status::Forbidden);
+		let res = get(&app.client, &url, app.admin_token());
+		assert_eq!(res.into_json::<User>().unwrap().sys_role, user.sys_role);
+
+		// nor editing others
+		let other_url = format!("/user/{}", app.fixture().user().create().user.id);
+		let update_user = UpdateUser {
+			firstname: Some("Mallory".to_string()),
+			..Default::default()
+		};
+		let res = patch(
+			&app.client,
+			&other_url,
+			to_string(&update_user).unwrap(),
+			&user_token,
+		);
+		assert_eq!(res.status(), Status::Forbidden);
+	}
+
+	#[tracing_test::traced_test]
+	#[test]
+	fn user_deleted_token() {
+		let app = TestApp::new();
+		let (user, user_token) = app.user_with_token();
+		let url = format!("/user/{}", user.id);
+		let res = get(&app.client, &url, &user_token);
 		assert_eq!(res.status(), Status::Ok);
+
+		let res = delete(&app.client, &url, app.admin_token());
+		assert_eq!(res.status(), Status::Ok);
+		// the token is still valid, the user is gone
+		let res = get(&app.client, &url, &user_token);
+		assert_eq!(res.status(), Status::Unauthorized);
 	}
 
 	#[tracing_test::traced_test]
 	#[test]
 	fn user_multiple() {
-		let client = Client::tracked(rocket()).unwrap();
-		let token = get_token_admin(&client);
-		let user_token = get_token_user(&client);
+		let app = TestApp::new();
+		let token = app.admin_token();
+		let user_token = app.user_token();
 		let base_url = String::from("/user");
 		let mut user_list = Vec::new();
 		for _ in 0..10 {
 			let user = generate_user();
-			let res = post(&client, &base_url, to_string(&user).unwrap(), token);
+			let res = post(&app.client, &base_url, to_string(&user).unwrap(), token);
 			assert_eq!(res.status(), Status::Ok);
 			user_list.push(res.into_json::<User>().unwrap());
 		}
 
 		// get single user from id
 		let url = format!("{base_url}/{}", user_list[0].id);
-		let res = get(&client, &url, "");
+		let res = get(&app.client, &url, "");
 		assert_eq!(res.status(), Status::Unauthorized);
-		let res = get(&client, &url, user_token);
+		let res = get(&app.client, &url, user_token);
 		assert_eq!(res.status(), Status::Ok);
 		assert_eq!(res.into_json::<User>().unwrap(), user_list[0]);
 
 		// get first page of users with page_size 5
-		let res = get(&client, "/user/page/5/0", user_token);
+		let res = get(&app.client, "/user/page/5/0", user_token);
 		assert_eq!(res.status(), Status::Ok);
 		let pagination = res.into_json::<PaginationResult<User>>().unwrap();
 		assert_eq!(pagination.page, 0);
@@ -243,7 +292,7 @@ mod test {
 
 		// get last page of users with page_size 5 with number
 		let url = format!("{base_url}/page/5/{}", pagination.num_pages - 1);
-		let res = get(&client, &url, user_token);
+		let res = get(&app.client, &url, user_token);
 		assert_eq!(res.status(), Status::Ok);
 		let pagination = res.into_json::<PaginationResult<User>>().unwrap();
 		// the last page contains 5 OR LESS items
@@ -259,7 +308,7 @@ mod test {
 		);
 
 		let url = format!("{base_url}/page/5/last");
-		let res = get(&client, &url, user_token);
+		let res = get(&app.client, &url, user_token);
 		assert_eq!(res.status(), Status::Ok);
 		let last_page = res.into_json::<PaginationResult<User>>().unwrap();
 		assert_eq!(last_page, pagination);
@@ -267,10 +316,55 @@ mod test {
 		// delete all activitys
 		for user in user_list {
 			let url = format!("{base_url}/{}", user.id);
-			let res = delete(&client, &url, user_token);
+			let res = delete(&app.client, &url, user_token);
 			assert_eq!(res.status(), Status::Forbidden);
-			let res = delete(&client, &url, token);
+			let res = delete(&app.client, &url, token);
 			assert_eq!(res.status(), Status::Ok);
 		}
 	}
+
+	#[tracing_test::traced_test]
+	#[test]
+	fn user_clear_manager() {
+		let app = TestApp::new();
+		let token = app.admin_token();
+		let manager = app.fixture().user().create();
+		let user = app.fixture().user().create();
+		let url = format!("/user/{}", user.id);
+
+		let update_user = UpdateUser {
+			manager_id: Some(Some(manager.id)),
+			..Default::default()
+		};
+		let res = patch(&app.client, &url, to_string(&update_user).unwrap(), token);
+		assert_eq!(res.status(), Status::Ok);
+		assert_eq!(
+			res.into_json::<User>().unwrap().manager_id,
+			Some(manager.id)
+		);
+
+		// a missing field leaves the manager as it is
+		let res = patch(
+			&app.client,
+			&url,
+			r#"{"firstname": "Manfred"}"#.to_string(),
+			token,
+		);
+		assert_eq!(res.status(), Status::Ok);
+		assert_eq!(
+			res.into_json::<User>().unwrap().manager_id,
+			Some(manager.id)
+		);
+
+		// null removes it
+		let res = patch(
+			&app.client,
+			&url,
+			r#"{"manager_id": null}"#.to_string(),
+			token,
+		);
+		assert_eq!(res.status(), Status::Ok);
+		let res = get(&app.client, &url, token);
+		assert_eq!(res.into_json::<User>().unwrap().manager_id, None);
+	}
 }
@@ -0,0 +1,20 @@
+//! The tables of the selected backend, `diesel print-schema` writes one file per backend
+//!
+//! `diesel.toml` belongs to MySQL, `diesel.postgres.toml` to PostgreSQL and `diesel.sqlite.toml` to
+//! SQLite, pass the latter two with `--config-file`. The files describe the same tables, only the
+//! names of the types differ.
+
+#[cfg(feature = "mysql")]
+mod mysql;
+#[cfg(feature = "mysql")]
+pub use mysql::*;
+
+#[cfg(feature = "postgres")]
+mod postgres;
+#[cfg(feature = "postgres")]
+pub use postgres::*;
+
+#[cfg(feature = "sqlite")]
+mod sqlite;
+#[cfg(feature = "sqlite")]
+pub use sqlite::*;
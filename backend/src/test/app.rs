@@ -0,0 +1,85 @@
+use std::cell::OnceCell;
+
+use rocket::{
+	figment::Figment,
+	local::blocking::Client,
+};
+
+use super::{
+	db::TestDb,
+	fixture::Fixtures,
+	token::get_token,
+};
+use crate::User;
+
+/// Rocket client of a single test, running against its own [`TestDb`]
+///
+/// ```ignore
+/// let app = TestApp::new();
+/// let project = app.fixture().project().create();
+/// let res = get(&app.client, &format!("/project/{}", project.id), app.admin_token());
+/// ```
+pub struct TestApp {
+	/// Dropped first, so its pool doesn't keep the database open
+	pub client: Client,
+	db: TestDb,
+	admin_token: OnceCell<String>,
+	user_token: OnceCell<String>,
+}
+
+impl TestApp {
+	pub fn new() -> Self {
+		Self::configured(|figment| figment)
+	}
+
+	/// Like [`TestApp::new`] with changes to the configuration, e.g. `|f| f.merge(("key", value))`
+	pub fn configured(configure: impl FnOnce(Figment) -> Figment) -> Self {
+		let rocket = crate::rocket();
+		let base_url: String = rocket
+			.figment()
+			.extract_inner("databases.zeiterfassung.url")
+			.expect("DB not configured");
+		let db = TestDb::new(&base_url);
+		let figment =
+			configure(rocket.figment().clone()).merge(("databases.zeiterfassung.url", db.url()));
+		let client = Client::tracked(rocket.configure(figment)).expect("Invalid rocket instance");
+		Self {
+			client,
+			db,
+			admin_token: OnceCell::new(),
+			user_token: OnceCell::new(),
+		}
+	}
+
+	pub fn db(&self) -> &TestDb {
+		&self.db
+	}
+
+	/// Builders for rows of the test database
+	pub fn fixture(&self) -> Fixtures<'_> {
+		Fixtures::new(self)
+	}
+
+	/// Token of an admin, created on first use
+	pub fn admin_token(&self) -> &str {
+		self.admin_token.get_or_init(|| {
+			let admin = self.fixture().user().admin().create();
+			get_token(&self.client, &admin.email, &admin.password)
+		})
+	}
+
+	/// Token of a user without any permissions, created on first use
+	pub fn user_token(&self) -> &str {
+		self.user_token.get_or_init(|| {
+			let user = self.fixture().user().create();
+			get_token(&self.client, &user.email, &user.password)
+		})
+	}
+
+	/// Creates another user without any permissions and logs in as them
+	pub fn user_with_token(&self) -> (User, String) {
+		let user = self.fixture().user().create();
+		let token = get_token(&self.client, &user.email, &user.password);
+		(user.user, token)
+	}
+}
@@ -1,83 +1,157 @@
-use std::sync::OnceLock;
-
-use diesel::{
-	prelude::*,
-	r2d2::{
-		ConnectionManager,
-		Pool,
-		PooledConnection,
-	},
+use std::sync::atomic::{
+	AtomicUsize,
+	Ordering,
 };
-use rocket::local::blocking::Client;
 
-use super::generate_user;
-use crate::{
-	auth::Tokenizer,
-	db::user::CreateUser,
+use diesel::prelude::*;
+#[cfg(not(feature = "sqlite"))]
+use diesel::sql_query;
+use diesel_migrations::MigrationHarness;
+
+use crate::db::{
+	SyncConnection,
+	MIGRATIONS,
 };
 
-pub fn db_url(client: &Client) -> &'static str {
-	static DB_URL: OnceLock<String> = OnceLock::new();
-	DB_URL.get_or_init(|| {
-		client
-			.rocket()
-			.figment()
-			.extract_inner("databases.zeiterfassung.url")
-			.unwrap()
-	})
+/// Database of a single test, created next to the configured one and dropped with the test
+///
+/// Every test gets its own migrated database, so tests can run in parallel and leave nothing
+/// behind. The name contains the process id for test runners running one process per test.
+/// With SQLite the database is a file next to the configured one, e.g. for `sqlite://zeit.sqlite`.
+pub struct TestDb {
+	#[cfg(not(feature = "sqlite"))]
+	name: String,
+	url: String,
+	#[cfg(not(feature = "sqlite"))]
+	base_url: String,
 }
 
-fn get_sync_connection(client: &Client) -> PooledConnection<ConnectionManager<MysqlConnection>> {
-	static DB_POOL: OnceLock<Pool<ConnectionManager<MysqlConnection>>> = OnceLock::new();
-	DB_POOL.get_or_init(|| {
-		let db_url = db_url(client);
-		let manager = ConnectionManager::<MysqlConnection>::new(db_url);
-		Pool::builder()
-			.build(manager)
-			.expect("Could not build connection pool")
-	});
-	DB_POOL.get().unwrap().get().unwrap()
-}
+impl TestDb {
+	/// Creates and migrates a fresh database on the server of `base_url`
+	pub fn new(base_url: &str) -> Self {
+		static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+		let (server, base_name, query) = split_url(base_url);
+		// the file of a SQLite database keeps its extension
+		let (stem, extension) = match base_name.rsplit_once('.') {
+			Some((stem, extension)) => (stem, format!(".{extension}")),
+			None => (base_name, String::new()),
+		};
+		let name = format!(
+			"{stem}_test_{}_{}{extension}",
+			std::process::id(),
+			COUNTER.fetch_add(1, Ordering::Relaxed)
+		);
+		let url = match query {
+			Some(query) => format!("{server}/{name}?{query}"),
+			None => format!("{server}/{name}"),
+		};
+		let db = Self {
+			#[cfg(not(feature = "sqlite"))]
+			name,
+			url,
+			#[cfg(not(feature = "sqlite"))]
+			base_url: base_url.to_owned(),
+		};
+
+		// SQLite creates the file on the first connection
+		#[cfg(feature = "sqlite")]
+		db.remove_files();
+		#[cfg(not(feature = "sqlite"))]
+		{
+			let mut conn = db.base_connection();
+			sql_query(format!("DROP DATABASE IF EXISTS {}", db.quoted_name()))
+				.execute(&mut conn)
+				.expect("Could not drop stale test database");
+			sql_query(format!("CREATE DATABASE {}", db.quoted_name()))
+				.execute(&mut conn)
+				.expect("Could not create test database");
+		}
+		db.connection()
+			.run_pending_migrations(MIGRATIONS)
+			.expect("Could not migrate test database");
+		db
+	}
+
+	/// Url of the test database, replacing the configured one
+	pub fn url(&self) -> &str {
+		&self.url
+	}
 
-pub fn create_user(client: &Client, item: CreateUser) -> anyhow::Result<()> {
-	use crate::schema::user::dsl::*;
+	/// New sync connection to the test database
+	pub fn connection(&self) -> SyncConnection {
+		SyncConnection::establish(&self.url).expect("Could not connect to test database")
+	}
 
-	let mut conn = get_sync_connection(client);
+	#[cfg(not(feature = "sqlite"))]
+	fn base_connection(&self) -> SyncConnection {
+		SyncConnection::establish(&self.base_url).expect("Could not connect to database")
+	}
 
-	diesel::insert_into(user)
-		.values(item)
-		.execute(&mut conn)
-		.expect("Inserting into user table failed");
-	Ok(())
+	#[cfg(not(feature = "sqlite"))]
+	fn quoted_name(&self) -> String {
+		if cfg!(feature = "postgres") {
+			format!("\"{}\"", self.name)
+		} else {
+			format!("`{}`", self.name)
+		}
+	}
 }
 
-pub fn cleanup_admin_user(client: &Client, admin_email: String) {
-	let cleanup = delete_user(client, admin_email.clone());
-	if let Err(e) = cleanup {
-		panic!("Could not cleanup for user: {admin_email}\n{e:?}");
+#[cfg(feature = "sqlite")]
+impl TestDb {
+	/// Removes the file of the SQLite database with its journals
+	fn remove_files(&self) {
+		let path = self.url.split('?').next().unwrap_or_default();
+		let path = path.strip_prefix("sqlite://").unwrap_or(path);
+		for suffix in ["", "-wal", "-shm", "-journal"] {
+			let file = format!("{path}{suffix}");
+			match std::fs::remove_file(&file) {
+				Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+					tracing::warn!("Could not remove test database {file}: {e}");
+				}
+				_ => (),
+			}
+		}
 	}
 }
 
-pub fn delete_user(client: &Client, param_email: String) -> anyhow::Result<()> {
-	use crate::schema::user::dsl::*;
-	let mut conn = get_sync_connection(client);
+#[cfg(feature = "sqlite")]
+impl Drop for TestDb {
+	fn drop(&mut self) {
+		self.remove_files();
+	}
+}
 
-	diesel::delete(user.filter(email.eq(param_email)))
-		.execute(&mut conn)
-		.expect("Deleting from user table failed");
-	Ok(())
+#[cfg(not(feature = "sqlite"))]
+impl Drop for TestDb {
+	fn drop(&mut self) {
+		// connections of other clients are still closing when the test ends
+		let force = if cfg!(feature = "postgres") {
+			" WITH (FORCE)"
+		} else {
+			""
+		};
+		let dropped = sql_query(format!(
+			"DROP DATABASE IF EXISTS {}{force}",
+			self.quoted_name()
+		))
+		.execute(&mut self.base_connection());
+		if let Err(e) = dropped {
+			tracing::warn!("Could not drop test database {}: {e}", self.name);
+		}
+	}
 }
 
-pub fn create_admin(client: &Client, password: Option<String>) -> anyhow::Result<[String; 2]> {
-	let mut admin = generate_user();
-	let admin_password = if let Some(password) = password {
-		password
-	} else {
-		String::from("Admin_01!")
+/// Splits a database url into the server, the name of the database and the parameters
+fn split_url(url: &str) -> (&str, &str, Option<&str>) {
+	let (path, query) = match url.split_once('?') {
+		Some((path, query)) => (path, Some(query)),
+		None => (url, None),
 	};
-	admin.sys_role = "admin".to_string();
-	admin.password = Tokenizer::hash_password(admin_password.as_bytes()).unwrap();
-	let admin_email = admin.email.clone();
-	create_user(client, admin).expect("Creating admin failed");
-	Ok([admin_email, admin_password])
+	let (server, name) = path
+		.rsplit_once('/')
+		.filter(|(server, name)| server.contains(':') && !name.is_empty())
+		.expect("Database url without a database name");
+	(server, name, query)
 }
@@ -0,0 +1,266 @@
+use std::ops::Deref;
+
+use diesel::prelude::*;
+use rocket::{
+	http::Status,
+	serde::json::to_string,
+};
+
+use super::{
+	app::TestApp,
+	generate_client,
+	generate_project,
+	generate_tracking,
+	generate_user,
+	methods::post,
+};
+use crate::{
+	auth::Tokenizer,
+	db::{
+		client::{
+			Client,
+			CreateClient,
+		},
+		project::{
+			CreateProject,
+			Project,
+		},
+		project_member::{
+			CreateProjectMember,
+			ROLE_MEMBER,
+		},
+		tracking::Tracking,
+		user::{
+			CreateUser,
+			User,
+		},
+		SyncConnection,
+	},
+	schema::*,
+};
+
+/// Inserts `values` into `table` of the test database and loads the inserted row as `struct`
+macro_rules! insert {
+	($conn:expr, $table:ident, $values:expr, $struct:ty) => {{
+		#[cfg(feature = "mysql")]
+		let row = diesel::insert_into($table::table)
+			.values($values)
+			.execute($conn)
+			.and_then(|_| {
+				$table::table
+					.select(<$struct>::as_select())
+					.filter($table::id.eq(crate::db::last_insert_id()))
+					.first::<$struct>($conn)
+			});
+		#[cfg(any(feature = "postgres", feature = "sqlite"))]
+		let row = diesel::insert_into($table::table)
+			.values($values)
+			.returning(<$struct>::as_returning())
+			.get_result::<$struct>($conn);
+		row.expect(concat!(
+			"Inserting into ",
+			stringify!($table),
+			" table failed"
+		))
+	}};
+}
+
+/// Typed builders for the entity graph of a [`TestApp`], client → project → user → tracking
+///
+/// Rows the built one depends on are created as well when they aren't given, e.g. a tracking
+/// without a project gets a new project of a new client and a new user as member.
+pub struct Fixtures<'a> {
+	app: &'a TestApp,
+}
+
+impl<'a> Fixtures<'a> {
+	pub(super) fn new(app: &'a TestApp) -> Self {
+		Self { app }
+	}
+
+	pub fn client(&self) -> ClientFixture<'a> {
+		ClientFixture {
+			app: self.app,
+			item: generate_client(),
+			archived: false,
+		}
+	}
+
+	pub fn project(&self) -> ProjectFixture<'a> {
+		ProjectFixture {
+			app: self.app,
+			client: None,
+			item: generate_project(),
+			members: vec![],
+		}
+	}
+
+	pub fn user(&self) -> UserFixture<'a> {
+		UserFixture {
+			app: self.app,
+			item: generate_user(),
+		}
+	}
+
+	pub fn tracking(&self) -> TrackingFixture<'a> {
+		TrackingFixture {
+			app: self.app,
+			project: None,
+			activities: None,
+		}
+	}
+}
+
+pub struct ClientFixture<'a> {
+	app: &'a TestApp,
+	item: CreateClient,
+	archived: bool,
+}
+
+impl ClientFixture<'_> {
+	pub fn name(mut self, name: &str) -> Self {
+		self.item.name = name.to_owned();
+		self
+	}
+
+	pub fn archived(mut self) -> Self {
+		self.archived = true;
+		self
+	}
+
+	pub fn create(self) -> Client {
+		let conn = &mut self.app.db().connection();
+		let new = insert!(conn, client, &self.item, Client);
+		if !self.archived {
+			return new;
+		}
+		diesel::update(client::table.find(new.id))
+			.set(client::archived.eq(true))
+			.execute(conn)
+			.expect("Archiving client failed");
+		client::table
+			.find(new.id)
+			.first(conn)
+			.expect("Reading client failed")
+	}
+}
+
+pub struct ProjectFixture<'a> {
+	app: &'a TestApp,
+	client: Option<i32>,
+	item: CreateProject,
+	members: Vec<i32>,
+}
+
+impl ProjectFixture<'_> {
+	pub fn client(mut self, client: &Client) -> Self {
+		self.client = Some(client.id);
+		self
+	}
+
+	pub fn member(mut self, user: &User) -> Self {
+		self.members.push(user.id);
+		self
+	}
+
+	pub fn create(mut self) -> Project {
+		self.item.client_id = match self.client {
+			Some(client_id) => client_id,
+			None => self.app.fixture().client().create().id,
+		};
+		let conn = &mut self.app.db().connection();
+		let new = insert!(conn, project, &self.item, Project);
+		for user_id in self.members {
+			add_member(conn, new.id, user_id);
+		}
+		new
+	}
+}
+
+/// User created by a [`UserFixture`] together with the password to log in
+pub struct TestUser {
+	pub user: User,
+	pub password: String,
+}
+
+impl Deref for TestUser {
+	type Target = User;
+
+	fn deref(&self) -> &User {
+		&self.user
+	}
+}
+
+pub struct UserFixture<'a> {
+	app: &'a TestApp,
+	item: CreateUser,
+}
+
+impl UserFixture<'_> {
+	pub fn admin(mut self) -> Self {
+		self.item.sys_role = "admin".to_owned();
+		self
+	}
+
+	pub fn create(mut self) -> TestUser {
+		let password = self.item.password.clone();
+		self.item.password = Tokenizer::hash_password(password.as_bytes()).unwrap();
+		let conn = &mut self.app.db().connection();
+		let user = insert!(conn, user, &self.item, User);
+		TestUser { user, password }
+	}
+}
+
+pub struct TrackingFixture<'a> {
+	app: &'a TestApp,
+	project: Option<Project>,
+	activities: Option<Vec<i32>>,
+}
+
+impl TrackingFixture<'_> {
+	pub fn project(mut self, project: &Project) -> Self {
+		self.project = Some(project.clone());
+		self
+	}
+
+	pub fn activities(mut self, activities: &[i32]) -> Self {
+		self.activities = Some(activities.to_vec());
+		self
+	}
+
+	/// Created through the API, so activities, tags and custom fields are stored the same way
+	/// as for any other entry
+	pub fn create(self) -> Tracking {
+		let user = self.app.fixture().user().create();
+		let project = match self.project {
+			Some(project) => project,
+			None => self.app.fixture().project().create(),
+		};
+		add_member(&mut self.app.db().connection(), project.id, user.id);
+
+		let mut item = generate_tracking(project.client_id, user.id, project.id);
+		if let Some(activities) = self.activities {
+			item.activities = activities;
+		}
+		let res = post(
+			&self.app.client,
+			"/tracking",
+			to_string(&item).unwrap(),
+			self.app.admin_token(),
+		);
+		assert_eq!(res.status(), Status::Ok, "Creating tracking failed");
+		res.into_json::<Tracking>().unwrap()
+	}
+}
+
+/// Adds a user to a project as [`ROLE_MEMBER`]
+fn add_member(conn: &mut SyncConnection, project_id: i32, user_id: i32) {
+	diesel::insert_into(project_member::table)
+		.values(CreateProjectMember {
+			project_id,
+			user_id,
+			role: ROLE_MEMBER.to_owned(),
+		})
+		.execute(conn)
+		.expect("Inserting into project_member table failed");
+}
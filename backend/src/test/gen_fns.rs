@@ -2,16 +2,20 @@ use fake::{
 	Fake,
 	Faker,
 };
+use itertools::Itertools;
 use paste::paste;
 use rand::{
 	rngs::StdRng,
+	Rng,
 	SeedableRng,
 };
 
+use crate::db::tracking::CreateTracking;
+
 macro_rules! build_faker_fn {
 	($name:tt) => {
 		paste! {
-			pub fn [<generate_ $name:lower>]() -> crate::db::[<$name:lower>]::[<Create $name>] {
+			pub fn [<generate_ $name:snake>]() -> crate::db::[<$name:snake>]::[<Create $name>] {
 				let mut rng = StdRng::from_entropy();
 				Faker.fake_with_rng(&mut rng)
 			}
@@ -21,5 +25,28 @@ macro_rules! build_faker_fn {
 
 build_faker_fn!(Activity);
 build_faker_fn!(Client);
+build_faker_fn!(ClientContact);
 build_faker_fn!(User);
 build_faker_fn!(Project);
+build_faker_fn!(Team);
+
+/// Tracking entry with random values and up to three of the seeded activities
+pub fn generate_tracking(client_id: i32, user_id: i32, project_id: i32) -> CreateTracking {
+	let mut rng = StdRng::from_entropy();
+	let activities = (0..3).map(|_| rng.gen_range(1..50)).unique().collect();
+	CreateTracking {
+		client_id,
+		user_id,
+		project_id,
+		date: Faker.fake(),
+		begin: Faker.fake(),
+		end: Faker.fake(),
+		pause: Faker.fake(),
+		performed: ((Faker.fake::<f32>() * 100.0).round() / 100.0),
+		billed: ((Faker.fake::<f32>() * 100.0).round() / 100.0),
+		description: Faker.fake(),
+		activities,
+		tags: vec![],
+		custom_fields: Default::default(),
+	}
+}
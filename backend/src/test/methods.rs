@@ -32,7 +32,6 @@ pub fn post<'b>(
 	setup(client.post(uri), Some(item), token)
 }
 
-#[allow(dead_code)]
 pub fn put<'b>(
 	client: &'b Client,
 	uri: &'b str,
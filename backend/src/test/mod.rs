@@ -1,6 +1,9 @@
+pub mod app;
 pub mod db;
+pub mod fixture;
 pub mod gen_fns;
 pub mod methods;
 pub mod token;
 
+pub use app::TestApp;
 pub use gen_fns::*;
@@ -1,6 +1,12 @@
 use std::io;
 
-use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::{
+	non_blocking::WorkerGuard,
+	rolling::{
+		RollingFileAppender,
+		Rotation,
+	},
+};
 use tracing_subscriber::{
 	filter::filter_fn,
 	fmt,
@@ -11,29 +17,56 @@ use tracing_subscriber::{
 	EnvFilter,
 };
 
-pub fn init() -> WorkerGuard {
-	let dir = "/var/log/zeiterfassung";
-
-	let file_appender = tracing_appender::rolling::daily(dir, "server.log");
-	let (non_blocking, guard) = tracing_appender::non_blocking(file_appender);
+use crate::{
+	config::{
+		LogFormat,
+		LogRotation,
+		LoggingConfig,
+	},
+	Error,
+	Result,
+};
 
+/// Logs to stdout and, unless [`LoggingConfig::stdout_only`], to JSON files in [`LoggingConfig::dir`]
+///
+/// The returned guard flushes the files when dropped, keep it until the end of `main`
+pub fn init(config: &LoggingConfig) -> Result<Option<WorkerGuard>> {
 	let filter_bin =
 		filter_fn(|metadata: &tracing::Metadata<'_>| metadata.target().starts_with("zeiterfassung"));
 
+	let stdout = fmt::Layer::new().with_writer(io::stdout);
+	let stdout = match config.format {
+		LogFormat::Pretty => stdout.pretty().boxed(),
+		LogFormat::Compact => stdout.compact().boxed(),
+		LogFormat::Json => stdout.json().boxed(),
+	};
+
+	let (file, guard) = if config.stdout_only {
+		(None, None)
+	} else {
+		let rotation = match config.rotation {
+			LogRotation::Minutely => Rotation::MINUTELY,
+			LogRotation::Hourly => Rotation::HOURLY,
+			LogRotation::Daily => Rotation::DAILY,
+			LogRotation::Never => Rotation::NEVER,
+		};
+		let file_appender = RollingFileAppender::builder()
+			.rotation(rotation)
+			.filename_prefix("server.log")
+			.build(&config.dir)
+			.map_err(|e| Error::LogDir(config.dir.clone(), e))?;
+		let (non_blocking, guard) = tracing_appender::non_blocking(file_appender);
+		let file = fmt::Layer::new()
+			.with_writer(non_blocking)
+			.json()
+			.with_filter(filter_bin.clone());
+		(Some(file), Some(guard))
+	};
+
 	let subscriber = tracing_subscriber::registry()
 		.with(EnvFilter::from_default_env().add_directive(tracing::Level::TRACE.into()))
-		.with(
-			fmt::Layer::new()
-				.with_writer(io::stdout)
-				.pretty()
-				.with_filter(filter_bin.clone()),
-		)
-		.with(
-			fmt::Layer::new()
-				.with_writer(non_blocking)
-				.json()
-				.with_filter(filter_bin),
-		);
+		.with(stdout.with_filter(filter_bin))
+		.with(file);
 	tracing::subscriber::set_global_default(subscriber).expect("Unable to set a global subscriber");
-	guard
+	Ok(guard)
 }
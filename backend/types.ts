@@ -0,0 +1,731 @@
+/*
+ Generated by typeshare 1.13.4
+*/
+
+/** Values of the custom fields of a single row, keyed by the name of the field */
+export type CustomFields = Record<string, unknown>;
+
+/** Percentages of a budget that raise an alert once they are reached, a JSON array in the database */
+export type Thresholds = number[];
+
+/** Struct representing a row in table `activity` */
+export interface Activity {
+	/** Field representing column `id` */
+	id: number;
+	/** Field representing column `token` */
+	token?: string;
+	/** Field representing column `name` */
+	name: string;
+	/** Field representing column `created_at` */
+	created_at: string;
+	/** Field representing column `updated_at` */
+	updated_at: string;
+	/** Field representing column `deleted_at` */
+	deleted_at?: string;
+	/** Field representing column `billable`, entries with non-billable activities only are left out of billing */
+	billable: boolean;
+	/** Field representing column `category` */
+	category?: string;
+}
+
+/** A single entry of the audit log as it is returned by the API */
+export interface Audit {
+	id: number;
+	/** The user that made the change */
+	user_id: number;
+	/** Name of the table that got changed */
+	entity: string;
+	/** Primary key of the changed row */
+	entity_id: number;
+	action: Action;
+	/** Changed fields before the change, `null` on create */
+	old_value?: unknown;
+	/** Changed fields after the change, `null` on purge */
+	new_value?: unknown;
+	created_at: string;
+}
+
+/**
+ * Billable hours of a project in a period
+ * 
+ * Entries with non-billable activities only are never billed and only counted in `excluded_*`,
+ * see [`not_billed`]
+ */
+export interface BillingReport {
+	project_id: number;
+	from: string;
+	to: string;
+	entries: number;
+	performed: number;
+	billed: number;
+	excluded_entries: number;
+	excluded_billed: number;
+}
+
+/** Struct representing a row in table `project_budget` */
+export interface ProjectBudget {
+	/** Field representing column `id` */
+	id: number;
+	/** Field representing column `project_id` */
+	project_id: number;
+	/** Field representing column `activity_id`, the budget covers the whole project if `None` */
+	activity_id?: number;
+	/** Field representing column `unit`, either [`UNIT_HOURS`] or [`UNIT_MONEY`] */
+	unit: string;
+	/** Field representing column `amount`, hours or money with two decimal places */
+	amount: string;
+	/** Field representing column `hourly_rate`, required for [`UNIT_MONEY`] */
+	hourly_rate?: string;
+	/** Field representing column `thresholds`, percentages e.g. `[80, 100]` */
+	thresholds: Thresholds;
+	/** Field representing column `hard_stop`, rejects new tracking entries once the budget is used up */
+	hard_stop: boolean;
+	/** Field representing column `created_at` */
+	created_at: string;
+	/** Field representing column `updated_at` */
+	updated_at: string;
+}
+
+/** How much of a [`ProjectBudget`] is used up by the tracking entries of its project */
+export interface BudgetConsumption {
+	budget: ProjectBudget;
+	/** Sum of `tracking.performed` in hours */
+	performed: number;
+	/** Sum of `tracking.billed` in hours */
+	billed: number;
+	/** Billed hours converted to the unit of the budget */
+	consumed: string;
+	/** `consumed` relative to the budget's `amount` */
+	percentage: number;
+	/** All configured thresholds that `percentage` has reached */
+	reached_thresholds: number[];
+	/** Nothing is left of the budget */
+	exhausted: boolean;
+}
+
+/** Struct representing a row in table `client` */
+export interface Client {
+	/** Field representing column `id` */
+	id: number;
+	/** Field representing column `name` */
+	name: string;
+	/** Field representing column `created_at` */
+	created_at: string;
+	/** Field representing column `updated_at` */
+	updated_at: string;
+	/** Field representing column `deleted_at` */
+	deleted_at?: string;
+	/** Field representing column `archived` */
+	archived: boolean;
+	/** Field representing column `customer_number` */
+	customer_number?: string;
+	/** Field representing column `billing_street` */
+	billing_street?: string;
+	/** Field representing column `billing_zip` */
+	billing_zip?: string;
+	/** Field representing column `billing_city` */
+	billing_city?: string;
+	/** Field representing column `billing_country` */
+	billing_country?: string;
+	/** Field representing column `vat_id` */
+	vat_id?: string;
+	/** Field representing column `currency`, ISO 4217 code e.g. `EUR` */
+	currency: string;
+	/** Field representing column `payment_terms`, days until an invoice is due */
+	payment_terms: number;
+}
+
+/** Struct representing a row in table `client_contact` */
+export interface ClientContact {
+	/** Field representing column `id` */
+	id: number;
+	/** Field representing column `client_id` */
+	client_id: number;
+	/** Field representing column `name` */
+	name: string;
+	/** Field representing column `email` */
+	email?: string;
+	/** Field representing column `phone` */
+	phone?: string;
+	/** Field representing column `position` */
+	position?: string;
+	/** Field representing column `created_at` */
+	created_at: string;
+	/** Field representing column `updated_at` */
+	updated_at: string;
+}
+
+/** Create Struct for a row in table `activity` for [`Activity`] */
+export interface CreateActivity {
+	/** Field representing column `token` */
+	token?: string;
+	/** Field representing column `name` */
+	name: string;
+	/** Field representing column `billable` */
+	billable: boolean;
+	/** Field representing column `category` */
+	category?: string;
+}
+
+/** Create Struct for a row in table `client` for [`Client`] */
+export interface CreateClient {
+	/** Field representing column `name` */
+	name: string;
+	/** Field representing column `customer_number` */
+	customer_number?: string;
+	/** Field representing column `billing_street` */
+	billing_street?: string;
+	/** Field representing column `billing_zip` */
+	billing_zip?: string;
+	/** Field representing column `billing_city` */
+	billing_city?: string;
+	/** Field representing column `billing_country` */
+	billing_country?: string;
+	/** Field representing column `vat_id` */
+	vat_id?: string;
+	/** Field representing column `currency` */
+	currency: string;
+	/** Field representing column `payment_terms` */
+	payment_terms: number;
+}
+
+/** Create Struct for a row in table `client_contact` for [`ClientContact`] */
+export interface CreateClientContact {
+	/** Field representing column `client_id`, taken from the url */
+	client_id: number;
+	/** Field representing column `name` */
+	name: string;
+	/** Field representing column `email` */
+	email?: string;
+	/** Field representing column `phone` */
+	phone?: string;
+	/** Field representing column `position` */
+	position?: string;
+}
+
+/** Create Struct for a row in table `custom_field` for [`CustomField`] */
+export interface CreateCustomField {
+	/** Field representing column `entity` */
+	entity: string;
+	/** Field representing column `name` */
+	name: string;
+	/** Field representing column `field_type` */
+	field_type: string;
+	/** Field representing column `options` */
+	options: string[];
+	/** Field representing column `required` */
+	required?: boolean;
+}
+
+/** Create Struct for a row in table `project` for [`Project`] */
+export interface CreateProject {
+	/** Field representing column `client_id` */
+	client_id: number;
+	/** Field representing column `name` */
+	name: string;
+	/** Field representing column `start_date` */
+	start_date?: string;
+	/** Field representing column `end_date` */
+	end_date?: string;
+}
+
+/** Create Struct for a row in table `project_budget` for [`ProjectBudget`] */
+export interface CreateProjectBudget {
+	/** Field representing column `project_id`, taken from the url */
+	project_id: number;
+	/** Field representing column `activity_id` */
+	activity_id?: number;
+	/** Field representing column `unit` */
+	unit: string;
+	/** Field representing column `amount` */
+	amount: string;
+	/** Field representing column `hourly_rate` */
+	hourly_rate?: string;
+	/** Field representing column `thresholds` */
+	thresholds: Thresholds;
+	/** Field representing column `hard_stop` */
+	hard_stop?: boolean;
+}
+
+/** Create Struct for a row in table `project_member` for [`ProjectMember`] */
+export interface CreateProjectMember {
+	/** Field representing column `project_id`, taken from the url */
+	project_id: number;
+	/** Field representing column `user_id` */
+	user_id: number;
+	/** Field representing column `role` */
+	role: string;
+}
+
+/** Create Struct for a row in table `team` for [`Team`] */
+export interface CreateTeam {
+	/** Field representing column `name` */
+	name: string;
+	/** Field representing column `manager_id` */
+	manager_id?: number;
+}
+
+export interface CreateTracking {
+	client_id: number;
+	user_id: number;
+	project_id: number;
+	date: string;
+	begin: string;
+	end: string;
+	pause?: string;
+	performed: number;
+	billed: number;
+	description?: string;
+	activities: number[];
+	/** Names of the tags, tags that don't exist yet are created */
+	tags?: string[];
+	custom_fields?: CustomFields;
+}
+
+/** Create Struct for a row in table `user` for [`User`] */
+export interface CreateUser {
+	/** Field representing column `username` */
+	username: string;
+	/** Field representing column `firstname` */
+	firstname: string;
+	/** Field representing column `lastname` */
+	lastname: string;
+	/** Field representing column `email` */
+	email: string;
+	/** Field representing column `hash` */
+	password: string;
+	/** Field representing column `sys_role` */
+	sys_role: string;
+	/** Field representing column `manager_id` */
+	manager_id?: number;
+}
+
+/**
+ * Result of a `.cursor` function, a page of a keyset pagination
+ * 
+ * `next` and `prev` are passed back as `?after=` to get the following or the preceding page,
+ * they are `None` at the ends of the list
+ */
+export interface CursorPage<T> {
+	/** Items of the page, ordered by `id` */
+	items: T[];
+	/** Cursor of the following page */
+	next?: string;
+	/** Cursor of the preceding page */
+	prev?: string;
+	/** The count of total items there are, only if asked for since it needs a full count */
+	total_items?: number;
+}
+
+/** Struct representing a row in table `custom_field` */
+export interface CustomField {
+	/** Field representing column `id` */
+	id: number;
+	/** Field representing column `entity`, one of `client`, `project` and `tracking` */
+	entity: string;
+	/** Field representing column `name` */
+	name: string;
+	/** Field representing column `field_type`, one of `text`, `number`, `date` and `select` */
+	field_type: string;
+	/** Field representing column `options`, the allowed values of a `select` field */
+	options: string[];
+	/** Field representing column `required` */
+	required: boolean;
+	/** Field representing column `created_at` */
+	created_at: string;
+	/** Field representing column `updated_at` */
+	updated_at: string;
+}
+
+/** Hours of all entries sharing the same value of a custom field */
+export interface CustomFieldReport {
+	/** Value of the field, `None` for entries without a value */
+	value?: unknown;
+	/** Number of tracking entries */
+	entries: number;
+	performed: number;
+	billed: number;
+}
+
+/** A message about a single field of the request */
+export interface ErrorDetail {
+	field: string;
+	/** Stable identifier of the problem, e.g. `taken` or `does_not_exist` */
+	code: string;
+	message: string;
+}
+
+export interface ErrorJson {
+	/** Human readable message */
+	error: string;
+	/** HTTP status */
+	code: number;
+	/** Stable identifier of the error, e.g. `client.name_taken` or `fk.project_missing` */
+	error_code: string;
+	details: ErrorDetail[];
+	/** Id of the request in the server logs, also sent as `X-Request-Id` header */
+	request_id?: string;
+}
+
+export interface Health {
+	status: string;
+}
+
+/**
+ * Sort, search and filter parameters shared by every list endpoint
+ * 
+ * `?sort=name,-created_at&q=acme&filter[archived]=false`, only the fields whitelisted with
+ * [`list_query!`] for the entity may be used in `sort` and `filter`
+ */
+export interface ListQuery {
+	/** Comma separated fields, a leading `-` sorts descending */
+	sort?: string;
+	/** Case insensitive search in the text fields of the entity */
+	q?: string;
+	/** Exact values of fields, custom fields of clients, projects and entries as `custom_field_<id>` */
+	filter: Record<string, string>;
+}
+
+export interface Login {
+	email: string;
+	password: string;
+}
+
+/** Result of a `.paginate` function */
+export interface PaginationResult<T> {
+	/** Resulting items that are from the current page */
+	items: T[];
+	/** The count of total items there are */
+	total_items: number;
+	/** Current page, 0-based index */
+	page: number;
+	/** Size of a page */
+	page_size: number;
+	/** Number of total possible pages, given the `page_size` and `total_items` */
+	num_pages: number;
+}
+
+/** Struct representing a row in table `project` */
+export interface Project {
+	/** Field representing column `id` */
+	id: number;
+	/** Field representing column `client_id` */
+	client_id: number;
+	/** Field representing column `name` */
+	name: string;
+	/** Field representing column `created_at` */
+	created_at: string;
+	/** Field representing column `updated_at` */
+	updated_at: string;
+	/** Field representing column `deleted_at` */
+	deleted_at?: string;
+	/** Field representing column `archived` */
+	archived: boolean;
+	/** Field representing column `start_date` */
+	start_date?: string;
+	/** Field representing column `end_date` */
+	end_date?: string;
+}
+
+/** Struct representing a row in table `project_member` */
+export interface ProjectMember {
+	/** Field representing column `id` */
+	id: number;
+	/** Field representing column `project_id` */
+	project_id: number;
+	/** Field representing column `user_id` */
+	user_id: number;
+	/** Field representing column `role`, either [`ROLE_MEMBER`] or [`ROLE_LEAD`] */
+	role: string;
+	/** Field representing column `created_at` */
+	created_at: string;
+	/** Field representing column `updated_at` */
+	updated_at: string;
+}
+
+export interface Readiness {
+	/** Whether requests can be served, `database` is reachable and nothing is pending */
+	ready: boolean;
+	database: boolean;
+	pending_migrations: string[];
+}
+
+/**
+ * Hours of all entries carrying the same tag in a period
+ * 
+ * An entry with several tags is part of the group of each of its tags
+ */
+export interface TagReport {
+	/** Name of the tag, `None` for entries without any tag */
+	tag?: string;
+	/** Number of tracking entries */
+	entries: number;
+	performed: number;
+	billed: number;
+}
+
+/** Struct representing a row in table `team` */
+export interface Team {
+	/** Field representing column `id` */
+	id: number;
+	/** Field representing column `name` */
+	name: string;
+	/** Field representing column `manager_id` */
+	manager_id?: number;
+	/** Field representing column `created_at` */
+	created_at: string;
+	/** Field representing column `updated_at` */
+	updated_at: string;
+}
+
+/** Struct representing a row in table `team_member` */
+export interface TeamMember {
+	/** Field representing column `id` */
+	id: number;
+	/** Field representing column `team_id` */
+	team_id: number;
+	/** Field representing column `user_id` */
+	user_id: number;
+	/** Field representing column `created_at` */
+	created_at: string;
+}
+
+/** Hours booked by a single member of a team in a period */
+export interface TeamReport {
+	user_id: number;
+	/** Number of tracking entries */
+	entries: number;
+	performed: number;
+	billed: number;
+}
+
+export interface Token {
+	token: string;
+}
+
+export interface Tracking {
+	id: number;
+	client_id: number;
+	user_id: number;
+	project_id: number;
+	date: string;
+	begin: string;
+	end: string;
+	pause?: string;
+	performed: number;
+	billed: number;
+	description?: string;
+	created_at: string;
+	updated_at: string;
+	/** User that approved the entry, either an admin or a lead of the project */
+	approved_by?: number;
+	approved_at?: string;
+	activities: number[];
+	/** Names of the tags, sorted by name */
+	tags: string[];
+	custom_fields: CustomFields;
+}
+
+/** Update Struct for a row in table `activity` for [`Activity`] */
+export interface UpdateActivity {
+	/** Field representing column `token` */
+	token?: string | null;
+	/** Field representing column `name` */
+	name?: string;
+	/** Field representing column `billable` */
+	billable?: boolean;
+	/** Field representing column `category` */
+	category?: string | null;
+	/** Field representing column `created_at` */
+	created_at?: string;
+	/** Field representing column `updated_at` */
+	updated_at?: string;
+}
+
+/** Update Struct for a row in table `client` for [`Client`] */
+export interface UpdateClient {
+	/** Field representing column `name` */
+	name?: string;
+	/** Field representing column `archived` */
+	archived?: boolean;
+	/** Field representing column `customer_number` */
+	customer_number?: string | null;
+	/** Field representing column `billing_street` */
+	billing_street?: string | null;
+	/** Field representing column `billing_zip` */
+	billing_zip?: string | null;
+	/** Field representing column `billing_city` */
+	billing_city?: string | null;
+	/** Field representing column `billing_country` */
+	billing_country?: string | null;
+	/** Field representing column `vat_id` */
+	vat_id?: string | null;
+	/** Field representing column `currency` */
+	currency?: string;
+	/** Field representing column `payment_terms` */
+	payment_terms?: number;
+	/** Field representing column `created_at` */
+	created_at?: string;
+	/** Field representing column `updated_at` */
+	updated_at?: string;
+}
+
+/** Update Struct for a row in table `client_contact` for [`ClientContact`] */
+export interface UpdateClientContact {
+	/** Field representing column `name` */
+	name?: string;
+	/** Field representing column `email` */
+	email?: string | null;
+	/** Field representing column `phone` */
+	phone?: string | null;
+	/** Field representing column `position` */
+	position?: string | null;
+}
+
+/**
+ * Update Struct for a row in table `custom_field` for [`CustomField`]
+ * 
+ * Entity and type are fixed, changing them would invalidate the stored values
+ */
+export interface UpdateCustomField {
+	/** Field representing column `name` */
+	name?: string;
+	/** Field representing column `options` */
+	options?: string[];
+	/** Field representing column `required` */
+	required?: boolean;
+}
+
+/** Update Struct for a row in table `project` for [`Project`] */
+export interface UpdateProject {
+	/** Field representing column `name` */
+	name?: string;
+	/** Field representing column `archived` */
+	archived?: boolean;
+	/** Field representing column `start_date` */
+	start_date?: string | null;
+	/** Field representing column `end_date` */
+	end_date?: string | null;
+	/** Field representing column `created_at` */
+	created_at?: string;
+	/** Field representing column `updated_at` */
+	updated_at?: string;
+}
+
+/** Update Struct for a row in table `project_budget` for [`ProjectBudget`] */
+export interface UpdateProjectBudget {
+	/** Field representing column `activity_id` */
+	activity_id?: number | null;
+	/** Field representing column `unit` */
+	unit?: string;
+	/** Field representing column `amount` */
+	amount?: string;
+	/** Field representing column `hourly_rate` */
+	hourly_rate?: string | null;
+	/** Field representing column `thresholds` */
+	thresholds?: Thresholds;
+	/** Field representing column `hard_stop` */
+	hard_stop?: boolean;
+}
+
+/** Update Struct for a row in table `project_member` for [`ProjectMember`] */
+export interface UpdateProjectMember {
+	/** Field representing column `role` */
+	role?: string;
+}
+
+/** Update Struct for a row in table `team` for [`Team`] */
+export interface UpdateTeam {
+	/** Field representing column `name` */
+	name?: string;
+	/** Field representing column `manager_id` */
+	manager_id?: number | null;
+}
+
+export interface UpdateTracking {
+	client_id?: number;
+	user_id?: number;
+	project_id?: number;
+	date?: string;
+	begin?: string;
+	end?: string;
+	pause?: string | null;
+	performed?: number;
+	billed?: number;
+	description?: string;
+	created_at?: string;
+	updated_at?: string;
+	activities?: number[];
+	tags?: string[];
+	/** Only the given fields are changed, `null` removes a value */
+	custom_fields?: CustomFields;
+}
+
+/** Update Struct for a row in table `user` for [`User`] */
+export interface UpdateUser {
+	/** Field representing column `username` */
+	username?: string;
+	/** Field representing column `firstname` */
+	firstname?: string;
+	/** Field representing column `lastname` */
+	lastname?: string;
+	/** Field representing column `email` */
+	email?: string;
+	/** Field representing column `hash` */
+	password?: string;
+	/** Field representing column `sys_role` */
+	sys_role?: string;
+	/** Field representing column `manager_id` */
+	manager_id?: number | null;
+	/** Field representing column `created_at` */
+	created_at?: string;
+	/** Field representing column `updated_at` */
+	updated_at?: string;
+}
+
+/** Struct representing a row in table `user` */
+export interface User {
+	/** Field representing column `id` */
+	id: number;
+	/** Field representing column `username` */
+	username: string;
+	/** Field representing column `firstname` */
+	firstname: string;
+	/** Field representing column `lastname` */
+	lastname: string;
+	/** Field representing column `email` */
+	email: string;
+	/** Field representing column `sys_role` */
+	sys_role: string;
+	/** Field representing column `created_at` */
+	created_at: string;
+	/** Field representing column `updated_at` */
+	updated_at: string;
+	/** Field representing column `deleted_at` */
+	deleted_at?: string;
+	/** Field representing column `manager_id` */
+	manager_id?: number;
+}
+
+export interface Version {
+	/** Version of the backend crate */
+	version: string;
+	/** Short hash of the commit the backend was built from, `unknown` without git */
+	commit: string;
+	/** Migrations that are not applied yet, missing if the database can't be reached */
+	pending_migrations?: string[];
+}
+
+/** Kind of change that got recorded */
+export enum Action {
+	Create = "create",
+	Update = "update",
+	/** Soft delete */
+	Delete = "delete",
+	Restore = "restore",
+	/** Hard delete */
+	Purge = "purge",
+	/** All references were moved to another row, `new_value` holds the target */
+	Merge = "merge",
+}
+
@@ -0,0 +1,14 @@
+CREATE TABLE audit_log (
+	id INTEGER AUTO_INCREMENT NOT NULL,
+	user_id INTEGER NOT NULL,
+	entity VARCHAR(50) NOT NULL,
+	entity_id INTEGER NOT NULL,
+	action VARCHAR(20) NOT NULL,
+	old_value TEXT,
+	new_value TEXT,
+	created_at TIMESTAMP NOT NULL
+		DEFAULT CURRENT_TIMESTAMP,
+	PRIMARY KEY (id),
+	INDEX (entity, entity_id),
+	INDEX (user_id)
+);
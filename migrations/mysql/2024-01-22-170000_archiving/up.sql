@@ -0,0 +1,8 @@
+ALTER TABLE client
+	ADD COLUMN archived BOOLEAN NOT NULL
+		DEFAULT FALSE;
+ALTER TABLE project
+	ADD COLUMN archived BOOLEAN NOT NULL
+		DEFAULT FALSE,
+	ADD COLUMN start_date DATE,
+	ADD COLUMN end_date DATE;
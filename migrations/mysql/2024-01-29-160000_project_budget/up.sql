@@ -0,0 +1,23 @@
+CREATE TABLE project_budget (
+	id INTEGER AUTO_INCREMENT NOT NULL,
+	project_id INTEGER NOT NULL,
+	activity_id INTEGER,
+	unit VARCHAR(10) NOT NULL
+		DEFAULT 'hours',
+	amount DOUBLE NOT NULL,
+	hourly_rate DOUBLE,
+	thresholds VARCHAR(255) NOT NULL
+		DEFAULT '80,100',
+	hard_stop BOOLEAN NOT NULL
+		DEFAULT FALSE,
+	created_at TIMESTAMP NOT NULL
+		DEFAULT CURRENT_TIMESTAMP,
+	updated_at TIMESTAMP NOT NULL
+		DEFAULT CURRENT_TIMESTAMP
+		ON UPDATE CURRENT_TIMESTAMP,
+	PRIMARY KEY (id),
+	FOREIGN KEY (project_id)
+		REFERENCES project(id),
+	FOREIGN KEY (activity_id)
+		REFERENCES activity(id)
+);
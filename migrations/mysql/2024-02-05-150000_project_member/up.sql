@@ -0,0 +1,28 @@
+CREATE TABLE project_member (
+	id INTEGER AUTO_INCREMENT NOT NULL,
+	project_id INTEGER NOT NULL,
+	user_id INTEGER NOT NULL,
+	role VARCHAR(20) NOT NULL
+		DEFAULT 'member',
+	created_at TIMESTAMP NOT NULL
+		DEFAULT CURRENT_TIMESTAMP,
+	updated_at TIMESTAMP NOT NULL
+		DEFAULT CURRENT_TIMESTAMP
+		ON UPDATE CURRENT_TIMESTAMP,
+	PRIMARY KEY (id),
+	UNIQUE (project_id, user_id),
+	FOREIGN KEY (project_id)
+		REFERENCES project(id),
+	FOREIGN KEY (user_id)
+		REFERENCES user(id)
+);
+
+-- everybody who already booked on a project keeps being allowed to
+INSERT INTO project_member (project_id, user_id)
+	SELECT DISTINCT project_id, user_id FROM tracking;
+
+ALTER TABLE tracking
+	ADD COLUMN approved_by INTEGER,
+	ADD COLUMN approved_at TIMESTAMP NULL,
+	ADD CONSTRAINT tracking_approved_by_fk FOREIGN KEY (approved_by)
+		REFERENCES user(id);
@@ -0,0 +1,33 @@
+ALTER TABLE user
+	ADD COLUMN manager_id INTEGER,
+	ADD CONSTRAINT user_manager_id_fk FOREIGN KEY (manager_id)
+		REFERENCES user(id);
+
+CREATE TABLE team (
+	id INTEGER AUTO_INCREMENT NOT NULL,
+	name VARCHAR(255) NOT NULL,
+	manager_id INTEGER,
+	created_at TIMESTAMP NOT NULL
+		DEFAULT CURRENT_TIMESTAMP,
+	updated_at TIMESTAMP NOT NULL
+		DEFAULT CURRENT_TIMESTAMP
+		ON UPDATE CURRENT_TIMESTAMP,
+	PRIMARY KEY (id),
+	UNIQUE (name),
+	FOREIGN KEY (manager_id)
+		REFERENCES user(id)
+);
+
+CREATE TABLE team_member (
+	id INTEGER AUTO_INCREMENT NOT NULL,
+	team_id INTEGER NOT NULL,
+	user_id INTEGER NOT NULL,
+	created_at TIMESTAMP NOT NULL
+		DEFAULT CURRENT_TIMESTAMP,
+	PRIMARY KEY (id),
+	UNIQUE (team_id, user_id),
+	FOREIGN KEY (team_id)
+		REFERENCES team(id),
+	FOREIGN KEY (user_id)
+		REFERENCES user(id)
+);
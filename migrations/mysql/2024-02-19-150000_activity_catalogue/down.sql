@@ -0,0 +1,5 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE project_activity;
+ALTER TABLE activity
+	DROP COLUMN billable,
+	DROP COLUMN category;
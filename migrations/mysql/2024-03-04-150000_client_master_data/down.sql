@@ -0,0 +1,12 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE client_contact;
+ALTER TABLE client
+	DROP INDEX customer_number,
+	DROP COLUMN customer_number,
+	DROP COLUMN billing_street,
+	DROP COLUMN billing_zip,
+	DROP COLUMN billing_city,
+	DROP COLUMN billing_country,
+	DROP COLUMN vat_id,
+	DROP COLUMN currency,
+	DROP COLUMN payment_terms;
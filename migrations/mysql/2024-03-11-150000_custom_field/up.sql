@@ -0,0 +1,27 @@
+CREATE TABLE custom_field (
+	id INTEGER AUTO_INCREMENT NOT NULL,
+	entity VARCHAR(20) NOT NULL,
+	name VARCHAR(255) NOT NULL,
+	field_type VARCHAR(20) NOT NULL,
+	options TEXT NOT NULL,
+	required BOOLEAN NOT NULL
+		DEFAULT FALSE,
+	created_at TIMESTAMP NOT NULL
+		DEFAULT CURRENT_TIMESTAMP,
+	updated_at TIMESTAMP NOT NULL
+		DEFAULT CURRENT_TIMESTAMP
+		ON UPDATE CURRENT_TIMESTAMP,
+	PRIMARY KEY (id),
+	UNIQUE (entity, name)
+);
+
+CREATE TABLE custom_field_value (
+	id INTEGER AUTO_INCREMENT NOT NULL,
+	custom_field_id INTEGER NOT NULL,
+	entity_id INTEGER NOT NULL,
+	value TEXT NOT NULL,
+	PRIMARY KEY (id),
+	UNIQUE (custom_field_id, entity_id),
+	FOREIGN KEY (custom_field_id)
+		REFERENCES custom_field(id)
+);
@@ -0,0 +1,11 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE project_budget
+	ADD COLUMN threshold_list VARCHAR(255);
+UPDATE project_budget
+	SET threshold_list = REPLACE(REPLACE(REPLACE(CAST(thresholds AS CHAR), '[', ''), ']', ''), ' ', '');
+ALTER TABLE project_budget
+	DROP COLUMN thresholds,
+	CHANGE threshold_list thresholds VARCHAR(255) NOT NULL
+		DEFAULT '80,100',
+	MODIFY amount DOUBLE NOT NULL,
+	MODIFY hourly_rate DOUBLE;
@@ -0,0 +1,11 @@
+-- money is stored exactly, the thresholds as a JSON array of percentages
+ALTER TABLE project_budget
+	MODIFY amount DECIMAL(12, 2) NOT NULL,
+	MODIFY hourly_rate DECIMAL(12, 2),
+	ADD COLUMN threshold_list JSON;
+UPDATE project_budget
+	SET threshold_list = CAST(CONCAT('[', thresholds, ']') AS JSON);
+ALTER TABLE project_budget
+	DROP COLUMN thresholds,
+	CHANGE threshold_list thresholds JSON NOT NULL
+		DEFAULT (JSON_ARRAY(80, 100));
@@ -0,0 +1,12 @@
+CREATE TABLE client (
+	id SERIAL NOT NULL,
+	name VARCHAR(255) NOT NULL,
+	created_at TIMESTAMP NOT NULL
+		DEFAULT CURRENT_TIMESTAMP,
+	updated_at TIMESTAMP NOT NULL
+		DEFAULT CURRENT_TIMESTAMP,
+	UNIQUE (name),
+	PRIMARY KEY (id)
+);
+
+SELECT diesel_manage_updated_at('client');
@@ -0,0 +1,14 @@
+CREATE TABLE project (
+	id SERIAL NOT NULL,
+	client_id INTEGER NOT NULL,
+	name VARCHAR(255) NOT NULL,
+	created_at TIMESTAMP NOT NULL
+		DEFAULT CURRENT_TIMESTAMP,
+	updated_at TIMESTAMP NOT NULL
+		DEFAULT CURRENT_TIMESTAMP,
+	PRIMARY KEY (id),
+	FOREIGN KEY (client_id)
+		REFERENCES client(id)
+);
+
+SELECT diesel_manage_updated_at('project');
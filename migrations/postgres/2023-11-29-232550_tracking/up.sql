@@ -0,0 +1,26 @@
+CREATE TABLE tracking (
+	id SERIAL NOT NULL,
+	client_id INTEGER NOT NULL,
+	user_id INTEGER NOT NULL,
+	project_id INTEGER NOT NULL,
+	date DATE NOT NULL,
+	begin TIME NOT NULL,
+	"end" TIME NOT NULL,
+	pause TIME,
+	performed REAL NOT NULL,
+	billed REAL NOT NULL,
+	description TEXT,
+	created_at TIMESTAMP NOT NULL
+		DEFAULT CURRENT_TIMESTAMP,
+	updated_at TIMESTAMP NOT NULL
+		DEFAULT CURRENT_TIMESTAMP,
+	PRIMARY KEY (id),
+	FOREIGN KEY (client_id)
+		REFERENCES client(id),
+	FOREIGN KEY (user_id)
+		REFERENCES "user"(id),
+	FOREIGN KEY (project_id)
+		REFERENCES project(id)
+);
+
+SELECT diesel_manage_updated_at('tracking');
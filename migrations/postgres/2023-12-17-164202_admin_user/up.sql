@@ -0,0 +1,3 @@
+-- Your SQL goes here
+INSERT INTO "user"(username, firstname, lastname, email, hash, sys_role) VALUES
+	('admin', 'Adniel', 'Admin', 'admin@localhost', '$argon2id$v=19$m=4096,t=3,p=1$KO4tyOJKagkuD6BupAD0JQ$uavL0LtwpoxiwIK2qBtdS0b1sC/lS+QDk8cbs8PAQWQ', 'admin');
@@ -0,0 +1,15 @@
+CREATE TABLE audit_log (
+	id SERIAL NOT NULL,
+	user_id INTEGER NOT NULL,
+	entity VARCHAR(50) NOT NULL,
+	entity_id INTEGER NOT NULL,
+	action VARCHAR(20) NOT NULL,
+	old_value TEXT,
+	new_value TEXT,
+	created_at TIMESTAMP NOT NULL
+		DEFAULT CURRENT_TIMESTAMP,
+	PRIMARY KEY (id)
+);
+
+CREATE INDEX audit_log_entity ON audit_log (entity, entity_id);
+CREATE INDEX audit_log_user_id ON audit_log (user_id);
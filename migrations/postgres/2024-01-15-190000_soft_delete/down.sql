@@ -0,0 +1,5 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE "user" DROP COLUMN deleted_at;
+ALTER TABLE client DROP COLUMN deleted_at;
+ALTER TABLE project DROP COLUMN deleted_at;
+ALTER TABLE activity DROP COLUMN deleted_at;
@@ -0,0 +1,12 @@
+ALTER TABLE "user"
+	ADD COLUMN deleted_at TIMESTAMP NULL
+		DEFAULT NULL;
+ALTER TABLE client
+	ADD COLUMN deleted_at TIMESTAMP NULL
+		DEFAULT NULL;
+ALTER TABLE project
+	ADD COLUMN deleted_at TIMESTAMP NULL
+		DEFAULT NULL;
+ALTER TABLE activity
+	ADD COLUMN deleted_at TIMESTAMP NULL
+		DEFAULT NULL;
@@ -0,0 +1,6 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE client DROP COLUMN archived;
+ALTER TABLE project
+	DROP COLUMN archived,
+	DROP COLUMN start_date,
+	DROP COLUMN end_date;
@@ -0,0 +1,7 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE tracking
+	DROP CONSTRAINT tracking_approved_by_fk,
+	DROP COLUMN approved_at,
+	DROP COLUMN approved_by;
+
+DROP TABLE project_member;
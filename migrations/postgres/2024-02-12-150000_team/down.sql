@@ -0,0 +1,6 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE team_member;
+DROP TABLE team;
+ALTER TABLE "user"
+	DROP CONSTRAINT user_manager_id_fk,
+	DROP COLUMN manager_id;
@@ -0,0 +1,34 @@
+ALTER TABLE "user"
+	ADD COLUMN manager_id INTEGER,
+	ADD CONSTRAINT user_manager_id_fk FOREIGN KEY (manager_id)
+		REFERENCES "user"(id);
+
+CREATE TABLE team (
+	id SERIAL NOT NULL,
+	name VARCHAR(255) NOT NULL,
+	manager_id INTEGER,
+	created_at TIMESTAMP NOT NULL
+		DEFAULT CURRENT_TIMESTAMP,
+	updated_at TIMESTAMP NOT NULL
+		DEFAULT CURRENT_TIMESTAMP,
+	PRIMARY KEY (id),
+	UNIQUE (name),
+	FOREIGN KEY (manager_id)
+		REFERENCES "user"(id)
+);
+
+SELECT diesel_manage_updated_at('team');
+
+CREATE TABLE team_member (
+	id SERIAL NOT NULL,
+	team_id INTEGER NOT NULL,
+	user_id INTEGER NOT NULL,
+	created_at TIMESTAMP NOT NULL
+		DEFAULT CURRENT_TIMESTAMP,
+	PRIMARY KEY (id),
+	UNIQUE (team_id, user_id),
+	FOREIGN KEY (team_id)
+		REFERENCES team(id),
+	FOREIGN KEY (user_id)
+		REFERENCES "user"(id)
+);
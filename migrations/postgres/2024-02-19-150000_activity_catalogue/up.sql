@@ -0,0 +1,21 @@
+ALTER TABLE activity
+	ADD COLUMN billable BOOLEAN NOT NULL
+		DEFAULT TRUE,
+	ADD COLUMN category VARCHAR(50);
+
+UPDATE activity SET billable = FALSE, category = 'intern'
+	WHERE name IN ('privat', 'Pause');
+
+CREATE TABLE project_activity (
+	id SERIAL NOT NULL,
+	project_id INTEGER NOT NULL,
+	activity_id INTEGER NOT NULL,
+	created_at TIMESTAMP NOT NULL
+		DEFAULT CURRENT_TIMESTAMP,
+	PRIMARY KEY (id),
+	UNIQUE (project_id, activity_id),
+	FOREIGN KEY (project_id)
+		REFERENCES project(id),
+	FOREIGN KEY (activity_id)
+		REFERENCES activity(id)
+);
\ No newline at end of file
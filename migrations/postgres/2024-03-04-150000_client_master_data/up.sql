@@ -0,0 +1,30 @@
+ALTER TABLE client
+	ADD COLUMN customer_number VARCHAR(50),
+	ADD COLUMN billing_street VARCHAR(255),
+	ADD COLUMN billing_zip VARCHAR(20),
+	ADD COLUMN billing_city VARCHAR(255),
+	ADD COLUMN billing_country VARCHAR(255),
+	ADD COLUMN vat_id VARCHAR(50),
+	ADD COLUMN currency CHAR(3) NOT NULL
+		DEFAULT 'EUR',
+	ADD COLUMN payment_terms INTEGER NOT NULL
+		DEFAULT 30,
+	ADD CONSTRAINT client_customer_number_key UNIQUE (customer_number);
+
+CREATE TABLE client_contact (
+	id SERIAL NOT NULL,
+	client_id INTEGER NOT NULL,
+	name VARCHAR(255) NOT NULL,
+	email VARCHAR(255),
+	phone VARCHAR(50),
+	position VARCHAR(255),
+	created_at TIMESTAMP NOT NULL
+		DEFAULT CURRENT_TIMESTAMP,
+	updated_at TIMESTAMP NOT NULL
+		DEFAULT CURRENT_TIMESTAMP,
+	PRIMARY KEY (id),
+	FOREIGN KEY (client_id)
+		REFERENCES client(id)
+);
+
+SELECT diesel_manage_updated_at('client_contact');
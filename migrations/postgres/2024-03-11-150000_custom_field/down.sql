@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE custom_field_value;
+DROP TABLE custom_field;
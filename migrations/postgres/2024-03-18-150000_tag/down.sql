@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE tracking_to_tag;
+DROP TABLE tag;
@@ -0,0 +1,20 @@
+CREATE TABLE tag (
+	id SERIAL NOT NULL,
+	name VARCHAR(50) NOT NULL,
+	created_at TIMESTAMP NOT NULL
+		DEFAULT CURRENT_TIMESTAMP,
+	PRIMARY KEY (id),
+	UNIQUE (name)
+);
+
+CREATE TABLE tracking_to_tag (
+	id SERIAL NOT NULL,
+	tracking_id INTEGER NOT NULL,
+	tag_id INTEGER NOT NULL,
+	PRIMARY KEY (id),
+	UNIQUE (tracking_id, tag_id),
+	FOREIGN KEY (tracking_id)
+		REFERENCES tracking(id),
+	FOREIGN KEY (tag_id)
+		REFERENCES tag(id)
+);
\ No newline at end of file
@@ -0,0 +1,8 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE project_budget
+	ALTER COLUMN amount TYPE DOUBLE PRECISION,
+	ALTER COLUMN hourly_rate TYPE DOUBLE PRECISION,
+	ALTER COLUMN thresholds DROP DEFAULT,
+	ALTER COLUMN thresholds TYPE VARCHAR(255)
+		USING TRIM(BOTH '[]' FROM REPLACE(CAST(thresholds AS TEXT), ' ', '')),
+	ALTER COLUMN thresholds SET DEFAULT '80,100';
@@ -0,0 +1,8 @@
+-- money is stored exactly, the thresholds as a JSON array of percentages
+ALTER TABLE project_budget
+	ALTER COLUMN amount TYPE NUMERIC(12, 2),
+	ALTER COLUMN hourly_rate TYPE NUMERIC(12, 2),
+	ALTER COLUMN thresholds DROP DEFAULT,
+	ALTER COLUMN thresholds TYPE JSON
+		USING CAST('[' || thresholds || ']' AS JSON),
+	ALTER COLUMN thresholds SET DEFAULT '[80, 100]';
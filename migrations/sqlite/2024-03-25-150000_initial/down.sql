@@ -0,0 +1,18 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE audit_log;
+DROP TABLE custom_field_value;
+DROP TABLE custom_field;
+DROP TABLE tracking_to_tag;
+DROP TABLE tag;
+DROP TABLE tracking_to_activity;
+DROP TABLE tracking;
+DROP TABLE team_member;
+DROP TABLE team;
+DROP TABLE project_member;
+DROP TABLE project_budget;
+DROP TABLE project_activity;
+DROP TABLE activity;
+DROP TABLE project;
+DROP TABLE client_contact;
+DROP TABLE client;
+DROP TABLE "user";
@@ -0,0 +1,350 @@
+-- The schema of all migrations of the other backends up to this one at once, SQLite can't alter
+-- the constraints of a table. Decimals and JSON are stored as text.
+CREATE TABLE "user" (
+	id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+	username VARCHAR(50) NOT NULL,
+	firstname VARCHAR(40) NOT NULL,
+	lastname VARCHAR(40) NOT NULL,
+	email VARCHAR(255) NOT NULL,
+	hash VARCHAR(255) NOT NULL,
+	sys_role VARCHAR(255) NOT NULL
+		DEFAULT 'user',
+	created_at TIMESTAMP NOT NULL
+		DEFAULT CURRENT_TIMESTAMP,
+	updated_at TIMESTAMP NOT NULL
+		DEFAULT CURRENT_TIMESTAMP,
+	deleted_at TIMESTAMP NULL
+		DEFAULT NULL,
+	manager_id INTEGER,
+	UNIQUE (username),
+	FOREIGN KEY (manager_id)
+		REFERENCES "user"(id)
+);
+
+SELECT diesel_manage_updated_at('user');
+
+CREATE TABLE client (
+	id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+	name VARCHAR(255) NOT NULL,
+	created_at TIMESTAMP NOT NULL
+		DEFAULT CURRENT_TIMESTAMP,
+	updated_at TIMESTAMP NOT NULL
+		DEFAULT CURRENT_TIMESTAMP,
+	deleted_at TIMESTAMP NULL
+		DEFAULT NULL,
+	archived BOOLEAN NOT NULL
+		DEFAULT FALSE,
+	customer_number VARCHAR(50),
+	billing_street VARCHAR(255),
+	billing_zip VARCHAR(20),
+	billing_city VARCHAR(255),
+	billing_country VARCHAR(255),
+	vat_id VARCHAR(50),
+	currency CHAR(3) NOT NULL
+		DEFAULT 'EUR',
+	payment_terms INTEGER NOT NULL
+		DEFAULT 30,
+	UNIQUE (name),
+	UNIQUE (customer_number)
+);
+
+SELECT diesel_manage_updated_at('client');
+
+CREATE TABLE client_contact (
+	id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+	client_id INTEGER NOT NULL,
+	name VARCHAR(255) NOT NULL,
+	email VARCHAR(255),
+	phone VARCHAR(50),
+	position VARCHAR(255),
+	created_at TIMESTAMP NOT NULL
+		DEFAULT CURRENT_TIMESTAMP,
+	updated_at TIMESTAMP NOT NULL
+		DEFAULT CURRENT_TIMESTAMP,
+	FOREIGN KEY (client_id)
+		REFERENCES client(id)
+);
+
+SELECT diesel_manage_updated_at('client_contact');
+
+CREATE TABLE project (
+	id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+	client_id INTEGER NOT NULL,
+	name VARCHAR(255) NOT NULL,
+	created_at TIMESTAMP NOT NULL
+		DEFAULT CURRENT_TIMESTAMP,
+	updated_at TIMESTAMP NOT NULL
+		DEFAULT CURRENT_TIMESTAMP,
+	deleted_at TIMESTAMP NULL
+		DEFAULT NULL,
+	archived BOOLEAN NOT NULL
+		DEFAULT FALSE,
+	start_date DATE,
+	end_date DATE,
+	FOREIGN KEY (client_id)
+		REFERENCES client(id)
+);
+
+SELECT diesel_manage_updated_at('project');
+
+CREATE TABLE activity (
+	id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+	token VARCHAR(255),
+	name VARCHAR(255) NOT NULL,
+	created_at TIMESTAMP NOT NULL
+		DEFAULT CURRENT_TIMESTAMP,
+	updated_at TIMESTAMP NOT NULL
+		DEFAULT CURRENT_TIMESTAMP,
+	deleted_at TIMESTAMP NULL
+		DEFAULT NULL,
+	billable BOOLEAN NOT NULL
+		DEFAULT TRUE,
+	category VARCHAR(50),
+	UNIQUE (name),
+	UNIQUE (token)
+);
+
+SELECT diesel_manage_updated_at('activity');
+
+INSERT INTO activity (token, name) VALUES
+	('AE', 'Abstimmung per E-Mail'),
+	('AI', 'Abstimmung intern'),
+	('AP', 'Abstimmung persönlich'),
+	('AT', 'Abstimmung telefonisch'),
+	('CG', 'Coaching-Gespräch'),
+	('OE', 'Organisation-Entwicklung'),
+	('PS', 'Projektsteuerung'),
+	('VA', 'Vertriebsarbeit'),
+	('VP', 'Vertriebs-Planung'),
+	('VS', 'Vertriebs-Steuerung'),
+	('VT', 'Vertriebsarbeit telefonisch');
+INSERT INTO activity (name) VALUES
+	('Beratung'),
+	('Bewerbungsgespräch'),
+	('Briefing'),
+	('Daten bearbeiten'),
+	('Delegation'),
+	('Dokumentation'),
+	('executive sparring'),
+	('E-Mail-Bearbeitung'),
+	('Einarbeitung in Thema \ Kunde'),
+	('Fahrzeit'),
+	('Fotoprotokoll erstellen'),
+	('Geschäftsplanung'),
+	('Hospitation'),
+	('Insights Profil-Besprechung'),
+	('Interview/Einzelgespräch'),
+	('Konzeption'),
+	('Marketing-Planung'),
+	('Marketing-Steuerung'),
+	('Meeting'),
+	('Moderation'),
+	('Nachbereitung'),
+	('privat'),
+	('Pause'),
+	('Personal'),
+	('Planung/Organisation'),
+	('Projektplan'),
+	('Protokoll schreiben'),
+	('PR-Arbeit'),
+	('Recherche'),
+	('Reporting'),
+	('Social Media Kanäle pflegen'),
+	('Supervision'),
+	('Technik'),
+	('Telko - Telefonkonferenz'),
+	('Textarbeit'),
+	('Vorbereitung'),
+	('Wiedervorlagen bearbeitet'),
+	('Workshop');
+
+UPDATE activity SET billable = FALSE, category = 'intern'
+	WHERE name IN ('privat', 'Pause');
+
+CREATE TABLE project_activity (
+	id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+	project_id INTEGER NOT NULL,
+	activity_id INTEGER NOT NULL,
+	created_at TIMESTAMP NOT NULL
+		DEFAULT CURRENT_TIMESTAMP,
+	UNIQUE (project_id, activity_id),
+	FOREIGN KEY (project_id)
+		REFERENCES project(id),
+	FOREIGN KEY (activity_id)
+		REFERENCES activity(id)
+);
+
+CREATE TABLE project_budget (
+	id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+	project_id INTEGER NOT NULL,
+	activity_id INTEGER,
+	unit VARCHAR(10) NOT NULL
+		DEFAULT 'hours',
+	amount TEXT NOT NULL,
+	hourly_rate TEXT,
+	thresholds TEXT NOT NULL
+		DEFAULT '[80, 100]',
+	hard_stop BOOLEAN NOT NULL
+		DEFAULT FALSE,
+	created_at TIMESTAMP NOT NULL
+		DEFAULT CURRENT_TIMESTAMP,
+	updated_at TIMESTAMP NOT NULL
+		DEFAULT CURRENT_TIMESTAMP,
+	FOREIGN KEY (project_id)
+		REFERENCES project(id),
+	FOREIGN KEY (activity_id)
+		REFERENCES activity(id)
+);
+
+SELECT diesel_manage_updated_at('project_budget');
+
+CREATE TABLE project_member (
+	id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+	project_id INTEGER NOT NULL,
+	user_id INTEGER NOT NULL,
+	role VARCHAR(20) NOT NULL
+		DEFAULT 'member',
+	created_at TIMESTAMP NOT NULL
+		DEFAULT CURRENT_TIMESTAMP,
+	updated_at TIMESTAMP NOT NULL
+		DEFAULT CURRENT_TIMESTAMP,
+	UNIQUE (project_id, user_id),
+	FOREIGN KEY (project_id)
+		REFERENCES project(id),
+	FOREIGN KEY (user_id)
+		REFERENCES "user"(id)
+);
+
+SELECT diesel_manage_updated_at('project_member');
+
+CREATE TABLE team (
+	id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+	name VARCHAR(255) NOT NULL,
+	manager_id INTEGER,
+	created_at TIMESTAMP NOT NULL
+		DEFAULT CURRENT_TIMESTAMP,
+	updated_at TIMESTAMP NOT NULL
+		DEFAULT CURRENT_TIMESTAMP,
+	UNIQUE (name),
+	FOREIGN KEY (manager_id)
+		REFERENCES "user"(id)
+);
+
+SELECT diesel_manage_updated_at('team');
+
+CREATE TABLE team_member (
+	id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+	team_id INTEGER NOT NULL,
+	user_id INTEGER NOT NULL,
+	created_at TIMESTAMP NOT NULL
+		DEFAULT CURRENT_TIMESTAMP,
+	UNIQUE (team_id, user_id),
+	FOREIGN KEY (team_id)
+		REFERENCES team(id),
+	FOREIGN KEY (user_id)
+		REFERENCES "user"(id)
+);
+
+CREATE TABLE tracking (
+	id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+	client_id INTEGER NOT NULL,
+	user_id INTEGER NOT NULL,
+	project_id INTEGER NOT NULL,
+	date DATE NOT NULL,
+	begin TIME NOT NULL,
+	"end" TIME NOT NULL,
+	pause TIME,
+	performed REAL NOT NULL,
+	billed REAL NOT NULL,
+	description TEXT,
+	created_at TIMESTAMP NOT NULL
+		DEFAULT CURRENT_TIMESTAMP,
+	updated_at TIMESTAMP NOT NULL
+		DEFAULT CURRENT_TIMESTAMP,
+	approved_by INTEGER,
+	approved_at TIMESTAMP NULL,
+	FOREIGN KEY (client_id)
+		REFERENCES client(id),
+	FOREIGN KEY (user_id)
+		REFERENCES "user"(id),
+	FOREIGN KEY (project_id)
+		REFERENCES project(id),
+	FOREIGN KEY (approved_by)
+		REFERENCES "user"(id)
+);
+
+SELECT diesel_manage_updated_at('tracking');
+
+CREATE TABLE tracking_to_activity (
+	id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+	tracking_id INTEGER NOT NULL,
+	activity_id INTEGER NOT NULL,
+	FOREIGN KEY (tracking_id)
+		REFERENCES tracking(id),
+	FOREIGN KEY (activity_id)
+		REFERENCES activity(id)
+);
+
+CREATE TABLE tag (
+	id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+	name VARCHAR(50) NOT NULL,
+	created_at TIMESTAMP NOT NULL
+		DEFAULT CURRENT_TIMESTAMP,
+	UNIQUE (name)
+);
+
+CREATE TABLE tracking_to_tag (
+	id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+	tracking_id INTEGER NOT NULL,
+	tag_id INTEGER NOT NULL,
+	UNIQUE (tracking_id, tag_id),
+	FOREIGN KEY (tracking_id)
+		REFERENCES tracking(id),
+	FOREIGN KEY (tag_id)
+		REFERENCES tag(id)
+);
+
+CREATE TABLE custom_field (
+	id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+	entity VARCHAR(20) NOT NULL,
+	name VARCHAR(255) NOT NULL,
+	field_type VARCHAR(20) NOT NULL,
+	options TEXT NOT NULL,
+	required BOOLEAN NOT NULL
+		DEFAULT FALSE,
+	created_at TIMESTAMP NOT NULL
+		DEFAULT CURRENT_TIMESTAMP,
+	updated_at TIMESTAMP NOT NULL
+		DEFAULT CURRENT_TIMESTAMP,
+	UNIQUE (entity, name)
+);
+
+SELECT diesel_manage_updated_at('custom_field');
+
+CREATE TABLE custom_field_value (
+	id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+	custom_field_id INTEGER NOT NULL,
+	entity_id INTEGER NOT NULL,
+	value TEXT NOT NULL,
+	UNIQUE (custom_field_id, entity_id),
+	FOREIGN KEY (custom_field_id)
+		REFERENCES custom_field(id)
+);
+
+CREATE TABLE audit_log (
+	id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+	user_id INTEGER NOT NULL,
+	entity VARCHAR(50) NOT NULL,
+	entity_id INTEGER NOT NULL,
+	action VARCHAR(20) NOT NULL,
+	old_value TEXT,
+	new_value TEXT,
+	created_at TIMESTAMP NOT NULL
+		DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX audit_log_entity ON audit_log (entity, entity_id);
+CREATE INDEX audit_log_user_id ON audit_log (user_id);
+
+INSERT INTO "user"(username, firstname, lastname, email, hash, sys_role) VALUES
+	('admin', 'Adniel', 'Admin', 'admin@localhost', '$argon2id$v=19$m=4096,t=3,p=1$KO4tyOJKagkuD6BupAD0JQ$uavL0LtwpoxiwIK2qBtdS0b1sC/lS+QDk8cbs8PAQWQ', 'admin');